@@ -0,0 +1,236 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::services::prompt_merge::merge_documents;
+use crate::services::section_diff::{
+    compare_documents, has_differences, render_colored, render_unified,
+};
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
+/// Usage of the `diff` command
+pub const DIFF_USAGE: &str = "Uso: prompt_builder_gui diff <antigo> <novo> [--color | --unified]";
+
+/// Usage of the `merge` command
+pub const MERGE_USAGE: &str = "Uso: prompt_builder_gui merge <base> <nossa> <deles> [-o <saída>]";
+
+/// Output style of the `diff` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStyle {
+    /// ANSI colours, for terminals
+    Colored,
+    /// Plain text with `[-removido-]` and `{+adicionado+}` markers
+    Unified,
+}
+
+/// Runs the command given on the command line. Returns None when there is no
+/// command and the GUI should start, or the exit code of the command, as
+/// `diff` and `git merge-file` do: 0 without differences or conflicts, 1 with
+/// them and 2 on errors.
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, rest) = args.split_first()?;
+    let result = match command.as_str() {
+        "diff" => diff_command(rest, io::stdout().is_terminal()),
+        "merge" => merge_command(rest).map(|(output, conflicts)| {
+            if conflicts > 0 {
+                eprintln!("⚠️ {} seção(ões) em conflito", conflicts);
+            }
+            (output, conflicts > 0)
+        }),
+        _ => return None,
+    };
+
+    Some(match result {
+        Ok((output, found)) => {
+            if !output.is_empty() {
+                println!("{}", output);
+            }
+            i32::from(found)
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            2
+        }
+    })
+}
+
+/// Compares the two files named in `args`, section by section. Without a style
+/// option the output is coloured when `terminal` is true. Returns the rendered
+/// comparison and whether the files differ.
+pub fn diff_command(args: &[String], terminal: bool) -> Result<(String, bool)> {
+    let mut style = if terminal {
+        DiffStyle::Colored
+    } else {
+        DiffStyle::Unified
+    };
+    let mut files = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--color" => style = DiffStyle::Colored,
+            "--unified" => style = DiffStyle::Unified,
+            "-h" | "--help" => return Err(Error::validation(DIFF_USAGE)),
+            option if option.starts_with("--") => {
+                return Err(Error::validation(format!(
+                    "Opção desconhecida: {}\n{}",
+                    option, DIFF_USAGE
+                )))
+            }
+            file => files.push(file),
+        }
+    }
+    let [old_path, new_path] = files[..] else {
+        return Err(Error::validation(DIFF_USAGE));
+    };
+
+    let read = |path: &str| fs::read_to_string(path).with_path(Path::new(path));
+    let comparisons = compare_documents(&read(old_path)?, &read(new_path)?);
+    let output = match style {
+        DiffStyle::Colored => render_colored(&comparisons),
+        DiffStyle::Unified => render_unified(&comparisons),
+    };
+    Ok((output, has_differences(&comparisons)))
+}
+
+/// Three-way merge of the files named in `args`: base, ours and theirs.
+/// Conflicting sections get conflict markers. With `-o` the result is written
+/// to that file and nothing is returned to print, which makes the command
+/// usable as a Git merge driver (`merge %O %A %B -o %A`). Returns the text to
+/// print and the number of conflicts.
+pub fn merge_command(args: &[String]) -> Result<(String, usize)> {
+    let mut files = Vec::new();
+    let mut output_path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => match args.next() {
+                Some(path) => output_path = Some(path),
+                None => return Err(Error::validation(MERGE_USAGE)),
+            },
+            "-h" | "--help" => return Err(Error::validation(MERGE_USAGE)),
+            option if option.starts_with('-') => {
+                return Err(Error::validation(format!(
+                    "Opção desconhecida: {}\n{}",
+                    option, MERGE_USAGE
+                )))
+            }
+            file => files.push(file),
+        }
+    }
+    let [base, ours, theirs] = files[..] else {
+        return Err(Error::validation(MERGE_USAGE));
+    };
+
+    let read = |path: &str| fs::read_to_string(path).with_path(Path::new(path));
+    let result = merge_documents(&read(base)?, &read(ours)?, &read(theirs)?);
+    let mut document = result.to_document();
+    document.push('\n');
+
+    match output_path {
+        Some(path) => {
+            fs::write(path, document).with_path(Path::new(path))?;
+            Ok((String::new(), result.conflict_count()))
+        }
+        None => Ok((document.trim_end().to_string(), result.conflict_count())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn args(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn test_no_command_starts_gui() {
+        assert_eq!(run(&[]), None);
+        assert_eq!(run(&args(&["prompt.txt"])), None);
+    }
+
+    #[test]
+    fn test_diff_command_styles() {
+        let temp_dir = TempDir::new().unwrap();
+        let old = temp_dir.path().join("a.txt");
+        let new = temp_dir.path().join("b.txt");
+        fs::write(&old, "## Contexto\n\num revisor").unwrap();
+        fs::write(&new, "## Contexto\n\num autor").unwrap();
+        let (old, new) = (old.to_string_lossy(), new.to_string_lossy());
+
+        let (output, differs) = diff_command(&args(&[&old, &new, "--unified"]), true).unwrap();
+        assert!(differs);
+        assert_eq!(output, "## Contexto (modificada)\num [-revisor-]{+autor+}");
+
+        // Fora de um terminal a saída padrão não tem cores
+        let (output, _) = diff_command(&args(&[&old, &new]), false).unwrap();
+        assert!(!output.contains('\x1b'));
+        let (output, _) = diff_command(&args(&[&old, &new]), true).unwrap();
+        assert!(output.contains("\x1b[32mautor\x1b[0m"));
+
+        let (_, differs) = diff_command(&args(&[&old, &old]), false).unwrap();
+        assert!(!differs);
+    }
+
+    #[test]
+    fn test_diff_command_errors() {
+        assert_eq!(
+            diff_command(&args(&["a.txt"]), false)
+                .unwrap_err()
+                .to_string(),
+            DIFF_USAGE
+        );
+        assert!(diff_command(&args(&["a", "b", "--lado"]), false)
+            .unwrap_err()
+            .to_string()
+            .starts_with("Opção desconhecida: --lado"));
+        let error = diff_command(&args(&["/nao/existe/a", "/nao/existe/b"]), false).unwrap_err();
+        assert!(error.is_not_found());
+        assert!(error.to_string().starts_with("/nao/existe/a: "));
+    }
+
+    #[test]
+    fn test_merge_command_writes_output_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = |name: &str| temp_dir.path().join(name).to_string_lossy().to_string();
+        fs::write(path("base.txt"), "## Contexto\n\nA\n\n## Limitações\n\nB").unwrap();
+        fs::write(path("nossa.txt"), "## Contexto\n\nA2\n\n## Limitações\n\nB").unwrap();
+        fs::write(path("deles.txt"), "## Contexto\n\nA\n\n## Limitações\n\nB2").unwrap();
+
+        let (output, conflicts) = merge_command(&args(&[
+            &path("base.txt"),
+            &path("nossa.txt"),
+            &path("deles.txt"),
+        ]))
+        .unwrap();
+        assert_eq!(conflicts, 0);
+        assert_eq!(output, "## Contexto\n\nA2\n\n## Limitações\n\nB2");
+
+        // Como driver do Git o resultado substitui a nossa versão
+        fs::write(path("deles.txt"), "## Contexto\n\nA3\n\n## Limitações\n\nB").unwrap();
+        let (output, conflicts) = merge_command(&args(&[
+            &path("base.txt"),
+            &path("nossa.txt"),
+            &path("deles.txt"),
+            "-o",
+            &path("nossa.txt"),
+        ]))
+        .unwrap();
+        assert_eq!((output.as_str(), conflicts), ("", 1));
+        let merged = fs::read_to_string(path("nossa.txt")).unwrap();
+        assert!(merged.contains("<<<<<<< nossa\nA2\n||||||| base\nA\n=======\nA3\n>>>>>>> deles"));
+    }
+
+    #[test]
+    fn test_merge_command_needs_three_files() {
+        assert_eq!(
+            merge_command(&args(&["a", "b"])).unwrap_err().to_string(),
+            MERGE_USAGE
+        );
+        assert_eq!(
+            merge_command(&args(&["a", "b", "c", "-o"]))
+                .unwrap_err()
+                .to_string(),
+            MERGE_USAGE
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use crate::models::section::SectionKind;
+use crate::services::text_encoding::TextEncoding;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Result of the fallible functions of the library
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Where an error happened, as far as it is known: the file, the section of
+/// the prompt and the line of the file, counted from 1.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    pub path: Option<PathBuf>,
+    pub section: Option<SectionKind>,
+    pub line: Option<usize>,
+}
+
+impl ErrorContext {
+    /// Context naming only a file
+    pub fn path(path: &Path) -> Self {
+        Self {
+            path: Some(path.to_path_buf()),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.path.is_none() && self.section.is_none() && self.line.is_none()
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    /// Written as `arquivo.txt, seção Contexto, linha 3`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(path) = &self.path {
+            parts.push(path.display().to_string());
+        }
+        if let Some(section) = self.section {
+            parts.push(format!("seção {}", section.title()));
+        }
+        if let Some(line) = self.line {
+            parts.push(format!("linha {}", line));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Errors of the library, each with the context it happened in.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a file or folder, or running a program, failed
+    Io {
+        source: io::Error,
+        context: ErrorContext,
+    },
+    /// A file written by the application could not be understood
+    Parse {
+        message: String,
+        context: ErrorContext,
+    },
+    /// Text that cannot be read or written in an encoding
+    Encoding {
+        encoding: TextEncoding,
+        message: String,
+        context: ErrorContext,
+    },
+    /// A part of a section could not be rendered into the prompt, such as a
+    /// code reference to a file that cannot be read
+    Template {
+        message: String,
+        context: ErrorContext,
+    },
+    /// A value given by the user was rejected before any work was done
+    Validation {
+        message: String,
+        context: ErrorContext,
+    },
+    /// Writing the prompt in another form, such as a series, failed
+    Export {
+        source: Box<Error>,
+        context: ErrorContext,
+    },
+    /// The file changed on disk after it was read; writing it would lose
+    /// those changes
+    ChangedOnDisk { context: ErrorContext },
+}
+
+impl Error {
+    /// Failure to read or write `path`
+    pub fn io(source: io::Error, path: &Path) -> Self {
+        Error::Io {
+            source,
+            context: ErrorContext::path(path),
+        }
+    }
+
+    pub fn parse(message: impl Into<String>) -> Self {
+        Error::Parse {
+            message: message.into(),
+            context: ErrorContext::default(),
+        }
+    }
+
+    pub fn encoding(encoding: TextEncoding, message: impl Into<String>) -> Self {
+        Error::Encoding {
+            encoding,
+            message: message.into(),
+            context: ErrorContext::default(),
+        }
+    }
+
+    pub fn template(message: impl Into<String>) -> Self {
+        Error::Template {
+            message: message.into(),
+            context: ErrorContext::default(),
+        }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Error::Validation {
+            message: message.into(),
+            context: ErrorContext::default(),
+        }
+    }
+
+    /// Failure to export to `path`, caused by `source`
+    pub fn export(source: Error, path: &Path) -> Self {
+        Error::Export {
+            source: Box::new(source),
+            context: ErrorContext::path(path),
+        }
+    }
+
+    pub fn changed_on_disk(path: &Path) -> Self {
+        Error::ChangedOnDisk {
+            context: ErrorContext::path(path),
+        }
+    }
+
+    pub fn context(&self) -> &ErrorContext {
+        match self {
+            Error::Io { context, .. }
+            | Error::Parse { context, .. }
+            | Error::Encoding { context, .. }
+            | Error::Template { context, .. }
+            | Error::Validation { context, .. }
+            | Error::Export { context, .. }
+            | Error::ChangedOnDisk { context } => context,
+        }
+    }
+
+    fn context_mut(&mut self) -> &mut ErrorContext {
+        match self {
+            Error::Io { context, .. }
+            | Error::Parse { context, .. }
+            | Error::Encoding { context, .. }
+            | Error::Template { context, .. }
+            | Error::Validation { context, .. }
+            | Error::Export { context, .. }
+            | Error::ChangedOnDisk { context } => context,
+        }
+    }
+
+    /// Names the file of the error, unless it already names one
+    pub fn with_path(mut self, path: &Path) -> Self {
+        let context = self.context_mut();
+        if context.path.is_none() {
+            context.path = Some(path.to_path_buf());
+        }
+        self
+    }
+
+    /// Names the section of the error, unless it already names one
+    pub fn with_section(mut self, section: SectionKind) -> Self {
+        self.context_mut().section.get_or_insert(section);
+        self
+    }
+
+    /// Names the line of the error, unless it already names one
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.context_mut().line.get_or_insert(line);
+        self
+    }
+
+    /// Kind of the underlying IO error, also through an export error
+    pub fn io_kind(&self) -> Option<io::ErrorKind> {
+        match self {
+            Error::Io { source, .. } => Some(source.kind()),
+            Error::Export { source, .. } => source.io_kind(),
+            _ => None,
+        }
+    }
+
+    /// Whether the error is a file or folder that does not exist
+    pub fn is_not_found(&self) -> bool {
+        self.io_kind() == Some(io::ErrorKind::NotFound)
+    }
+}
+
+impl fmt::Display for Error {
+    /// Message shown to the user, preceded by its context, as in
+    /// `prompt.txt, linha 3: ...`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let context = self.context();
+        if !context.is_empty() {
+            write!(f, "{}: ", context)?;
+        }
+        match self {
+            Error::Io { source, .. } => write!(f, "{}", source),
+            Error::Parse { message, .. } => write!(f, "formato inválido: {}", message),
+            Error::Encoding {
+                encoding, message, ..
+            } => write!(f, "{} ({})", message, encoding.label()),
+            Error::Template { message, .. } | Error::Validation { message, .. } => {
+                write!(f, "{}", message)
+            }
+            Error::Export { source, .. } => write!(f, "falha ao exportar: {}", source),
+            Error::ChangedOnDisk { .. } => {
+                write!(f, "o arquivo foi alterado no disco depois de aberto")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Export { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Names the file of the errors of IO results, as in
+/// `fs::read_to_string(&path).with_path(&path)?`
+pub trait IoResultExt<T> {
+    fn with_path(self, path: &Path) -> Result<T>;
+}
+
+impl<T> IoResultExt<T> for io::Result<T> {
+    fn with_path(self, path: &Path) -> Result<T> {
+        self.map_err(|e| Error::io(e, path))
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self {
+        Error::Io {
+            source,
+            context: ErrorContext::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_starts_with_the_context() {
+        let error = Error::parse("campo ausente")
+            .with_path(Path::new("historico/log.txt"))
+            .with_line(3);
+        assert_eq!(
+            error.to_string(),
+            "historico/log.txt, linha 3: formato inválido: campo ausente"
+        );
+
+        let error = Error::template("arquivo ilegível").with_section(SectionKind::Refactoring);
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "seção {}: arquivo ilegível",
+                SectionKind::Refactoring.title()
+            )
+        );
+        assert_eq!(
+            Error::validation("informe um nome").to_string(),
+            "informe um nome"
+        );
+    }
+
+    #[test]
+    fn test_context_already_known_is_kept() {
+        let error = Error::io(
+            io::Error::new(io::ErrorKind::NotFound, "não existe"),
+            Path::new("a.txt"),
+        )
+        .with_path(Path::new("b.txt"));
+
+        assert_eq!(error.context().path.as_deref(), Some(Path::new("a.txt")));
+        assert!(error.is_not_found());
+
+        let export = Error::export(error, Path::new("serie.jsonl"));
+        assert!(export.is_not_found());
+        assert_eq!(
+            export.to_string(),
+            "serie.jsonl: falha ao exportar: a.txt: não existe"
+        );
+    }
+}
@@ -5,5 +5,7 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+pub mod cli;
+pub mod error;
 pub mod models;
 pub mod services;
@@ -1,514 +1,3486 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use copypasta::{ClipboardContext, ClipboardProvider};
-use rfd::FileDialog;
-use std::cell::RefCell;
-use std::error::Error;
+use rfd::{FileDialog, MessageButtons, MessageDialog, MessageDialogResult, MessageLevel};
+use slint::{CloseRequestResponse, Model, ModelRc, SharedString, Timer, TimerMode, VecModel};
+use std::cell::{Cell, RefCell};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::time::Duration;
 
-mod models;
-mod services;
+use prompt_builder_gui::cli;
+use prompt_builder_gui::error::{self, Error};
+use prompt_builder_gui::models::{
+    attachment::{Attachment, AttachmentKind},
+    auxiliary_content::AuxiliaryContent,
+    budget::{BudgetPolicy, TruncationStrategy},
+    context::Context,
+    few_shot::FewShot,
+    guidance::Guidance,
+    language::Language,
+    limitations::Limitations,
+    main_content::MainContent,
+    metadata::{format_timestamp, now_seconds, DocumentMetadata},
+    output_format::OutputFormat,
+    refactoring::{parse_line_range, CodeReference, Refactoring, RefactoringGoal},
+    section::SectionKind,
+    tests::{TestCase, TestKind, Tests, KNOWN_FRAMEWORKS},
+};
+use prompt_builder_gui::services::{
+    attachment_reader::read_attachments,
+    budget::apply_budget,
+    compactor::{compact_prompt, format_dictionary, parse_dictionary, CompactOptions},
+    file_service::{save_prompt_with, FileStamp, SaveOptions},
+    git_ingest::{self, GitSource},
+    heading_aliases::HeadingAliases,
+    history::History,
+    i18n,
+    import_review::ImportReview,
+    prompt_generator::PromptData,
+    prompt_library::{parse_tags, LibraryQuery, PromptLibrary, PROMPT_EXTENSIONS},
+    prompt_merge::{merge_documents, MergeResult, SectionMerge},
+    prompt_parser::{parse_prompt, parse_prompt_with},
+    prompt_series::{build_series, PromptSeries, SeriesOptions},
+    recent_files::RecentFiles,
+    recovery::{Autosave, RecoveryStore},
+    repo_map::{build_repo_map, RepoMapOptions},
+    section_classifier::{ClassifierStore, SectionClassifier},
+    section_diff::{compare_documents, render_unified, DiffSide},
+    settings::{
+        LineEndingPolicy, Renderer, Settings, SettingsStore, Theme, AUTOSAVE_RANGE,
+        FONT_SIZE_RANGE, MAX_BACKUP_COUNT,
+    },
+    source_reader::check_code_references,
+    tabs::{Closed, Session, SessionStore, SessionTab, Tabs},
+    text_encoding::{read_text_file, LineEnding, TextEncoding, TextFormat},
+    token_counter::Tokenizer,
+    undo::{UndoHistory, DEFAULT_UNDO_LIMIT},
+    workspace::Workspace,
+};
+
+slint::include_modules!();
+
+// Convert a Slint string model into a Vec<String>
+fn model_to_strings(model: &ModelRc<SharedString>) -> Vec<String> {
+    model.iter().map(|item| item.to_string()).collect()
+}
+
+// Build a Slint string model from a list of strings
+fn strings_to_model(items: Vec<String>) -> ModelRc<SharedString> {
+    let items: Vec<SharedString> = items.into_iter().map(SharedString::from).collect();
+    ModelRc::new(VecModel::from(items))
+}
+
+// Collect the refactoring section (text, attached files and goals) from the UI
+fn refactoring_from_ui(ui: &AppWindow) -> Refactoring {
+    let mut refactoring = Refactoring::new();
+    refactoring.text = ui.get_refactoring_text().to_string();
+    refactoring.files = model_to_strings(&ui.get_refactoring_files())
+        .iter()
+        .filter_map(|label| CodeReference::parse(label))
+        .collect();
+
+    let checked = [
+        ui.get_refactoring_goal_solid(),
+        ui.get_refactoring_goal_performance(),
+        ui.get_refactoring_goal_readability(),
+        ui.get_refactoring_goal_error_handling(),
+    ];
+    refactoring.goals = RefactoringGoal::ALL
+        .iter()
+        .zip(checked)
+        .filter(|(_, checked)| *checked)
+        .map(|(goal, _)| *goal)
+        .collect();
+
+    refactoring
+}
+
+// Show a refactoring section (text, attached files and goals) in the UI
+fn apply_refactoring_to_ui(refactoring: &Refactoring, ui: &AppWindow) {
+    ui.set_refactoring_text(refactoring.text.clone().into());
+    ui.set_refactoring_files(strings_to_model(
+        refactoring
+            .files
+            .iter()
+            .map(|file| file.to_string())
+            .collect(),
+    ));
+    ui.set_refactoring_goal_solid(refactoring.goals.contains(&RefactoringGoal::Solid));
+    ui.set_refactoring_goal_performance(refactoring.goals.contains(&RefactoringGoal::Performance));
+    ui.set_refactoring_goal_readability(refactoring.goals.contains(&RefactoringGoal::Readability));
+    ui.set_refactoring_goal_error_handling(
+        refactoring.goals.contains(&RefactoringGoal::ErrorHandling),
+    );
+}
+
+// Reset the refactoring attachments and goal checklist in the UI
+fn clear_refactoring_extras(ui: &AppWindow) {
+    ui.set_refactoring_files(strings_to_model(Vec::new()));
+    ui.set_refactoring_line_range("".into());
+    ui.set_refactoring_goal_solid(false);
+    ui.set_refactoring_goal_performance(false);
+    ui.set_refactoring_goal_readability(false);
+    ui.set_refactoring_goal_error_handling(false);
+}
+
+// Convert an attachment into the row shown in the UI list
+fn attachment_to_row(attachment: &Attachment) -> AttachmentRow {
+    AttachmentRow {
+        kind: attachment.kind.as_str().into(),
+        path: attachment.path.to_string_lossy().to_string().into(),
+        include: attachment.include.join(", ").into(),
+        exclude: attachment.exclude.join(", ").into(),
+        gitignore: attachment.respect_gitignore,
+        label: attachment.to_string().into(),
+    }
+}
+
+// Read the attachments listed in the UI
+fn attachments_from_ui(ui: &AppWindow) -> Vec<Attachment> {
+    ui.get_attachments()
+        .iter()
+        .filter_map(|row| {
+            let kind = AttachmentKind::parse(&row.kind)?;
+            let mut attachment = Attachment::new(row.path.as_str(), kind);
+            attachment.include = Attachment::parse_patterns(&row.include);
+            attachment.exclude = Attachment::parse_patterns(&row.exclude);
+            attachment.respect_gitignore = row.gitignore;
+            Some(attachment)
+        })
+        .collect()
+}
+
+// Append attachments to the UI list, applying the include/exclude options from the inputs
+fn add_attachments(ui: &AppWindow, paths: Vec<PathBuf>, glob: bool) {
+    let mut rows: Vec<AttachmentRow> = ui.get_attachments().iter().collect();
+    for path in paths {
+        let kind = if glob {
+            AttachmentKind::Glob
+        } else if path.is_dir() {
+            AttachmentKind::Directory
+        } else {
+            AttachmentKind::File
+        };
+        let mut attachment = Attachment::new(path, kind);
+        if kind != AttachmentKind::File {
+            attachment.include = Attachment::parse_patterns(&ui.get_attachment_include());
+            attachment.exclude = Attachment::parse_patterns(&ui.get_attachment_exclude());
+            attachment.respect_gitignore = ui.get_attachment_gitignore();
+        }
+        println!("📎 Anexo adicionado: {}", attachment);
+        rows.push(attachment_to_row(&attachment));
+    }
+    ui.set_attachments(ModelRc::new(VecModel::from(rows)));
+}
+
+// Show an auxiliary content section (text and attachment list) in the UI
+fn apply_auxiliary_to_ui(auxiliary: &AuxiliaryContent, ui: &AppWindow) {
+    ui.set_auxiliary_content_text(auxiliary.data.clone().into());
+    let rows: Vec<AttachmentRow> = auxiliary
+        .attachments
+        .iter()
+        .map(attachment_to_row)
+        .collect();
+    ui.set_attachments(ModelRc::new(VecModel::from(rows)));
+}
+
+// Reset the attachment list and options in the UI
+fn clear_attachments(ui: &AppWindow) {
+    ui.set_attachments(ModelRc::new(VecModel::from(Vec::<AttachmentRow>::new())));
+    ui.set_attachment_glob("".into());
+    ui.set_attachment_include("".into());
+    ui.set_attachment_exclude("".into());
+    ui.set_attachment_gitignore(true);
+    ui.set_attachments_summary("".into());
+}
+
+// Files dropped on the window are attached to the Auxiliary Content.
+// winit does not report where the drop happened, so the whole window is the drop target.
+#[cfg(feature = "drag-and-drop")]
+fn enable_file_drop(ui: &AppWindow) {
+    use slint::winit_030::{
+        winit::event::WindowEvent, WinitWindowAccessor, WinitWindowEventResult,
+    };
+
+    ui.set_file_drop_enabled(true);
+    let ui_weak = ui.as_weak();
+    ui.window().on_winit_window_event(move |_window, event| {
+        if let WindowEvent::DroppedFile(path) = event {
+            if let Some(ui) = ui_weak.upgrade() {
+                println!("📥 Arquivo solto na janela: {:?}", path);
+                add_attachments(&ui, vec![path.clone()], false);
+            }
+            return WinitWindowEventResult::PreventDefault;
+        }
+        WinitWindowEventResult::Propagate
+    });
+}
+
+// Collect the tests section (text, framework, kinds, coverage and cases) from the UI
+fn tests_from_ui(ui: &AppWindow) -> Tests {
+    let mut tests = Tests::new();
+    tests.text = ui.get_tests_text().to_string();
+    tests.framework = ui.get_tests_framework().to_string();
+
+    let checked = [
+        ui.get_tests_kind_unit(),
+        ui.get_tests_kind_integration(),
+        ui.get_tests_kind_property(),
+    ];
+    tests.kinds = TestKind::ALL
+        .iter()
+        .zip(checked)
+        .filter(|(_, checked)| *checked)
+        .map(|(kind, _)| *kind)
+        .collect();
+
+    let coverage = ui.get_tests_coverage();
+    tests.coverage_target = if coverage > 0 {
+        Some(coverage.min(100) as u8)
+    } else {
+        None
+    };
+
+    tests.cases = ui
+        .get_tests_cases()
+        .iter()
+        .map(|row| TestCase {
+            name: row.name.to_string(),
+            given: row.given.to_string(),
+            when: row.when.to_string(),
+            then: row.then.to_string(),
+        })
+        .collect();
+
+    tests
+}
+
+// Show a structured tests section in the dedicated UI controls
+fn apply_tests_to_ui(tests: &Tests, ui: &AppWindow) {
+    ui.set_tests_text(tests.text.clone().into());
+    ui.set_tests_framework(tests.framework.clone().into());
+    ui.set_tests_kind_unit(tests.kinds.contains(&TestKind::Unit));
+    ui.set_tests_kind_integration(tests.kinds.contains(&TestKind::Integration));
+    ui.set_tests_kind_property(tests.kinds.contains(&TestKind::Property));
+    ui.set_tests_coverage(tests.coverage_target.map(i32::from).unwrap_or(0));
+
+    let rows: Vec<TestCaseRow> = tests
+        .cases
+        .iter()
+        .map(|case| TestCaseRow {
+            name: case.name.clone().into(),
+            given: case.given.clone().into(),
+            when: case.when.clone().into(),
+            then: case.then.clone().into(),
+        })
+        .collect();
+    ui.set_tests_cases(ModelRc::new(VecModel::from(rows)));
+}
+
+// Reset the structured tests controls in the UI
+fn clear_tests_extras(ui: &AppWindow) {
+    apply_tests_to_ui(&Tests::new(), ui);
+    ui.set_tests_case_name("".into());
+    ui.set_tests_case_given("".into());
+    ui.set_tests_case_when("".into());
+    ui.set_tests_case_then("".into());
+}
+
+// Read the free text of a section from the UI
+fn section_text(ui: &AppWindow, kind: SectionKind) -> SharedString {
+    match kind {
+        SectionKind::FewShot => ui.get_few_shot_text(),
+        SectionKind::Context => ui.get_context_text(),
+        SectionKind::MainContent => ui.get_main_content_text(),
+        SectionKind::AuxiliaryContent => ui.get_auxiliary_content_text(),
+        SectionKind::Limitations => ui.get_limitations_text(),
+        SectionKind::Refactoring => ui.get_refactoring_text(),
+        SectionKind::Guidance => ui.get_guidance_text(),
+        SectionKind::Tests => ui.get_tests_text(),
+        SectionKind::OutputFormat => ui.get_output_format_text(),
+    }
+}
+
+// Append text to the free text of a section in the UI, separated by a blank line
+fn append_to_section(ui: &AppWindow, kind: SectionKind, text: &str) {
+    let current = section_text(ui, kind);
+    let combined: SharedString = if current.trim().is_empty() {
+        text.into()
+    } else {
+        format!("{}\n\n{}", current.trim_end(), text).into()
+    };
+
+    set_section_text(ui, kind, combined);
+}
+
+// Replace the free text of a section in the UI
+fn set_section_text(ui: &AppWindow, kind: SectionKind, text: SharedString) {
+    match kind {
+        SectionKind::FewShot => ui.set_few_shot_text(text),
+        SectionKind::Context => ui.set_context_text(text),
+        SectionKind::MainContent => ui.set_main_content_text(text),
+        SectionKind::AuxiliaryContent => ui.set_auxiliary_content_text(text),
+        SectionKind::Limitations => ui.set_limitations_text(text),
+        SectionKind::Refactoring => ui.set_refactoring_text(text),
+        SectionKind::Guidance => ui.set_guidance_text(text),
+        SectionKind::Tests => ui.set_tests_text(text),
+        SectionKind::OutputFormat => ui.set_output_format_text(text),
+    }
+}
+
+// Build the Git source selected in the UI
+fn git_source_from_ui(ui: &AppWindow) -> GitSource {
+    match ui.get_git_source() {
+        1 => GitSource::UnstagedDiff,
+        2 => GitSource::RefDiff {
+            from: ui.get_git_ref_from().trim().to_string(),
+            to: ui.get_git_ref_to().trim().to_string(),
+        },
+        3 => GitSource::RecentCommits {
+            count: ui.get_git_commit_count().max(1) as usize,
+        },
+        4 => GitSource::ChangedFiles,
+        _ => GitSource::StagedDiff,
+    }
+}
+
+// Build the prompt series from the generated prompt and the series options in the UI
+fn series_from_ui(ui: &AppWindow, data: &PromptData) -> PromptSeries {
+    let options = SeriesOptions {
+        chunk_tokens: ui.get_series_chunk_tokens().max(1) as usize,
+        combine_prompt: ui.get_series_combine(),
+    };
+    build_series(data, &options)
+}
+
+// List the recent files in the File menu
+fn show_recent_files(ui: &AppWindow, recent: &RecentFiles) {
+    let rows: Vec<RecentFileRow> = recent
+        .entries()
+        .iter()
+        .map(|entry| RecentFileRow {
+            path: entry.path.display().to_string().into(),
+            name: entry.name().into(),
+            folder: entry
+                .path
+                .parent()
+                .map(|folder| folder.display().to_string())
+                .unwrap_or_default()
+                .into(),
+            pinned: entry.pinned,
+        })
+        .collect();
+    ui.set_recent_files(ModelRc::new(VecModel::from(rows)));
+}
+
+// The prompt as it is previewed and copied: compacted first, so that the
+// budget only cuts what is still needed
+fn prompt_output(ui: &AppWindow, data: &PromptData, tokenizer: Tokenizer) -> PromptData {
+    budgeted_output(ui, &compacted_output(ui, data), tokenizer)
+}
+
+// Apply the compact mode of the UI, showing the before/after comparison
+fn compacted_output(ui: &AppWindow, data: &PromptData) -> PromptData {
+    if !ui.get_compact_enabled() {
+        ui.set_compact_report("".into());
+        return data.clone();
+    }
+
+    let options = CompactOptions {
+        collapse_whitespace: ui.get_compact_whitespace(),
+        dedupe_lines: ui.get_compact_dedupe(),
+        dictionary: parse_dictionary(&ui.get_compact_dictionary()),
+    };
+    let (compacted, report) = compact_prompt(data, &options);
+    println!("🗜️ {}", report.summary());
+    ui.set_compact_report(report.summary().into());
+    compacted
+}
+
+// Apply the token budget of the UI, counting tokens with the tokenizer of the
+// settings and showing what was cut; without a target the prompt is used as it is
+fn budgeted_output(ui: &AppWindow, data: &PromptData, tokenizer: Tokenizer) -> PromptData {
+    let target_tokens = ui.get_budget_target().max(0) as usize;
+    if target_tokens == 0 {
+        ui.set_budget_report("".into());
+        return data.clone();
+    }
+
+    let mut policy = BudgetPolicy::new(target_tokens);
+    if let (Some(strategy), Some(budget)) = (
+        TruncationStrategy::ALL.get(ui.get_budget_auxiliary_strategy().max(0) as usize),
+        policy.sections.get_mut(&SectionKind::AuxiliaryContent),
+    ) {
+        budget.strategy = *strategy;
+    }
+
+    let (budgeted, report) = apply_budget(data, &policy, tokenizer);
+    println!("📏 {}", report.summary());
+    ui.set_budget_report(report.summary().into());
+    budgeted
+}
+
+// Report an error in the status line of the window, until it is dismissed
+fn show_error(ui: &AppWindow, message: &str) {
+    eprintln!("❌ {}", message);
+    ui.set_error_message(message.into());
+}
+
+// Save the recent files after a change and show them in the File menu
+fn store_recent_files(ui: &AppWindow, recent: &RecentFiles) {
+    if let Err(e) = recent.save() {
+        show_error(ui, &format!("Erro ao salvar arquivos recentes: {}", e));
+    }
+    show_recent_files(ui, recent);
+}
+
+// Show the workspace tree, filtered by the text typed in the sidebar
+fn show_workspace(ui: &AppWindow, workspace: &Workspace) {
+    let rows = workspace.rows(&ui.get_workspace_filter());
+    let files = rows.iter().filter(|row| !row.node.is_dir).count();
+    ui.set_workspace_status(if files == 0 {
+        "Nenhum arquivo de prompt encontrado".into()
+    } else {
+        format!("{} arquivo(s)", files).into()
+    });
+    let rows: Vec<WorkspaceRow> = rows
+        .iter()
+        .map(|row| WorkspaceRow {
+            path: row.node.path.display().to_string().into(),
+            name: row.node.name().into(),
+            depth: row.node.depth as i32,
+            folder: row.node.is_dir,
+            expanded: row.expanded,
+        })
+        .collect();
+    ui.set_workspace_rows(ModelRc::new(VecModel::from(rows)));
+}
+
+// List the library prompts matching the search filters in the UI
+fn show_library_results(ui: &AppWindow, library: &PromptLibrary) {
+    let selected_tag = ui
+        .get_library_tag_options()
+        .row_data(ui.get_library_tag_index().max(0) as usize)
+        .filter(|_| ui.get_library_tag_index() > 0)
+        .map(|tag| tag.to_string());
+
+    let tags = library.all_tags();
+    let tag_index = selected_tag
+        .as_ref()
+        .and_then(|selected| tags.iter().position(|tag| tag == selected))
+        .map(|position| position as i32 + 1)
+        .unwrap_or(0);
+    ui.set_library_tag_options(strings_to_model(
+        std::iter::once("Todas as tags".to_string())
+            .chain(tags.iter().cloned())
+            .collect(),
+    ));
+    ui.set_library_tag_index(tag_index);
+
+    let query = LibraryQuery {
+        text: ui.get_library_query().to_string(),
+        tags: selected_tag.filter(|_| tag_index > 0).into_iter().collect(),
+        favorites_only: ui.get_library_favorites_only(),
+    };
+    let rows: Vec<LibraryRow> = library
+        .search(&query)
+        .into_iter()
+        .map(|entry| LibraryRow {
+            file: entry.file.clone().into(),
+            title: entry.title.clone().into(),
+            details: entry.details().into(),
+            favorite: entry.favorite,
+        })
+        .collect();
+
+    ui.set_library_status(
+        format!(
+            "{} de {} prompt(s) em {}",
+            rows.len(),
+            library.entries().len(),
+            library.root().display()
+        )
+        .into(),
+    );
+    ui.set_library_results(ModelRc::new(VecModel::from(rows)));
+}
+
+// Collect the document metadata from the metadata panel
+fn metadata_from_ui(ui: &AppWindow) -> DocumentMetadata {
+    let temperature = ui
+        .get_metadata_temperature()
+        .trim()
+        .replace(',', ".")
+        .parse::<f32>()
+        .ok();
+
+    DocumentMetadata {
+        title: ui.get_metadata_title().trim().to_string(),
+        description: ui.get_metadata_description().trim().to_string(),
+        author: ui.get_metadata_author().trim().to_string(),
+        tags: parse_tags(&ui.get_metadata_tags()),
+        created: ui.get_metadata_created().parse().ok(),
+        modified: ui.get_metadata_modified().parse().ok(),
+        model: ui.get_metadata_model().trim().to_string(),
+        temperature,
+        max_tokens: u32::try_from(ui.get_metadata_max_tokens())
+            .ok()
+            .filter(|tokens| *tokens > 0),
+        stop_sequences: ui
+            .get_metadata_stop()
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.to_string())
+            .collect(),
+        extra: DocumentMetadata::parse_extra(&ui.get_metadata_extra()),
+    }
+}
+
+// Show the document metadata in the metadata panel
+fn apply_metadata_to_ui(metadata: &DocumentMetadata, ui: &AppWindow) {
+    ui.set_metadata_title(metadata.title.clone().into());
+    ui.set_metadata_description(metadata.description.clone().into());
+    ui.set_metadata_author(metadata.author.clone().into());
+    ui.set_metadata_tags(metadata.tags.join(", ").into());
+    ui.set_metadata_model(metadata.model.clone().into());
+    ui.set_metadata_temperature(
+        metadata
+            .temperature
+            .map(|temperature| temperature.to_string())
+            .unwrap_or_default()
+            .into(),
+    );
+    ui.set_metadata_max_tokens(metadata.max_tokens.map(|tokens| tokens as i32).unwrap_or(0));
+    ui.set_metadata_stop(metadata.stop_sequences.join("\n").into());
+    ui.set_metadata_extra(metadata.format_extra().into());
+
+    let seconds = |time: Option<u64>| time.map(|t| t.to_string()).unwrap_or_default();
+    ui.set_metadata_created(seconds(metadata.created).into());
+    ui.set_metadata_modified(seconds(metadata.modified).into());
+
+    let mut dates = Vec::new();
+    if let Some(created) = metadata.created {
+        dates.push(format!("Criado em {}", format_timestamp(created)));
+    }
+    if let Some(modified) = metadata.modified {
+        dates.push(format!("modificado em {}", format_timestamp(modified)));
+    }
+    ui.set_metadata_dates(dates.join(", ").into());
+}
+
+// List the revisions of a document in the history panel
+fn show_history(ui: &AppWindow, document: &Path) {
+    let revisions = match History::for_document(document).revisions() {
+        Ok(revisions) => revisions,
+        Err(e) => {
+            show_error(ui, &format!("Erro ao ler histórico: {}", e));
+            Vec::new()
+        }
+    };
+
+    let count = revisions.len() as i32;
+    ui.set_history_document(document.display().to_string().into());
+    ui.set_history_revisions(strings_to_model(
+        revisions.iter().map(|revision| revision.label()).collect(),
+    ));
+    ui.set_history_from((count - 2).max(0));
+    ui.set_history_to((count - 1).max(0));
+    ui.set_history_diff("".into());
+}
+
+// Record the saved document as a new revision, using the note typed in the history panel
+fn record_revision(ui: &AppWindow, document: &Path, data: &PromptData) {
+    let history = History::for_document(document);
+    match history.record(
+        &data.build_document(),
+        ui.get_history_note().trim(),
+        now_seconds(),
+    ) {
+        Ok(Some(revision)) => {
+            println!("🕓 Revisão #{} registrada", revision.number);
+            ui.set_history_note("".into());
+        }
+        Ok(None) => println!("🕓 Documento sem alterações desde a última revisão"),
+        Err(e) => show_error(ui, &format!("Erro ao registrar revisão: {}", e)),
+    }
+    show_history(ui, document);
+}
+
+// Text written by Save: prompt files (.txt, .md) get the native document, so
+// the metadata and sections come back when reopened; other files are written
+// with the renderer of the settings
+fn saved_text(settings: &Settings, data: &PromptData, path: &Path) -> String {
+    let is_prompt_file = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| PROMPT_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+    if is_prompt_file {
+        data.build_document_in(settings.output_language)
+    } else {
+        settings.renderer.render(data, settings.output_language)
+    }
+}
+
+// Show a parsed prompt in the editor fields
+fn apply_prompt_to_ui(data: &PromptData, ui: &AppWindow) {
+    for kind in SectionKind::ALL {
+        match kind {
+            SectionKind::AuxiliaryContent => {
+                if let Some(auxiliary) = &data.auxiliary_content {
+                    apply_auxiliary_to_ui(auxiliary, ui);
+                }
+            }
+            SectionKind::Refactoring => {
+                if let Some(refactoring) = &data.refactoring {
+                    apply_refactoring_to_ui(refactoring, ui);
+                }
+            }
+            SectionKind::Tests => {
+                if let Some(tests) = &data.tests {
+                    apply_tests_to_ui(tests, ui);
+                }
+            }
+            _ => {
+                if let Some(body) = data.section_body(kind) {
+                    set_section_text(ui, kind, body.into());
+                }
+            }
+        }
+    }
+}
+
+// Replace the editor contents with a parsed prompt and refresh the preview
+fn load_prompt_into_editor(prompt: PromptData, ui: &AppWindow, data: &mut PromptData) {
+    for kind in SectionKind::ALL {
+        set_section_text(ui, kind, "".into());
+    }
+    clear_refactoring_extras(ui);
+    clear_tests_extras(ui);
+    clear_attachments(ui);
+
+    *data = prompt;
+    apply_prompt_to_ui(data, ui);
+    apply_metadata_to_ui(&data.metadata, ui);
+    ui.set_preview_text(data.build_preview_prompt_in(output_language(ui)).into());
+}
+
+// Language of the section headings in the preview
+fn output_language(ui: &AppWindow) -> Language {
+    Language::ALL
+        .get(ui.get_output_language() as usize)
+        .copied()
+        .unwrap_or(Language::Portuguese)
+}
+
+// Editor contents covered by undo and redo: section texts, metadata and the
+// structured controls of the attachments, refactoring and tests sections
+#[derive(Clone, Default, PartialEq)]
+struct EditorState {
+    sections: Vec<SharedString>,
+    metadata: DocumentMetadata,
+    attachments: Vec<AttachmentRow>,
+    refactoring_files: Vec<SharedString>,
+    refactoring_goals: [bool; 4],
+    tests_framework: SharedString,
+    tests_kinds: [bool; 3],
+    tests_coverage: i32,
+    tests_cases: Vec<TestCaseRow>,
+}
+
+impl EditorState {
+    // True when any section text, metadata field, attachment or structured
+    // control holds something the user entered
+    fn has_content(&self) -> bool {
+        self.sections.iter().any(|text| !text.trim().is_empty())
+            || !self.metadata.is_empty()
+            || !self.attachments.is_empty()
+            || !self.refactoring_files.is_empty()
+            || !self.tests_cases.is_empty()
+    }
+}
+
+// Capture the editor contents for the undo history
+fn editor_state(ui: &AppWindow) -> EditorState {
+    EditorState {
+        sections: SectionKind::ALL
+            .iter()
+            .map(|kind| section_text(ui, *kind))
+            .collect(),
+        metadata: metadata_from_ui(ui),
+        attachments: ui.get_attachments().iter().collect(),
+        refactoring_files: ui.get_refactoring_files().iter().collect(),
+        refactoring_goals: [
+            ui.get_refactoring_goal_solid(),
+            ui.get_refactoring_goal_performance(),
+            ui.get_refactoring_goal_readability(),
+            ui.get_refactoring_goal_error_handling(),
+        ],
+        tests_framework: ui.get_tests_framework(),
+        tests_kinds: [
+            ui.get_tests_kind_unit(),
+            ui.get_tests_kind_integration(),
+            ui.get_tests_kind_property(),
+        ],
+        tests_coverage: ui.get_tests_coverage(),
+        tests_cases: ui.get_tests_cases().iter().collect(),
+    }
+}
+
+// Show an editor state taken from the undo history
+fn apply_editor_state(state: &EditorState, ui: &AppWindow) {
+    for (kind, text) in SectionKind::ALL.iter().zip(&state.sections) {
+        set_section_text(ui, *kind, text.clone());
+    }
+    apply_metadata_to_ui(&state.metadata, ui);
+    ui.set_attachments(ModelRc::new(VecModel::from(state.attachments.clone())));
+    ui.set_refactoring_files(ModelRc::new(VecModel::from(
+        state.refactoring_files.clone(),
+    )));
+    let [solid, performance, readability, error_handling] = state.refactoring_goals;
+    ui.set_refactoring_goal_solid(solid);
+    ui.set_refactoring_goal_performance(performance);
+    ui.set_refactoring_goal_readability(readability);
+    ui.set_refactoring_goal_error_handling(error_handling);
+    ui.set_tests_framework(state.tests_framework.clone());
+    let [unit, integration, property] = state.tests_kinds;
+    ui.set_tests_kind_unit(unit);
+    ui.set_tests_kind_integration(integration);
+    ui.set_tests_kind_property(property);
+    ui.set_tests_coverage(state.tests_coverage);
+    ui.set_tests_cases(ModelRc::new(VecModel::from(state.tests_cases.clone())));
+}
+
+// Describe what changed between two editor states, for the edit history list
+fn change_label(before: &EditorState, after: &EditorState) -> String {
+    let mut parts: Vec<&str> = SectionKind::ALL
+        .iter()
+        .zip(before.sections.iter().zip(&after.sections))
+        .filter(|(_, (old, new))| old != new)
+        .map(|(kind, _)| kind.title())
+        .collect();
+    if before.metadata != after.metadata {
+        parts.push("Metadados");
+    }
+    if before.attachments != after.attachments {
+        parts.push("Anexos");
+    }
+    if before.refactoring_files != after.refactoring_files
+        || before.refactoring_goals != after.refactoring_goals
+    {
+        parts.push("Refatoração");
+    }
+    if before.tests_framework != after.tests_framework
+        || before.tests_kinds != after.tests_kinds
+        || before.tests_coverage != after.tests_coverage
+        || before.tests_cases != after.tests_cases
+    {
+        parts.push("Testes");
+    }
+    parts.dedup();
+
+    match parts.len() {
+        0 => "Edição".to_string(),
+        1..=3 => format!("Edição em {}", parts.join(", ")),
+        count => format!("Edição em {} partes", count),
+    }
+}
+
+// Show the undo and redo lists and enable the matching buttons
+fn show_undo_history(ui: &AppWindow, history: &UndoHistory<EditorState>) {
+    ui.set_undo_history(strings_to_model(history.undo_labels()));
+    ui.set_redo_history(strings_to_model(history.redo_labels()));
+    ui.set_can_undo(history.can_undo());
+    ui.set_can_redo(history.can_redo());
+}
+
+// Commit typing not picked up by the periodic check yet, so that an operation
+// about to run gets its own undo step
+fn flush_typing(ui: &AppWindow, undo: &RefCell<UndoHistory<EditorState>>) {
+    let state = editor_state(ui);
+    let label = change_label(undo.borrow().current(), &state);
+    if undo.borrow_mut().commit(&label, state) {
+        show_undo_history(ui, &undo.borrow());
+    }
+}
+
+// Commit the result of an operation as one undo step
+fn record_undo_step(ui: &AppWindow, undo: &RefCell<UndoHistory<EditorState>>, label: &str) {
+    if undo.borrow_mut().commit(label, editor_state(ui)) {
+        show_undo_history(ui, &undo.borrow());
+    }
+}
+
+// Editor contents used to detect unsaved changes and written by the autosave
+// and the session: the section texts, the attachments, the structured
+// refactoring and tests controls and the metadata, in the document format
+fn editor_snapshot(ui: &AppWindow) -> String {
+    let mut data = PromptData::new();
+    data.metadata = metadata_from_ui(ui);
+    for kind in SectionKind::ALL {
+        let text = section_text(ui, kind);
+        if !text.trim().is_empty() {
+            data.set_section_body(kind, text.to_string());
+        }
+    }
+
+    let mut auxiliary = AuxiliaryContent::new();
+    auxiliary.data = ui.get_auxiliary_content_text().to_string();
+    auxiliary.attachments = attachments_from_ui(ui);
+    data.auxiliary_content = Some(auxiliary).filter(|section| !section.is_empty());
+    data.refactoring = Some(refactoring_from_ui(ui)).filter(|section| !section.is_empty());
+    data.tests = Some(tests_from_ui(ui)).filter(|section| !section.is_empty());
+
+    data.build_document()
+}
+
+// Record the editor contents as saved, clearing the unsaved-changes indicator
+// and the recovery snapshot
+fn mark_clean(ui: &AppWindow, autosave: &RefCell<Autosave>) {
+    if let Err(e) = autosave.borrow_mut().mark_clean(editor_snapshot(ui)) {
+        show_error(
+            ui,
+            &format!("Erro ao remover rascunho de recuperação: {}", e),
+        );
+    }
+    ui.set_document_dirty(false);
+}
+
+// Offer to restore the work left unsaved by a crash or an accidental close
+fn offer_recovery(
+    ui: &AppWindow,
+    autosave: &RefCell<Autosave>,
+    undo: &RefCell<UndoHistory<EditorState>>,
+    data: &mut PromptData,
+    current_document: &RefCell<Option<PathBuf>>,
+) {
+    let snapshot = match autosave.borrow().store().map(RecoveryStore::load) {
+        Some(Ok(Some(snapshot))) => snapshot,
+        Some(Err(e)) => {
+            show_error(ui, &format!("Erro ao ler rascunho de recuperação: {}", e));
+            return;
+        }
+        _ => return,
+    };
+
+    let mut description = format!(
+        "Há alterações não salvas de {}",
+        format_timestamp(snapshot.saved_at)
+    );
+    if let Some(source) = &snapshot.source {
+        description.push_str(&format!(" em {}", source.display()));
+    }
+    description.push_str(". Deseja restaurá-las?");
+
+    let answer = MessageDialog::new()
+        .set_level(MessageLevel::Warning)
+        .set_title("Recuperar trabalho não salvo")
+        .set_description(description)
+        .set_buttons(MessageButtons::YesNo)
+        .show();
+    if answer == MessageDialogResult::Yes {
+        load_prompt_into_editor(parse_prompt(&snapshot.document), ui, data);
+        if let Some(source) = snapshot.source {
+            show_history(ui, &source);
+            *current_document.borrow_mut() = Some(source);
+        }
+        ui.set_document_dirty(true);
+        record_undo_step(ui, undo, "Recuperar trabalho não salvo");
+        println!("♻️ Trabalho não salvo restaurado");
+    } else if let Some(Err(e)) = autosave.borrow().store().map(RecoveryStore::clear) {
+        show_error(
+            ui,
+            &format!("Erro ao remover rascunho de recuperação: {}", e),
+        );
+    }
+}
+
+// Shared editor state; switching tabs swaps it with the contents kept in a tab
+#[derive(Clone)]
+struct Editor {
+    data: Rc<RefCell<PromptData>>,
+    document: Rc<RefCell<Option<PathBuf>>>,
+    autosave: Rc<RefCell<Autosave>>,
+    undo: Rc<RefCell<UndoHistory<EditorState>>>,
+    // Heading variants recognized when opening prompts
+    aliases: Rc<HeadingAliases>,
+    // Proposes sections for the paragraphs of files without headings
+    classifier: Rc<RefCell<SectionClassifier>>,
+    // File without headings waiting in the import review
+    review: Rc<RefCell<Option<PendingImport>>>,
+    settings: Rc<RefCell<Settings>>,
+    // How the shown document is kept on disk, used when saving it
+    disk: Rc<Cell<DiskFile>>,
+}
+
+// Encoding and line breaks a document is written back with, and the stamp of
+// its file when last read or written, to catch changes made by others
+#[derive(Clone, Copy, Default)]
+struct DiskFile {
+    format: TextFormat,
+    stamp: Option<FileStamp>,
+}
+
+impl Editor {
+    // Read a text file in the encoding of the settings, or the detected one.
+    // The stamp is taken first, so a change made while reading is caught
+    // when saving.
+    fn read(&self, path: &Path) -> error::Result<(String, DiskFile)> {
+        let stamp = FileStamp::of(path)?;
+        let decoded = read_text_file(path, self.settings.borrow().encoding)?;
+        let disk = DiskFile {
+            format: decoded.format,
+            stamp: Some(stamp),
+        };
+        Ok((decoded.text, disk))
+    }
+
+    // Parse a prompt written anywhere, with the user's heading aliases and
+    // trained classifier
+    fn import(&self, content: &str) -> PromptData {
+        parse_prompt_with(content, &self.aliases, &self.classifier.borrow())
+    }
+
+    // Import review for a file about to be opened; None when it has headings
+    // or is already open in a tab
+    fn review_import(
+        &self,
+        tabs: &RefCell<Tabs<TabSession>>,
+        path: &Path,
+        content: &str,
+    ) -> Option<ImportReview> {
+        if tabs.borrow().position_of(path).is_some() {
+            return None;
+        }
+        ImportReview::from_content(content, &self.aliases, &self.classifier.borrow())
+    }
+}
+
+// A file without headings whose blocks the user is reviewing, with the undo
+// label used once it is opened
+struct PendingImport {
+    path: PathBuf,
+    review: ImportReview,
+    disk: DiskFile,
+    label: &'static str,
+}
+
+impl PendingImport {
+    fn new(path: &Path, review: ImportReview, disk: DiskFile, label: &'static str) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            review,
+            disk,
+            label,
+        }
+    }
+}
+
+// Editor contents of a tab in the background
+struct TabSession {
+    data: PromptData,
+    state: EditorState,
+    // Editor snapshot when the tab was left, written to the session file
+    snapshot: String,
+    // Snapshot at the last save or open, to tell whether the tab is dirty
+    clean: String,
+    undo: UndoHistory<EditorState>,
+    preview: SharedString,
+    disk: DiskFile,
+}
+
+// Contents of a new, empty tab
+fn empty_tab_session() -> TabSession {
+    let blank = PromptData::new().build_document();
+    TabSession {
+        data: PromptData::new(),
+        state: EditorState::default(),
+        snapshot: blank.clone(),
+        clean: blank,
+        undo: UndoHistory::new(EditorState::default(), DEFAULT_UNDO_LIMIT),
+        preview: "".into(),
+        disk: DiskFile::default(),
+    }
+}
+
+// Take the contents of the shown tab out of the editor
+fn stash_editor(ui: &AppWindow, editor: &Editor) -> TabSession {
+    flush_typing(ui, &editor.undo);
+    TabSession {
+        data: editor.data.borrow().clone(),
+        state: editor_state(ui),
+        snapshot: editor_snapshot(ui),
+        clean: editor.autosave.borrow().clean().to_string(),
+        undo: editor.undo.borrow().clone(),
+        preview: ui.get_preview_text(),
+        disk: editor.disk.get(),
+    }
+}
+
+// Empty the version history panel, for documents never saved
+fn clear_history_panel(ui: &AppWindow) {
+    ui.set_history_document("".into());
+    ui.set_history_note("".into());
+    ui.set_history_revisions(strings_to_model(Vec::new()));
+    ui.set_history_diff("".into());
+}
+
+// Keep how the shown document is kept on disk and show its encoding and line
+// breaks in the status bar
+fn set_disk_file(ui: &AppWindow, editor: &Editor, disk: DiskFile) {
+    editor.disk.set(disk);
+    ui.set_document_format(disk.format.label().into());
+}
+
+// Show the contents kept in a tab in the editor
+fn show_tab_session(ui: &AppWindow, editor: &Editor, path: Option<PathBuf>, session: TabSession) {
+    apply_editor_state(&session.state, ui);
+    *editor.data.borrow_mut() = session.data;
+    editor.autosave.borrow_mut().switch_to(session.clean);
+    *editor.undo.borrow_mut() = session.undo;
+    show_undo_history(ui, &editor.undo.borrow());
+    ui.set_preview_text(session.preview);
+    ui.set_preview_tokens("".into());
+    set_disk_file(ui, editor, session.disk);
+    ui.set_document_dirty(editor.autosave.borrow().is_dirty(&session.snapshot));
+    match &path {
+        Some(path) => show_history(ui, path),
+        None => clear_history_panel(ui),
+    }
+    *editor.document.borrow_mut() = path;
+}
+
+// Copy the file and unsaved-changes flag of the editor into the shown tab
+fn sync_active_tab(ui: &AppWindow, editor: &Editor, tabs: &mut Tabs<TabSession>) {
+    let tab = tabs.active_tab_mut();
+    tab.path = editor.document.borrow().clone();
+    tab.dirty = ui.get_document_dirty();
+}
+
+// Show the open documents in the tab bar
+fn show_tabs(ui: &AppWindow, tabs: &Tabs<TabSession>) {
+    let rows: Vec<DocumentTabRow> = tabs
+        .tabs()
+        .iter()
+        .map(|tab| DocumentTabRow {
+            title: tab.title().into(),
+            dirty: tab.dirty,
+        })
+        .collect();
+    ui.set_document_tabs(ModelRc::new(VecModel::from(rows)));
+    ui.set_active_tab(tabs.active() as i32);
+}
+
+// Show the tab at `index`, keeping the contents of the tab being left
+fn select_tab(ui: &AppWindow, editor: &Editor, tabs: &RefCell<Tabs<TabSession>>, index: usize) {
+    sync_active_tab(ui, editor, &mut tabs.borrow_mut());
+    let contents = tabs.borrow_mut().select(index, || stash_editor(ui, editor));
+    if let Some(contents) = contents {
+        let path = tabs.borrow().active_tab().path.clone();
+        show_tab_session(ui, editor, path, contents);
+    }
+    show_tabs(ui, &tabs.borrow());
+}
+
+// Make room for a document about to be read from `path`. Returns None after
+// switching to the tab already holding it, Some(true) when a new tab was
+// opened for it and Some(false) when the shown tab is an empty new document
+// that can take it.
+fn prepare_tab_for(
+    ui: &AppWindow,
+    editor: &Editor,
+    tabs: &RefCell<Tabs<TabSession>>,
+    path: &Path,
+) -> Option<bool> {
+    let open_at = tabs.borrow().position_of(path);
+    if let Some(index) = open_at {
+        println!("📑 Documento já aberto em uma aba: {}", path.display());
+        select_tab(ui, editor, tabs, index);
+        return None;
+    }
+    if editor.document.borrow().is_none() && !editor_state(ui).has_content() {
+        flush_typing(ui, &editor.undo);
+        return Some(false);
+    }
+
+    sync_active_tab(ui, editor, &mut tabs.borrow_mut());
+    let shown = stash_editor(ui, editor);
+    tabs.borrow_mut().open(Some(path.to_path_buf()), shown);
+    Some(true)
+}
+
+// Finish showing a document read from `path` after `prepare_tab_for`
+fn finish_open(
+    ui: &AppWindow,
+    editor: &Editor,
+    tabs: &RefCell<Tabs<TabSession>>,
+    path: &Path,
+    disk: DiskFile,
+    new_tab: bool,
+    label: &str,
+) {
+    set_disk_file(ui, editor, disk);
+    if new_tab {
+        *editor.undo.borrow_mut() = UndoHistory::new(editor_state(ui), DEFAULT_UNDO_LIMIT);
+        show_undo_history(ui, &editor.undo.borrow());
+    } else {
+        record_undo_step(ui, &editor.undo, label);
+    }
+    show_history(ui, path);
+    *editor.document.borrow_mut() = Some(path.to_path_buf());
+    mark_clean(ui, &editor.autosave);
+    sync_active_tab(ui, editor, &mut tabs.borrow_mut());
+    show_tabs(ui, &tabs.borrow());
+}
+
+// List the blocks of the file under import review
+fn show_review_rows(ui: &AppWindow, review: &ImportReview) {
+    let rows: Vec<ReviewRow> = review
+        .blocks()
+        .iter()
+        .map(|block| ReviewRow {
+            text: block.text.clone().into(),
+            section: block.kind.index() as i32,
+            confidence: format!("{:.0}%", block.confidence * 100.0).into(),
+            uncertain: block.is_uncertain(),
+        })
+        .collect();
+    ui.set_review_rows(ModelRc::new(VecModel::from(rows)));
+}
+
+// Show the import review of a file without headings; nothing is loaded until
+// the user confirms it
+fn show_import_review(ui: &AppWindow, editor: &Editor, pending: PendingImport) {
+    let uncertain = pending
+        .review
+        .blocks()
+        .iter()
+        .filter(|block| block.is_uncertain())
+        .count();
+    println!(
+        "📥 {} bloco(s) para revisar, {} com seção incerta",
+        pending.review.blocks().len(),
+        uncertain
+    );
+    show_review_rows(ui, &pending.review);
+    ui.set_review_file(pending.path.display().to_string().into());
+    ui.set_review_visible(true);
+    *editor.review.borrow_mut() = Some(pending);
+}
+
+// Ask whether to save over a file changed on disk since it was opened
+fn confirm_overwrite(path: &Path, backups: usize) -> bool {
+    let mut description = format!(
+        "O arquivo {} foi alterado por outro programa depois de aberto. Sobrescrever mesmo assim?",
+        path.display()
+    );
+    if backups > 0 {
+        description.push_str(" A versão do disco fica guardada como cópia de segurança.");
+    }
+    MessageDialog::new()
+        .set_level(MessageLevel::Warning)
+        .set_title("Arquivo alterado no disco")
+        .set_description(description)
+        .set_buttons(MessageButtons::YesNo)
+        .show()
+        == MessageDialogResult::Yes
+}
+
+// Read a prompt file and show it in a tab, recording it as recently used.
+// Returns false when the file could not be read.
+fn open_prompt_file(
+    ui: &AppWindow,
+    editor: &Editor,
+    tabs: &RefCell<Tabs<TabSession>>,
+    recent: &RefCell<RecentFiles>,
+    path: &Path,
+) -> bool {
+    let (content, disk) = match editor.read(path) {
+        Ok(read) => read,
+        Err(e) => {
+            show_error(ui, &format!("Erro ao ler arquivo: {}", e));
+            return false;
+        }
+    };
+    println!(
+        "✅ Arquivo carregado com sucesso! ({})",
+        disk.format.label()
+    );
+
+    recent.borrow_mut().add(path);
+    store_recent_files(ui, &recent.borrow());
+
+    // Files without headings are loaded only after the import review
+    if let Some(review) = editor.review_import(tabs, path, &content) {
+        let label = "Abrir arquivo";
+        show_import_review(ui, editor, PendingImport::new(path, review, disk, label));
+        return true;
+    }
+
+    // Show the parsed sections in a new tab, unless the shown tab is an empty
+    // new document or the file is already open
+    let Some(new_tab) = prepare_tab_for(ui, editor, tabs, path) else {
+        return true;
+    };
+    load_prompt_into_editor(editor.import(&content), ui, &mut editor.data.borrow_mut());
+    finish_open(ui, editor, tabs, path, disk, new_tab, "Abrir arquivo");
+    true
+}
+
+// Editor contents of a tab saved in the last session. Tabs without unsaved
+// changes are read again from their file, which may have changed since.
+fn restore_tab_session(ui: &AppWindow, editor: &Editor, tab: &SessionTab) -> TabSession {
+    let saved = tab.path.as_ref().and_then(|path| editor.read(path).ok());
+    let disk = saved.as_ref().map(|(_, disk)| *disk).unwrap_or_default();
+    let saved = saved.map(|(text, _)| text);
+    let mut data = PromptData::new();
+    load_prompt_into_editor(editor.import(saved.as_deref().unwrap_or("")), ui, &mut data);
+    let clean = editor_snapshot(ui);
+    if tab.dirty || saved.is_none() {
+        load_prompt_into_editor(editor.import(&tab.document), ui, &mut data);
+    }
+
+    let state = editor_state(ui);
+    TabSession {
+        data,
+        snapshot: editor_snapshot(ui),
+        clean,
+        undo: UndoHistory::new(state.clone(), DEFAULT_UNDO_LIMIT),
+        state,
+        preview: ui.get_preview_text(),
+        disk,
+    }
+}
+
+// Reopen the tabs of the last session, showing the tab that was shown
+fn restore_session(
+    ui: &AppWindow,
+    editor: &Editor,
+    tabs: &RefCell<Tabs<TabSession>>,
+    session: &Session,
+) {
+    let mut restored = session
+        .tabs
+        .iter()
+        .map(|tab| (tab, restore_tab_session(ui, editor, tab)));
+    let Some((first, first_contents)) = restored.next() else {
+        return;
+    };
+
+    let mut opened = Tabs::new(first.path.clone());
+    opened.active_tab_mut().dirty = first.dirty;
+    for (tab, contents) in restored {
+        opened.push_background(tab.path.clone(), tab.dirty, contents);
+    }
+    *tabs.borrow_mut() = opened;
+    show_tab_session(ui, editor, first.path.clone(), first_contents);
+    select_tab(ui, editor, tabs, session.active);
+    println!(
+        "📑 {} aba(s) da última sessão restaurada(s)",
+        session.tabs.len()
+    );
+}
+
+// Open tabs as a session for the next launch. With `discard`, unsaved changes
+// are left out: tabs go back to their saved contents and new documents are
+// dropped.
+fn session_of(ui: &AppWindow, editor: &Editor, tabs: &Tabs<TabSession>, discard: bool) -> Session {
+    let mut session = Session {
+        tabs: Vec::new(),
+        active: 0,
+    };
+    for (index, tab) in tabs.tabs().iter().enumerate() {
+        let (snapshot, clean) = match tab.stash() {
+            Some(contents) => (contents.snapshot.clone(), contents.clean.clone()),
+            None => (
+                editor_snapshot(ui),
+                editor.autosave.borrow().clean().to_string(),
+            ),
+        };
+        if index == tabs.active() {
+            session.active = session.tabs.len();
+        }
+        if !discard {
+            session.tabs.push(SessionTab {
+                path: tab.path.clone(),
+                document: snapshot,
+                dirty: tab.dirty,
+            });
+        } else if tab.path.is_some() {
+            session.tabs.push(SessionTab {
+                path: tab.path.clone(),
+                document: clean,
+                dirty: false,
+            });
+        }
+    }
+    session.active = session.active.min(session.tabs.len().saturating_sub(1));
+    session
+}
+
+// Write the open tabs to the session store
+fn save_session(ui: &AppWindow, store: Option<&SessionStore>, session: &Session) {
+    if let Some(Err(e)) = store.map(|store| store.save(session)) {
+        show_error(ui, &format!("Erro ao salvar sessão: {}", e));
+    }
+}
+
+// Fill the settings dialog with the given settings
+fn show_settings(ui: &AppWindow, settings: &Settings) {
+    let path_text = |path: &Option<PathBuf>| {
+        path.as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default()
+    };
+    ui.set_settings_save_dir(path_text(&settings.save_dir).into());
+    ui.set_settings_file_name(settings.file_name.clone().into());
+    ui.set_settings_library_dir(settings.library_dir.display().to_string().into());
+    ui.set_settings_workspace_dir(path_text(&settings.workspace_dir).into());
+    let renderer = Renderer::ALL
+        .iter()
+        .position(|item| *item == settings.renderer);
+    ui.set_settings_renderer(renderer.unwrap_or(0) as i32);
+    ui.set_settings_autosave(settings.autosave_seconds as i32);
+    let tokenizer = Tokenizer::ALL
+        .iter()
+        .position(|item| *item == settings.tokenizer);
+    ui.set_settings_tokenizer(tokenizer.unwrap_or(0) as i32);
+    ui.set_settings_font_size(settings.font_size as i32);
+    let theme = Theme::ALL.iter().position(|item| *item == settings.theme);
+    ui.set_settings_theme(theme.unwrap_or(0) as i32);
+    ui.set_settings_ui_language(settings.ui_language.index() as i32);
+    ui.set_settings_output_language(settings.output_language.index() as i32);
+    // The first encoding option detects it from the contents of each file
+    let encoding = settings
+        .encoding
+        .and_then(|encoding| TextEncoding::ALL.iter().position(|item| *item == encoding));
+    ui.set_settings_encoding(encoding.map_or(0, |index| index + 1) as i32);
+    let line_endings = LineEndingPolicy::ALL
+        .iter()
+        .position(|item| *item == settings.line_endings);
+    ui.set_settings_line_endings(line_endings.unwrap_or(0) as i32);
+    ui.set_settings_backups(settings.backup_count as i32);
+    ui.set_settings_status("".into());
+}
+
+// Read the settings edited in the settings dialog; empty fields keep the defaults
+fn settings_from_ui(ui: &AppWindow) -> Settings {
+    let defaults = Settings::default();
+    let optional_path = |text: SharedString| {
+        let text = text.trim();
+        (!text.is_empty()).then(|| PathBuf::from(text))
+    };
+    let file_name = ui.get_settings_file_name().trim().to_string();
+    let library_dir = ui.get_settings_library_dir().trim().to_string();
+    Settings {
+        save_dir: optional_path(ui.get_settings_save_dir()),
+        file_name: if file_name.is_empty() {
+            defaults.file_name
+        } else {
+            file_name
+        },
+        library_dir: if library_dir.is_empty() {
+            defaults.library_dir
+        } else {
+            PathBuf::from(library_dir)
+        },
+        workspace_dir: optional_path(ui.get_settings_workspace_dir()),
+        renderer: Renderer::ALL
+            .get(ui.get_settings_renderer() as usize)
+            .copied()
+            .unwrap_or(defaults.renderer),
+        autosave_seconds: (ui.get_settings_autosave().max(0) as u64)
+            .clamp(AUTOSAVE_RANGE.0, AUTOSAVE_RANGE.1),
+        tokenizer: Tokenizer::ALL
+            .get(ui.get_settings_tokenizer() as usize)
+            .copied()
+            .unwrap_or(defaults.tokenizer),
+        font_size: (ui.get_settings_font_size().max(0) as u32)
+            .clamp(FONT_SIZE_RANGE.0, FONT_SIZE_RANGE.1),
+        theme: Theme::ALL
+            .get(ui.get_settings_theme() as usize)
+            .copied()
+            .unwrap_or(defaults.theme),
+        ui_language: Language::ALL
+            .get(ui.get_settings_ui_language() as usize)
+            .copied()
+            .unwrap_or(defaults.ui_language),
+        output_language: Language::ALL
+            .get(ui.get_settings_output_language() as usize)
+            .copied()
+            .unwrap_or(defaults.output_language),
+        encoding: (ui.get_settings_encoding() as usize)
+            .checked_sub(1)
+            .and_then(|index| TextEncoding::ALL.get(index).copied()),
+        line_endings: LineEndingPolicy::ALL
+            .get(ui.get_settings_line_endings() as usize)
+            .copied()
+            .unwrap_or(defaults.line_endings),
+        backup_count: (ui.get_settings_backups().max(0) as usize).min(MAX_BACKUP_COUNT),
+    }
+}
+
+// Apply the font size, theme and languages of the settings to the window
+fn apply_appearance(ui: &AppWindow, settings: &Settings) {
+    ui.set_font_size(settings.font_size as i32);
+    let theme = Theme::ALL.iter().position(|item| *item == settings.theme);
+    ui.invoke_apply_theme(theme.unwrap_or(0) as i32);
+    ui.global::<I18n>()
+        .set_language(settings.ui_language.index() as i32);
+    ui.set_output_language(settings.output_language.index() as i32);
+    show_option_lists(ui, settings.ui_language);
+}
+
+// Fill the option lists of the combo boxes with labels in `language`
+fn show_option_lists(ui: &AppWindow, language: Language) {
+    let labels = |labels: Vec<&str>| {
+        strings_to_model(
+            labels
+                .into_iter()
+                .map(|label| i18n::translate(label, language).to_string())
+                .collect(),
+        )
+    };
+    ui.set_budget_strategy_options(labels(
+        TruncationStrategy::ALL
+            .iter()
+            .map(|strategy| strategy.label())
+            .collect(),
+    ));
+    let section_titles = strings_to_model(
+        SectionKind::ALL
+            .iter()
+            .map(|kind| kind.title_in(language).to_string())
+            .collect(),
+    );
+    ui.set_git_target_options(section_titles.clone());
+    ui.set_section_options(section_titles);
+    ui.set_settings_renderer_options(labels(
+        Renderer::ALL
+            .iter()
+            .map(|renderer| renderer.label())
+            .collect(),
+    ));
+    ui.set_settings_tokenizer_options(labels(
+        Tokenizer::ALL
+            .iter()
+            .map(|tokenizer| tokenizer.label())
+            .collect(),
+    ));
+    ui.set_settings_theme_options(labels(
+        Theme::ALL.iter().map(|theme| theme.label()).collect(),
+    ));
+    ui.set_settings_encoding_options(labels(
+        std::iter::once("Detectar automaticamente")
+            .chain(TextEncoding::ALL.iter().map(|encoding| encoding.label()))
+            .collect(),
+    ));
+    ui.set_settings_line_ending_options(labels(
+        LineEndingPolicy::ALL
+            .iter()
+            .map(|policy| policy.label())
+            .collect(),
+    ));
+    ui.set_settings_language_options(strings_to_model(
+        Language::ALL
+            .iter()
+            .map(|language| language.label().to_string())
+            .collect(),
+    ));
+}
+
+// Ask for a prompt document or text file to compare
+fn pick_compare_file(title: &str) -> Option<PathBuf> {
+    FileDialog::new()
+        .add_filter("Documentos", &["txt", "md"])
+        .set_title(title)
+        .pick_file()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Command-line commands such as `diff` run without opening the window
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
+    let ui = AppWindow::new()?;
+
+    // Labels are looked up in the translation catalog in the chosen language
+    ui.global::<I18n>().on_translate(|text, language| {
+        let language = Language::ALL
+            .get(language as usize)
+            .copied()
+            .unwrap_or(Language::Portuguese);
+        i18n::translate(&text, language).into()
+    });
+
+    // Initialize prompt data in a shared state
+    let prompt_data = Rc::new(RefCell::new(PromptData::new()));
+
+    // File of the document being edited, whose saves are recorded as revisions
+    let current_document: Rc<RefCell<Option<PathBuf>>> = Rc::new(RefCell::new(None));
+
+    // Undo and redo history of the whole document
+    let undo = Rc::new(RefCell::new(UndoHistory::new(
+        editor_state(&ui),
+        DEFAULT_UNDO_LIMIT,
+    )));
+
+    // Unsaved-changes tracking, autosaved to the user data directory for crash recovery
+    let autosave = Rc::new(RefCell::new(Autosave::new(
+        RecoveryStore::in_user_data_dir(),
+        editor_snapshot(&ui),
+    )));
+
+    // Heading aliases for opening prompts written elsewhere, from defaults
+    // plus the user file in the configuration directory
+    let heading_aliases = HeadingAliases::in_user_config_dir().unwrap_or_else(|e| {
+        show_error(&ui, &format!("Erro ao ler apelidos de títulos: {}", e));
+        HeadingAliases::new()
+    });
+
+    // Section classifier for files without headings, as last trained on the
+    // user's library or the bundled one
+    let classifier_store = ClassifierStore::in_user_data_dir();
+    let classifier = match classifier_store.as_ref().map(ClassifierStore::load) {
+        Some(Ok(classifier)) => classifier,
+        Some(Err(e)) => {
+            show_error(&ui, &format!("Erro ao ler classificador de seções: {}", e));
+            SectionClassifier::bundled()
+        }
+        None => SectionClassifier::bundled(),
+    };
+
+    // User preferences kept in the configuration directory
+    let settings_store = SettingsStore::in_user_config_dir();
+    let settings = match settings_store.as_ref().map(SettingsStore::load) {
+        Some(Ok(settings)) => settings,
+        Some(Err(e)) => {
+            show_error(&ui, &format!("Erro ao ler configurações: {}", e));
+            Settings::default()
+        }
+        None => Settings::default(),
+    };
+    apply_appearance(&ui, &settings);
+    let settings = Rc::new(RefCell::new(settings));
+
+    // Open documents, one tab each; the shown tab lives in the shared state above
+    let editor = Editor {
+        data: prompt_data.clone(),
+        document: current_document.clone(),
+        autosave: autosave.clone(),
+        undo: undo.clone(),
+        aliases: Rc::new(heading_aliases),
+        classifier: Rc::new(RefCell::new(classifier)),
+        review: Rc::new(RefCell::new(None)),
+        settings: settings.clone(),
+        disk: Rc::new(Cell::new(DiskFile::default())),
+    };
+    set_disk_file(&ui, &editor, DiskFile::default());
+    let tabs: Rc<RefCell<Tabs<TabSession>>> = Rc::new(RefCell::new(Tabs::new(None)));
+    let session_store = SessionStore::in_user_data_dir();
+
+    // Recently opened or saved files, listed in the File menu
+    let recent_files = match RecentFiles::in_user_data_dir() {
+        Ok(recent) => recent,
+        Err(e) => {
+            show_error(&ui, &format!("Erro ao ler arquivos recentes: {}", e));
+            RecentFiles::new()
+        }
+    };
+    show_recent_files(&ui, &recent_files);
+    let recent_files = Rc::new(RefCell::new(recent_files));
+
+    // Offer the known test frameworks in the tests section
+    ui.set_tests_framework_options(strings_to_model(
+        std::iter::once(String::new())
+            .chain(KNOWN_FRAMEWORKS.iter().map(|name| name.to_string()))
+            .collect(),
+    ));
+
+    // Start the compact mode with the default phrase dictionary
+    ui.set_compact_dictionary(format_dictionary(&CompactOptions::default().dictionary).into());
+
+    // The library and the workspace start in the folders of the settings
+    ui.set_library_path(settings.borrow().library_dir.display().to_string().into());
+    if let Some(dir) = &settings.borrow().workspace_dir {
+        ui.set_workspace_path(dir.display().to_string().into());
+    }
+
+    // Set up callbacks for UI events
+    let ui_weak = ui.as_weak();
+    let prompt_data_clone = prompt_data.clone();
+    let settings_generate = settings.clone();
+
+    // Generate prompt callback
+    ui.on_generate_prompt(move || {
+        let ui = ui_weak.unwrap();
+        let mut data = prompt_data_clone.borrow_mut();
+
+        // Clear previous data
+        *data = PromptData::new();
+        data.metadata = metadata_from_ui(&ui);
+        if data.metadata.temperature.is_none() && !ui.get_metadata_temperature().trim().is_empty() {
+            show_error(
+                &ui,
+                &format!(
+                    "Temperatura inválida: '{}'",
+                    ui.get_metadata_temperature().trim()
+                ),
+            );
+        }
+
+        println!("🔄 Gerando preview do prompt...");
+
+        // Collect all field values first
+        let few_shot_val = ui.get_few_shot_text().to_string();
+        let context_val = ui.get_context_text().to_string();
+        let main_content_val = ui.get_main_content_text().to_string();
+        let auxiliary_val = ui.get_auxiliary_content_text().to_string();
+        let attachments_val = attachments_from_ui(&ui);
+        let limitations_val = ui.get_limitations_text().to_string();
+        let refactoring_val = refactoring_from_ui(&ui);
+        if let Err(e) = check_code_references(&refactoring_val) {
+            show_error(&ui, &e.to_string());
+        }
+        let guidance_val = ui.get_guidance_text().to_string();
+        let tests_val = tests_from_ui(&ui);
+        let output_format_val = ui.get_output_format_text().to_string();
+
+        // Debug: Check if UI values are being read
+        println!("📝 Valores dos campos:");
+        println!("  Few-shot: '{}'", few_shot_val);
+        println!("  Context: '{}'", context_val);
+        println!("  Main content: '{}'", main_content_val);
+        println!("  Auxiliary: '{}'", auxiliary_val);
+        println!("  Attachments: {}", attachments_val.len());
+        println!("  Limitations: '{}'", limitations_val);
+        println!("  Refactoring: '{}'", refactoring_val.text);
+        println!("  Refactoring files: {}", refactoring_val.files.len());
+        println!("  Guidance: '{}'", guidance_val);
+        println!("  Tests: '{}'", tests_val.text);
+        println!("  Test cases: {}", tests_val.cases.len());
+        println!("  Output format: '{}'", output_format_val);
+
+        // Update prompt data with UI values
+        if !few_shot_val.trim().is_empty() {
+            println!("✅ Adicionando Few-shot");
+            let mut few_shot = FewShot::new();
+            few_shot.content = few_shot_val;
+            data.few_shot = Some(few_shot);
+        }
+
+        if !context_val.trim().is_empty() {
+            println!("✅ Adicionando Context");
+            let mut context = Context::new();
+            context.description = context_val;
+            data.context = Some(context);
+        }
+
+        if !main_content_val.trim().is_empty() {
+            println!("✅ Adicionando Main Content");
+            let mut main_content = MainContent::new();
+            main_content.instructions = main_content_val;
+            data.main_content = Some(main_content);
+        }
+
+        let mut aux_content = AuxiliaryContent::new();
+        aux_content.data = auxiliary_val;
+        aux_content.attachments = attachments_val;
+        if aux_content.attachments.is_empty() {
+            ui.set_attachments_summary("".into());
+        } else {
+            let report = read_attachments(&aux_content.attachments, &aux_content.limits);
+            println!("📎 {}", report.summary());
+            ui.set_attachments_summary(report.summary().into());
+        }
+        if !aux_content.is_empty() {
+            println!("✅ Adicionando Auxiliary Content");
+            data.auxiliary_content = Some(aux_content);
+        }
+
+        if !limitations_val.trim().is_empty() {
+            println!("✅ Adicionando Limitations");
+            let mut limitations = Limitations::new();
+            limitations.text = limitations_val;
+            data.limitations = Some(limitations);
+        }
+
+        if !refactoring_val.is_empty() {
+            println!("✅ Adicionando Refactoring");
+            data.refactoring = Some(refactoring_val);
+        }
+
+        if !guidance_val.trim().is_empty() {
+            println!("✅ Adicionando Guidance");
+            let mut guidance = Guidance::new();
+            guidance.text = guidance_val;
+            data.guidance = Some(guidance);
+        }
+
+        if !tests_val.is_empty() {
+            println!("✅ Adicionando Tests");
+            data.tests = Some(tests_val);
+        }
+
+        if !output_format_val.trim().is_empty() {
+            println!("✅ Adicionando Output Format");
+            let mut output_format = OutputFormat::new();
+            output_format.text = output_format_val;
+            data.output_format = Some(output_format);
+        }
+
+        // Compaction and budget only change the rendered prompt; the document
+        // keeps its full text
+        let output = prompt_output(&ui, &data, settings_generate.borrow().tokenizer);
+
+        // Generate the prompt with clean formatting for preview
+        let generated_prompt = output.build_preview_prompt_in(output_language(&ui));
+
+        // Debug: print generated prompt to console
+        println!(
+            "📝 Prompt gerado ({} caracteres):\n{}",
+            generated_prompt.len(),
+            generated_prompt
+        );
+
+        // Force update the preview regardless of content
+        println!("🔄 Atualizando preview...");
+        ui.set_preview_text(generated_prompt.into());
+        println!("✅ Preview atualizado!");
+
+        // Tokens of the text that is saved and copied
+        let settings = settings_generate.borrow();
+        let prompt_text = settings.renderer.render(&output, settings.output_language);
+        let tokens = settings.tokenizer.count(&prompt_text);
+        let tokenizer = i18n::translate(settings.tokenizer.label(), settings.ui_language);
+        ui.set_preview_tokens(format!("≈ {} tokens · {}", tokens, tokenizer).into());
+    });
+
+    // Save prompt callback
+    let ui_weak2 = ui.as_weak();
+    let prompt_data_clone2 = prompt_data.clone();
+    let current_document_save = current_document.clone();
+    let autosave_save = autosave.clone();
+    let recent_save = recent_files.clone();
+    let settings_save = settings.clone();
+    let editor_save = editor.clone();
+    ui.on_save_prompt(move || {
+        let ui = ui_weak2.unwrap();
+        // Saved from the editor as it is now, not as it was at the last Generate,
+        // since the editor is marked clean afterwards
+        ui.invoke_generate_prompt();
+        let mut data = prompt_data_clone2.borrow_mut();
+        let settings = settings_save.borrow();
+
+        let file_name = data
+            .metadata
+            .file_stem()
+            .map(|stem| format!("{}.txt", stem))
+            .unwrap_or_else(|| settings.file_name.clone());
+
+        // Open file dialog to choose save location, in the folder of the settings
+        let mut dialog = FileDialog::new();
+        if let Some(dir) = settings.save_dir.as_ref().filter(|dir| dir.is_dir()) {
+            dialog = dialog.set_directory(dir);
+        }
+        if let Some(file_path) = dialog
+            .set_title("Salvar Prompt")
+            .set_file_name(&file_name)
+            .add_filter("Arquivo de Texto", &["txt"])
+            .add_filter("Todos os Arquivos", &["*"])
+            .save_file()
+        {
+            // Touched before rendering, so the file, the revision and the
+            // editor all carry the same modification time
+            data.metadata.touch(now_seconds());
+            let prompt_text = saved_text(&settings, &data, &file_path);
+
+            // Written back in the encoding and line breaks it was read with,
+            // without overwriting changes made to the file by others since
+            let disk = editor_save.disk.get();
+            let mut format = disk.format.for_text(&prompt_text);
+            format.line_ending = settings.line_endings.resolve(format.line_ending);
+            let same_file = current_document_save.borrow().as_deref() == Some(file_path.as_path());
+            let mut options = SaveOptions {
+                format,
+                backups: settings.backup_count,
+                expected: disk.stamp.filter(|_| same_file),
+            };
+            let mut saved = save_prompt_with(&prompt_text, &file_path, &options);
+            if matches!(saved, Err(Error::ChangedOnDisk { .. }))
+                && confirm_overwrite(&file_path, settings.backup_count)
+            {
+                options.expected = None;
+                saved = save_prompt_with(&prompt_text, &file_path, &options);
+            }
+            match saved {
+                Ok(stamp) => {
+                    println!(
+                        "✅ Prompt salvo com sucesso em: {:?} ({})",
+                        file_path,
+                        format.label()
+                    );
+                    let stamp = Some(stamp);
+                    set_disk_file(&ui, &editor_save, DiskFile { format, stamp });
+                    apply_metadata_to_ui(&data.metadata, &ui);
+                    record_revision(&ui, &file_path, &data);
+                    recent_save.borrow_mut().add(&file_path);
+                    store_recent_files(&ui, &recent_save.borrow());
+                    *current_document_save.borrow_mut() = Some(file_path);
+                    mark_clean(&ui, &autosave_save);
+                }
+                Err(Error::ChangedOnDisk { .. }) => {
+                    println!("💭 Salvamento cancelado para não sobrescrever o arquivo")
+                }
+                Err(e) => {
+                    eprintln!("❌ Erro ao salvar prompt: {}", e);
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Error)
+                        .set_title("Erro ao salvar prompt")
+                        .set_description(e.to_string())
+                        .set_buttons(MessageButtons::Ok)
+                        .show();
+                }
+            }
+        } else {
+            println!("💭 Salvamento cancelado pelo usuário");
+        }
+    });
+
+    // Clear all callback
+    let ui_weak3 = ui.as_weak();
+    let prompt_data_clone3 = prompt_data.clone();
+    let current_document_clear = current_document.clone();
+    let autosave_clear = autosave.clone();
+    let undo_clear = undo.clone();
+    let editor_clear = editor.clone();
+    ui.on_clear_all(move || {
+        let ui = ui_weak3.unwrap();
+
+        // Ask before wiping a non-empty editor; the step can still be undone
+        if editor_state(&ui).has_content() {
+            let answer = MessageDialog::new()
+                .set_level(MessageLevel::Warning)
+                .set_title("Limpar Tudo")
+                .set_description("Limpar todos os campos do editor? Você pode desfazer com Ctrl+Z.")
+                .set_buttons(MessageButtons::YesNo)
+                .show();
+            if answer != MessageDialogResult::Yes {
+                return;
+            }
+        }
+        flush_typing(&ui, &undo_clear);
+
+        let mut data = prompt_data_clone3.borrow_mut();
+
+        // Clear all data
+        *data = PromptData::new();
+
+        // Clear UI fields
+        ui.set_few_shot_text("".into());
+        ui.set_context_text("".into());
+        ui.set_main_content_text("".into());
+        ui.set_auxiliary_content_text("".into());
+        ui.set_limitations_text("".into());
+        ui.set_refactoring_text("".into());
+        ui.set_guidance_text("".into());
+        ui.set_tests_text("".into());
+        ui.set_output_format_text("".into());
+        clear_refactoring_extras(&ui);
+        ui.set_compact_report("".into());
+        ui.set_budget_report("".into());
+        ui.set_series_report("".into());
+        apply_metadata_to_ui(&DocumentMetadata::new(), &ui);
+
+        // A cleared editor starts a new document without history
+        *current_document_clear.borrow_mut() = None;
+        set_disk_file(&ui, &editor_clear, DiskFile::default());
+        clear_history_panel(&ui);
+        clear_tests_extras(&ui);
+        clear_attachments(&ui);
+        ui.set_preview_text("".into());
+        ui.set_preview_tokens("".into());
+        mark_clean(&ui, &autosave_clear);
+        record_undo_step(&ui, &undo_clear, "Limpar Tudo");
+    });
+
+    // Copy to clipboard callback
+    let ui_weak4 = ui.as_weak();
+    let prompt_data_clone4 = prompt_data.clone();
+    let settings_copy = settings.clone();
+    ui.on_copy_to_clipboard(move || {
+        let ui = ui_weak4.unwrap();
+        let data = prompt_data_clone4.borrow();
+        let settings = settings_copy.borrow();
+        let output = prompt_output(&ui, &data, settings.tokenizer);
+
+        let prompt_text = settings.renderer.render(&output, settings.output_language);
+
+        // Try to copy to clipboard
+        match ClipboardContext::new() {
+            Ok(mut ctx) => match ctx.set_contents(prompt_text) {
+                Ok(_) => println!("✅ Prompt copiado para a área de transferência!"),
+                Err(e) => show_error(&ui, &format!("Erro ao copiar: {}", e)),
+            },
+            Err(e) => show_error(
+                &ui,
+                &format!("Erro ao acessar área de transferência: {}", e),
+            ),
+        }
+    });
+
+    // Open prompt callback
+    let ui_weak5 = ui.as_weak();
+    let editor_open = editor.clone();
+    let tabs_open = tabs.clone();
+    let recent_open = recent_files.clone();
+    ui.on_open_prompt(move || {
+        let ui = ui_weak5.unwrap();
+
+        println!("📂 Abrindo dialog para selecionar arquivo...");
+
+        // Start in the workspace folder, or next to the last file used
+        let mut dialog = FileDialog::new()
+            .add_filter("Arquivos de prompt", &PROMPT_EXTENSIONS)
+            .set_title("Abrir Prompt Salvo");
+        let workspace_dir = PathBuf::from(ui.get_workspace_path().trim());
+        let last_dir = recent_open
+            .borrow()
+            .entries()
+            .first()
+            .and_then(|entry| entry.path.parent().map(Path::to_path_buf));
+        if workspace_dir.is_dir() {
+            dialog = dialog.set_directory(workspace_dir);
+        } else if let Some(dir) = last_dir.filter(|dir| dir.is_dir()) {
+            dialog = dialog.set_directory(dir);
+        }
+
+        if let Some(path) = dialog.pick_file() {
+            println!("📄 Arquivo selecionado: {:?}", path);
+            if open_prompt_file(&ui, &editor_open, &tabs_open, &recent_open, &path) {
+                println!("�📝 Prompt carregado, campos preenchidos e preview atualizado!");
+            }
+        } else {
+            println!("❌ Nenhum arquivo selecionado.");
+        }
+    });
+
+    // Attach source files to the refactoring section
+    let ui_weak6 = ui.as_weak();
+    let undo_attach_code = undo.clone();
+    ui.on_attach_refactoring_file(move || {
+        let ui = ui_weak6.unwrap();
+
+        let range_text = ui.get_refactoring_line_range().to_string();
+        let line_range = if range_text.trim().is_empty() {
+            None
+        } else {
+            match parse_line_range(&range_text) {
+                Some(range) => Some(range),
+                None => {
+                    show_error(
+                        &ui,
+                        &format!("Intervalo de linhas inválido: '{}'", range_text),
+                    );
+                    return;
+                }
+            }
+        };
+
+        if let Some(paths) = FileDialog::new()
+            .set_title("Anexar Código para Refatoração")
+            .pick_files()
+        {
+            flush_typing(&ui, &undo_attach_code);
+            let mut files = model_to_strings(&ui.get_refactoring_files());
+            for path in paths {
+                let reference = CodeReference { path, line_range };
+                println!("📎 Código anexado: {}", reference);
+                files.push(reference.to_string());
+            }
+            ui.set_refactoring_files(strings_to_model(files));
+            ui.set_refactoring_line_range("".into());
+            record_undo_step(&ui, &undo_attach_code, "Anexar código");
+        } else {
+            println!("💭 Anexo cancelado pelo usuário");
+        }
+    });
+
+    // Remove an attached refactoring file
+    let ui_weak7 = ui.as_weak();
+    let undo_remove_code = undo.clone();
+    ui.on_remove_refactoring_file(move |index| {
+        let ui = ui_weak7.unwrap();
+        let mut files = model_to_strings(&ui.get_refactoring_files());
+        if index >= 0 && (index as usize) < files.len() {
+            flush_typing(&ui, &undo_remove_code);
+            let removed = files.remove(index as usize);
+            println!("🗑️ Anexo removido: {}", removed);
+            ui.set_refactoring_files(strings_to_model(files));
+            record_undo_step(&ui, &undo_remove_code, "Remover código anexado");
+        }
+    });
+
+    // Attach files to the auxiliary content
+    let ui_weak10 = ui.as_weak();
+    let undo_attach_files = undo.clone();
+    ui.on_attach_files(move || {
+        let ui = ui_weak10.unwrap();
+        if let Some(paths) = FileDialog::new()
+            .set_title("Anexar Arquivos ao Conteúdo Auxiliar")
+            .pick_files()
+        {
+            flush_typing(&ui, &undo_attach_files);
+            add_attachments(&ui, paths, false);
+            record_undo_step(&ui, &undo_attach_files, "Anexar arquivos");
+        } else {
+            println!("💭 Anexo cancelado pelo usuário");
+        }
+    });
+
+    // Attach a directory to the auxiliary content
+    let ui_weak11 = ui.as_weak();
+    let undo_attach_dir = undo.clone();
+    ui.on_attach_directory(move || {
+        let ui = ui_weak11.unwrap();
+        if let Some(path) = FileDialog::new()
+            .set_title("Anexar Pasta ao Conteúdo Auxiliar")
+            .pick_folder()
+        {
+            flush_typing(&ui, &undo_attach_dir);
+            add_attachments(&ui, vec![path], false);
+            record_undo_step(&ui, &undo_attach_dir, "Anexar pasta");
+        } else {
+            println!("💭 Anexo cancelado pelo usuário");
+        }
+    });
+
+    // Attach a glob pattern to the auxiliary content
+    let ui_weak12 = ui.as_weak();
+    let undo_attach_glob = undo.clone();
+    ui.on_attach_glob(move || {
+        let ui = ui_weak12.unwrap();
+        let pattern = ui.get_attachment_glob().trim().to_string();
+        if pattern.is_empty() {
+            show_error(&ui, "Informe um padrão glob, por exemplo src/**/*.rs");
+            return;
+        }
+        flush_typing(&ui, &undo_attach_glob);
+        add_attachments(&ui, vec![PathBuf::from(pattern)], true);
+        ui.set_attachment_glob("".into());
+        record_undo_step(&ui, &undo_attach_glob, "Anexar padrão glob");
+    });
+
+    // Remove an attachment
+    let ui_weak13 = ui.as_weak();
+    let undo_remove_attachment = undo.clone();
+    ui.on_remove_attachment(move |index| {
+        let ui = ui_weak13.unwrap();
+        let mut rows: Vec<AttachmentRow> = ui.get_attachments().iter().collect();
+        if index >= 0 && (index as usize) < rows.len() {
+            flush_typing(&ui, &undo_remove_attachment);
+            let removed = rows.remove(index as usize);
+            println!("🗑️ Anexo removido: {}", removed.label);
+            ui.set_attachments(ModelRc::new(VecModel::from(rows)));
+            record_undo_step(&ui, &undo_remove_attachment, "Remover anexo");
+        }
+    });
+
+    // Choose the repository used for Git context
+    let ui_weak14 = ui.as_weak();
+    ui.on_pick_git_repo(move || {
+        let ui = ui_weak14.unwrap();
+        if let Some(path) = FileDialog::new()
+            .set_title("Selecionar Repositório Git")
+            .pick_folder()
+        {
+            ui.set_git_repo_path(path.display().to_string().into());
+        } else {
+            println!("💭 Seleção cancelada pelo usuário");
+        }
+    });
+
+    // Import Git context into the chosen section
+    let ui_weak15 = ui.as_weak();
+    ui.on_import_git_context(move || {
+        let ui = ui_weak15.unwrap();
+
+        let repo_path = ui.get_git_repo_path().trim().to_string();
+        if repo_path.is_empty() {
+            show_error(&ui, "Informe o caminho do repositório Git");
+            return;
+        }
+
+        let target = SectionKind::ALL
+            .get(ui.get_git_target_section().max(0) as usize)
+            .copied()
+            .unwrap_or(SectionKind::Context);
+
+        match git_ingest::ingest(&PathBuf::from(&repo_path), &git_source_from_ui(&ui)) {
+            Ok(text) => {
+                append_to_section(&ui, target, &text);
+                println!("✅ Contexto do Git importado em: {}", target.title());
+            }
+            Err(e) => show_error(&ui, &format!("Erro ao ler o repositório Git: {}", e)),
+        }
+    });
+
+    // Choose the project folder for the repository map
+    let ui_weak16 = ui.as_weak();
+    ui.on_pick_repo_map_dir(move || {
+        let ui = ui_weak16.unwrap();
+        if let Some(path) = FileDialog::new()
+            .set_title("Selecionar Pasta do Projeto")
+            .pick_folder()
+        {
+            ui.set_repo_map_path(path.display().to_string().into());
+        } else {
+            println!("💭 Seleção cancelada pelo usuário");
+        }
+    });
+
+    // Generate the repository map into Auxiliary Content or Context
+    let ui_weak17 = ui.as_weak();
+    ui.on_generate_repo_map(move || {
+        let ui = ui_weak17.unwrap();
+
+        let root = ui.get_repo_map_path().trim().to_string();
+        if root.is_empty() {
+            show_error(&ui, "Informe a pasta do projeto");
+            return;
+        }
+
+        let options = RepoMapOptions {
+            token_budget: ui.get_repo_map_budget().max(1) as usize,
+            include_symbols: ui.get_repo_map_symbols(),
+            respect_gitignore: ui.get_repo_map_gitignore(),
+            exclude: Attachment::parse_patterns(&ui.get_repo_map_exclude()),
+        };
+        let target = if ui.get_repo_map_target() == 1 {
+            SectionKind::Context
+        } else {
+            SectionKind::AuxiliaryContent
+        };
+
+        match build_repo_map(&PathBuf::from(&root), &options) {
+            Ok(map) => {
+                append_to_section(&ui, target, &map);
+                println!("✅ Mapa do repositório inserido em: {}", target.title());
+            }
+            Err(e) => show_error(&ui, &format!("Erro ao gerar o mapa do repositório: {}", e)),
+        }
+    });
+
+    // Export the prompt series as numbered files
+    let ui_weak18 = ui.as_weak();
+    let prompt_data_series = prompt_data.clone();
+    ui.on_export_series_files(move || {
+        let ui = ui_weak18.unwrap();
+        let series = series_from_ui(&ui, &prompt_data_series.borrow());
+
+        if let Some(directory) = FileDialog::new()
+            .set_title("Exportar Série de Prompts")
+            .pick_folder()
+        {
+            match series.export_numbered(&directory, "prompt") {
+                Ok(paths) => {
+                    let report = format!(
+                        "{} arquivo(s) exportado(s) em {}",
+                        paths.len(),
+                        directory.display()
+                    );
+                    println!("✅ {}", report);
+                    ui.set_series_report(report.into());
+                }
+                Err(e) => show_error(&ui, &format!("Erro ao exportar série: {}", e)),
+            }
+        } else {
+            println!("💭 Exportação cancelada pelo usuário");
+        }
+    });
+
+    // Export the prompt series as a single JSONL file
+    let ui_weak19 = ui.as_weak();
+    let prompt_data_jsonl = prompt_data.clone();
+    ui.on_export_series_jsonl(move || {
+        let ui = ui_weak19.unwrap();
+        let series = series_from_ui(&ui, &prompt_data_jsonl.borrow());
+
+        if let Some(file_path) = FileDialog::new()
+            .set_title("Exportar Série de Prompts (JSONL)")
+            .set_file_name("prompt_series.jsonl")
+            .add_filter("JSON Lines", &["jsonl"])
+            .save_file()
+        {
+            match series.export_jsonl(&file_path) {
+                Ok(_) => {
+                    let report = format!(
+                        "{} prompt(s) exportado(s) em {}",
+                        series.prompts.len(),
+                        file_path.display()
+                    );
+                    println!("✅ {}", report);
+                    ui.set_series_report(report.into());
+                }
+                Err(e) => show_error(&ui, &format!("Erro ao exportar série: {}", e)),
+            }
+        } else {
+            println!("💭 Exportação cancelada pelo usuário");
+        }
+    });
+
+    // Prompt library, opened from the folder typed in the side panel
+    let library: Rc<RefCell<Option<PromptLibrary>>> = Rc::new(RefCell::new(None));
+
+    // Choose the library folder and open it
+    let ui_weak20 = ui.as_weak();
+    ui.on_pick_library_dir(move || {
+        let ui = ui_weak20.unwrap();
+        if let Some(path) = FileDialog::new()
+            .set_title("Selecionar Pasta da Biblioteca")
+            .pick_folder()
+        {
+            ui.set_library_path(path.display().to_string().into());
+            ui.invoke_refresh_library();
+        } else {
+            println!("💭 Seleção cancelada pelo usuário");
+        }
+    });
+
+    // (Re)open the library folder and index every prompt in it
+    let ui_weak21 = ui.as_weak();
+    let library_refresh = library.clone();
+    ui.on_refresh_library(move || {
+        let ui = ui_weak21.unwrap();
+
+        let root = ui.get_library_path().trim().to_string();
+        if root.is_empty() {
+            ui.set_library_status("Informe a pasta da biblioteca".into());
+            return;
+        }
+
+        match PromptLibrary::open(&PathBuf::from(&root)) {
+            Ok(opened) => {
+                println!(
+                    "📚 Biblioteca carregada: {} prompt(s) em {}",
+                    opened.entries().len(),
+                    root
+                );
+                show_library_results(&ui, &opened);
+                *library_refresh.borrow_mut() = Some(opened);
+            }
+            Err(e) => {
+                eprintln!("❌ Erro ao abrir biblioteca: {}", e);
+                ui.set_library_status(format!("Erro ao abrir biblioteca: {}", e).into());
+            }
+        }
+    });
+
+    // Filter the library by text, tag and favourites
+    let ui_weak22 = ui.as_weak();
+    let library_search = library.clone();
+    ui.on_search_library(move || {
+        let ui = ui_weak22.unwrap();
+        if let Some(library) = library_search.borrow().as_ref() {
+            show_library_results(&ui, library);
+        }
+    });
+
+    // Load a library prompt into the editor
+    let ui_weak23 = ui.as_weak();
+    let library_open = library.clone();
+    let editor_library = editor.clone();
+    let tabs_library = tabs.clone();
+    ui.on_open_library_prompt(move |file| {
+        let ui = ui_weak23.unwrap();
+        let library = library_open.borrow();
+        let Some(library) = library.as_ref() else {
+            return;
+        };
+
+        match editor_library.read(&library.path_of(&file)) {
+            Ok((content, disk)) => {
+                let path = library.path_of(&file);
+                if let Some(mut review) =
+                    editor_library.review_import(&tabs_library, &path, &content)
+                {
+                    if let Some(entry) = library.entry(&file) {
+                        if review.metadata.is_empty() {
+                            review.metadata.title = entry.title.clone();
+                            review.metadata.tags = entry.tags.clone();
+                            review.metadata.model = entry.model.clone();
+                        }
+                    }
+                    let pending = PendingImport::new(&path, review, disk, "Abrir da biblioteca");
+                    show_import_review(&ui, &editor_library, pending);
+                    return;
+                }
+
+                let Some(new_tab) = prepare_tab_for(&ui, &editor_library, &tabs_library, &path)
+                else {
+                    return;
+                };
+                {
+                    let mut data = editor_library.data.borrow_mut();
+                    load_prompt_into_editor(editor_library.import(&content), &ui, &mut data);
+
+                    // Files without a metadata header take their details from the index
+                    if let Some(entry) = library.entry(&file) {
+                        if data.metadata.is_empty() {
+                            data.metadata.title = entry.title.clone();
+                            data.metadata.tags = entry.tags.clone();
+                            data.metadata.model = entry.model.clone();
+                            apply_metadata_to_ui(&data.metadata, &ui);
+                        }
+                    }
+                }
+                finish_open(
+                    &ui,
+                    &editor_library,
+                    &tabs_library,
+                    &path,
+                    disk,
+                    new_tab,
+                    "Abrir da biblioteca",
+                );
+                println!("📝 Prompt da biblioteca carregado: {}", file);
+            }
+            Err(e) => {
+                eprintln!("❌ Erro ao ler prompt da biblioteca: {}", e);
+                ui.set_library_status(format!("Erro ao ler {}: {}", file, e).into());
+            }
+        }
+    });
+
+    // Mark or unmark a library prompt as favourite
+    let ui_weak24 = ui.as_weak();
+    let library_favorite = library.clone();
+    ui.on_toggle_library_favorite(move |file| {
+        let ui = ui_weak24.unwrap();
+        let mut library = library_favorite.borrow_mut();
+        let Some(library) = library.as_mut() else {
+            return;
+        };
+
+        let favorite = library.entry(&file).is_some_and(|entry| !entry.favorite);
+        match library.set_favorite(&file, favorite) {
+            Ok(_) => show_library_results(&ui, library),
+            Err(e) => {
+                eprintln!("❌ Erro ao atualizar favorito: {}", e);
+                ui.set_library_status(format!("Erro ao atualizar favorito: {}", e).into());
+            }
+        }
+    });
+
+    // Save the generated prompt as a new library entry
+    let ui_weak25 = ui.as_weak();
+    let library_save = library.clone();
+    let prompt_data_save = prompt_data.clone();
+    let current_document_library_save = current_document.clone();
+    let autosave_library_save = autosave.clone();
+    let editor_library_save = editor.clone();
+    ui.on_save_to_library(move || {
+        let ui = ui_weak25.unwrap();
+        if library_save.borrow().is_none() {
+            ui.invoke_refresh_library();
+        }
+        let mut library = library_save.borrow_mut();
+        let Some(library) = library.as_mut() else {
+            return;
+        };
+
+        ui.invoke_generate_prompt();
+        let mut data = prompt_data_save.borrow_mut();
+        if SectionKind::ALL
+            .iter()
+            .all(|kind| data.section_body(*kind).is_none())
+        {
+            ui.set_library_status("Nenhum campo foi preenchido ainda.".into());
+            return;
+        }
+
+        data.metadata.touch(now_seconds());
+        apply_metadata_to_ui(&data.metadata, &ui);
+        match library.add_prompt(&data) {
+            Ok(file) => {
+                println!("✅ Prompt salvo na biblioteca: {}", file);
+                show_library_results(&ui, library);
+                record_revision(&ui, &library.path_of(&file), &data);
+                let path = library.path_of(&file);
+                let disk = DiskFile {
+                    format: TextFormat {
+                        encoding: TextEncoding::Utf8,
+                        line_ending: LineEnding::Lf,
+                    },
+                    stamp: FileStamp::of(&path).ok(),
+                };
+                set_disk_file(&ui, &editor_library_save, disk);
+                *current_document_library_save.borrow_mut() = Some(path);
+                mark_clean(&ui, &autosave_library_save);
+            }
+            Err(e) => {
+                eprintln!("❌ Erro ao salvar na biblioteca: {}", e);
+                ui.set_library_status(format!("Erro ao salvar na biblioteca: {}", e).into());
+            }
+        }
+    });
+
+    // Compare two revisions of the current document section by section
+    let ui_weak26 = ui.as_weak();
+    let current_document_compare = current_document.clone();
+    ui.on_compare_revisions(move || {
+        let ui = ui_weak26.unwrap();
+        let Some(document) = current_document_compare.borrow().clone() else {
+            return;
+        };
+
+        let history = History::for_document(&document);
+        let revisions = history.revisions().unwrap_or_default();
+        let pick = |index: i32| revisions.get(usize::try_from(index).ok()?).cloned();
+        let (Some(from), Some(to)) = (pick(ui.get_history_from()), pick(ui.get_history_to()))
+        else {
+            return;
+        };
+
+        match (history.read(from.number), history.read(to.number)) {
+            (Ok(old), Ok(new)) => {
+                let diff = render_unified(&compare_documents(&old, &new));
+                ui.set_history_diff(
+                    format!("Revisão #{} → #{}\n\n{}", from.number, to.number, diff).into(),
+                );
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                eprintln!("❌ Erro ao ler revisão: {}", e);
+                ui.set_history_diff(format!("Erro ao ler revisão: {}", e).into());
+            }
+        }
+    });
+
+    // Load a revision of the current document into the editor
+    let ui_weak27 = ui.as_weak();
+    let prompt_data_restore = prompt_data.clone();
+    let current_document_restore = current_document.clone();
+    let undo_restore = undo.clone();
+    ui.on_restore_revision(move |index| {
+        let ui = ui_weak27.unwrap();
+        let Some(document) = current_document_restore.borrow().clone() else {
+            return;
+        };
+
+        let history = History::for_document(&document);
+        let Some(revision) = history
+            .revisions()
+            .unwrap_or_default()
+            .into_iter()
+            .nth(usize::try_from(index).unwrap_or(usize::MAX))
+        else {
+            return;
+        };
+
+        match history.read(revision.number) {
+            Ok(content) => {
+                flush_typing(&ui, &undo_restore);
+                load_prompt_into_editor(
+                    parse_prompt(&content),
+                    &ui,
+                    &mut prompt_data_restore.borrow_mut(),
+                );
+                record_undo_step(
+                    &ui,
+                    &undo_restore,
+                    &format!("Restaurar revisão #{}", revision.number),
+                );
+                ui.set_history_note(format!("Restaurada a revisão #{}", revision.number).into());
+                println!("↩️ Revisão #{} restaurada no editor", revision.number);
+            }
+            Err(e) => {
+                eprintln!("❌ Erro ao ler revisão: {}", e);
+                ui.set_history_diff(format!("Erro ao ler revisão: {}", e).into());
+            }
+        }
+    });
+
+    // Choose the two documents of the side-by-side comparison
+    let ui_weak28 = ui.as_weak();
+    ui.on_pick_compare_old(move || {
+        let ui = ui_weak28.unwrap();
+        if let Some(path) = pick_compare_file("Selecionar Arquivo Antigo") {
+            ui.set_compare_old_path(path.display().to_string().into());
+        }
+    });
+
+    let ui_weak29 = ui.as_weak();
+    ui.on_pick_compare_new(move || {
+        let ui = ui_weak29.unwrap();
+        if let Some(path) = pick_compare_file("Selecionar Arquivo Novo") {
+            ui.set_compare_new_path(path.display().to_string().into());
+        }
+    });
+
+    // Compare the two documents section by section
+    let ui_weak30 = ui.as_weak();
+    ui.on_compare_files(move || {
+        let ui = ui_weak30.unwrap();
+        let old_path = ui.get_compare_old_path().trim().to_string();
+        let new_path = ui.get_compare_new_path().trim().to_string();
+        if old_path.is_empty() || new_path.is_empty() {
+            ui.set_compare_summary("Escolha os dois arquivos a comparar".into());
+            return;
+        }
+
+        let read = |path: &str| {
+            read_text_file(Path::new(path), None)
+                .map(|decoded| decoded.text)
+                .map_err(|e| format!("Erro ao ler {}: {}", path, e))
+        };
+        let (old, new) = match (read(&old_path), read(&new_path)) {
+            (Ok(old), Ok(new)) => (old, new),
+            (Err(message), _) | (_, Err(message)) => {
+                eprintln!("❌ {}", message);
+                ui.set_compare_summary(message.into());
+                ui.set_compare_rows(ModelRc::new(VecModel::from(Vec::<DiffRow>::new())));
+                return;
+            }
+        };
+
+        let comparisons = compare_documents(&old, &new);
+        let changed = comparisons
+            .iter()
+            .filter(|comparison| comparison.has_differences())
+            .count();
+        let rows: Vec<DiffRow> = comparisons
+            .iter()
+            .map(|comparison| DiffRow {
+                title: comparison.title().into(),
+                status: comparison.status().into(),
+                old_text: comparison.side_text(DiffSide::Old).into(),
+                new_text: comparison.side_text(DiffSide::New).into(),
+            })
+            .collect();
+        ui.set_compare_summary(
+            if changed == 0 {
+                "Nenhuma diferença entre os documentos.".to_string()
+            } else {
+                format!("{} de {} seções com diferenças", changed, comparisons.len())
+            }
+            .into(),
+        );
+        ui.set_compare_rows(ModelRc::new(VecModel::from(rows)));
+    });
+
+    // Choose the base, our or their version of a three-way merge
+    let ui_weak31 = ui.as_weak();
+    ui.on_pick_merge_file(move |which| {
+        let ui = ui_weak31.unwrap();
+        let title = match which {
+            0 => "Selecionar Versão Base",
+            1 => "Selecionar Nossa Versão",
+            _ => "Selecionar Versão Deles",
+        };
+        if let Some(path) = pick_compare_file(title) {
+            let path = SharedString::from(path.display().to_string());
+            match which {
+                0 => ui.set_merge_base_path(path),
+                1 => ui.set_merge_ours_path(path),
+                _ => ui.set_merge_theirs_path(path),
+            }
+        }
+    });
+
+    // Merge the three versions and list the sections in conflict
+    let merge_result: Rc<RefCell<Option<MergeResult>>> = Rc::new(RefCell::new(None));
+    let ui_weak32 = ui.as_weak();
+    let merge_result_merge = merge_result.clone();
+    ui.on_merge_files(move || {
+        let ui = ui_weak32.unwrap();
+        let paths = [
+            ui.get_merge_base_path().trim().to_string(),
+            ui.get_merge_ours_path().trim().to_string(),
+            ui.get_merge_theirs_path().trim().to_string(),
+        ];
+        if paths.iter().any(|path| path.is_empty()) {
+            ui.set_merge_status("Escolha a base e as duas versões a mesclar".into());
+            return;
+        }
+
+        let mut texts = Vec::new();
+        for path in &paths {
+            match read_text_file(Path::new(path), None) {
+                Ok(decoded) => texts.push(decoded.text),
+                Err(e) => {
+                    eprintln!("❌ Erro ao ler {}: {}", path, e);
+                    ui.set_merge_status(format!("Erro ao ler {}: {}", path, e).into());
+                    return;
+                }
+            }
+        }
+
+        let result = merge_documents(&texts[0], &texts[1], &texts[2]);
+        let rows: Vec<MergeConflictRow> = result
+            .sections
+            .iter()
+            .enumerate()
+            .filter_map(|(index, section)| match &section.merge {
+                SectionMerge::Conflict { base, ours, theirs } => Some(MergeConflictRow {
+                    section: index as i32,
+                    title: section.title().into(),
+                    base: base.clone().unwrap_or_default().into(),
+                    ours: ours.clone().unwrap_or_default().into(),
+                    theirs: theirs.clone().unwrap_or_default().into(),
+                    result: "".into(),
+                    resolved: false,
+                }),
+                SectionMerge::Clean(_) => None,
+            })
+            .collect();
+
+        ui.set_merge_status(
+            if rows.is_empty() {
+                format!(
+                    "✅ {} seções mescladas sem conflitos",
+                    result.sections.len()
+                )
+            } else {
+                format!(
+                    "⚠️ {} de {} seções em conflito",
+                    rows.len(),
+                    result.sections.len()
+                )
+            }
+            .into(),
+        );
+        ui.set_merge_conflicts(ModelRc::new(VecModel::from(rows)));
+        *merge_result_merge.borrow_mut() = Some(result);
+    });
 
-use models::{
-    auxiliary_content::AuxiliaryContent, context::Context, few_shot::FewShot, guidance::Guidance,
-    limitations::Limitations, main_content::MainContent, output_format::OutputFormat,
-    refactoring::Refactoring, tests::Tests,
-};
-use services::{file_service::save_prompt_to_specific_path, prompt_generator::PromptData};
+    // Record the text chosen for a conflicting section
+    let ui_weak33 = ui.as_weak();
+    ui.on_resolve_merge_conflict(move |index, text| {
+        let ui = ui_weak33.unwrap();
+        let conflicts = ui.get_merge_conflicts();
+        let Some(mut row) = conflicts.row_data(index as usize) else {
+            return;
+        };
+        row.result = text;
+        row.resolved = true;
+        conflicts.set_row_data(index as usize, row);
+    });
 
-slint::include_modules!();
+    // Load the merged document into the editor once every conflict is resolved
+    let ui_weak34 = ui.as_weak();
+    let prompt_data_merge = prompt_data.clone();
+    let undo_merge = undo.clone();
+    ui.on_apply_merge(move || {
+        let ui = ui_weak34.unwrap();
+        let Some(mut result) = merge_result.borrow().clone() else {
+            ui.set_merge_status("Mescle as versões antes de aplicar".into());
+            return;
+        };
 
-// Parse saved prompt content and populate UI fields
-fn parse_prompt_content(content: &str, ui: &AppWindow, data: &mut PromptData) {
-    let lines: Vec<&str> = content.lines().collect();
-    let mut current_section = "";
-    let mut section_content = String::new();
-
-    // Check if the file has section headers (structured format)
-    let has_headers = content.contains("## ");
-
-    if has_headers {
-        // Parse structured format with headers
-        for line in lines {
-            let line = line.trim();
-
-            // Check for section headers
-            if line.starts_with("## Few-shot") {
-                save_current_section(current_section, &section_content, ui, data);
-                current_section = "few_shot";
-                section_content.clear();
-            } else if line.starts_with("## Contexto") {
-                save_current_section(current_section, &section_content, ui, data);
-                current_section = "context";
-                section_content.clear();
-            } else if line.starts_with("## Conteúdo Principal") {
-                save_current_section(current_section, &section_content, ui, data);
-                current_section = "main_content";
-                section_content.clear();
-            } else if line.starts_with("## Conteúdo Auxiliar") {
-                save_current_section(current_section, &section_content, ui, data);
-                current_section = "auxiliary_content";
-                section_content.clear();
-            } else if line.starts_with("## Limitações") {
-                save_current_section(current_section, &section_content, ui, data);
-                current_section = "limitations";
-                section_content.clear();
-            } else if line.starts_with("## Refatoração") {
-                save_current_section(current_section, &section_content, ui, data);
-                current_section = "refactoring";
-                section_content.clear();
-            } else if line.starts_with("## Orientações") {
-                save_current_section(current_section, &section_content, ui, data);
-                current_section = "guidance";
-                section_content.clear();
-            } else if line.starts_with("## Testes") {
-                save_current_section(current_section, &section_content, ui, data);
-                current_section = "tests";
-                section_content.clear();
-            } else if line.starts_with("## Formato de Saída") {
-                save_current_section(current_section, &section_content, ui, data);
-                current_section = "output_format";
-                section_content.clear();
-            } else if line.starts_with("---") || line.starts_with("*Prompt gerado") {
-                // Skip separators and footer
-                break;
-            } else if !line.is_empty() && !current_section.is_empty() {
-                // Add content to current section
-                if !section_content.is_empty() {
-                    section_content.push('\n');
-                }
-                section_content.push_str(line);
-            }
+        let conflicts = ui.get_merge_conflicts();
+        let pending = conflicts.iter().filter(|row| !row.resolved).count();
+        if pending > 0 {
+            ui.set_merge_status(
+                format!(
+                    "Resolva os {} conflito(s) restantes antes de aplicar",
+                    pending
+                )
+                .into(),
+            );
+            return;
+        }
+        for row in conflicts.iter() {
+            result.resolve(row.section as usize, &row.result);
         }
 
-        // Save the last section
-        save_current_section(current_section, &section_content, ui, data);
-    } else {
-        // Parse simple format - try to intelligently distribute content
-        let content = content.trim();
-        if !content.is_empty() {
-            println!("📝 Carregando formato simples - tentando distribuir inteligentemente");
-            parse_simple_format(content, ui, data);
+        flush_typing(&ui, &undo_merge);
+        load_prompt_into_editor(
+            parse_prompt(&result.to_document()),
+            &ui,
+            &mut prompt_data_merge.borrow_mut(),
+        );
+        record_undo_step(&ui, &undo_merge, "Aplicar mesclagem");
+        ui.set_merge_status("✅ Mesclagem aplicada no editor".into());
+    });
+
+    // Refresh the unsaved-changes indicator as the editor is typed in
+    let ui_weak35 = ui.as_weak();
+    let editor_dirty = editor.clone();
+    let tabs_dirty = tabs.clone();
+    let dirty_timer = Timer::default();
+    dirty_timer.start(TimerMode::Repeated, Duration::from_secs(1), move || {
+        if let Some(ui) = ui_weak35.upgrade() {
+            let dirty = editor_dirty
+                .autosave
+                .borrow()
+                .is_dirty(&editor_snapshot(&ui));
+            ui.set_document_dirty(dirty);
+
+            // Saves and opens change the title and flag of the shown tab
+            let mut tabs = tabs_dirty.borrow_mut();
+            let (path, was_dirty) = (tabs.active_tab().path.clone(), tabs.active_tab().dirty);
+            sync_active_tab(&ui, &editor_dirty, &mut tabs);
+            if tabs.active_tab().path != path || dirty != was_dirty {
+                show_tabs(&ui, &tabs);
+            }
         }
-    }
-}
+    });
 
-// Parse simple format files by trying to intelligently distribute content
-fn parse_simple_format(content: &str, ui: &AppWindow, data: &mut PromptData) {
-    let paragraphs: Vec<&str> = content
-        .split("\n\n")
-        .filter(|p| !p.trim().is_empty())
-        .collect();
+    // Periodically write unsaved work to the recovery folder, and the open tabs
+    // to the session restored on the next launch
+    let ui_weak36 = ui.as_weak();
+    let autosave_timer_state = autosave.clone();
+    let current_document_autosave = current_document.clone();
+    let editor_session = editor.clone();
+    let tabs_session = tabs.clone();
+    let session_store_timer = session_store.clone();
+    let autosave_timer = Rc::new(Timer::default());
+    autosave_timer.start(
+        TimerMode::Repeated,
+        Duration::from_secs(settings.borrow().autosave_seconds),
+        move || {
+            let Some(ui) = ui_weak36.upgrade() else {
+                return;
+            };
+            match autosave_timer_state.borrow_mut().autosave(
+                &editor_snapshot(&ui),
+                current_document_autosave.borrow().as_deref(),
+                now_seconds(),
+            ) {
+                Ok(true) => println!("💾 Rascunho de recuperação atualizado"),
+                Ok(false) => {}
+                Err(e) => show_error(
+                    &ui,
+                    &format!("Erro ao salvar rascunho de recuperação: {}", e),
+                ),
+            }
+            save_session(
+                &ui,
+                session_store_timer.as_ref(),
+                &session_of(&ui, &editor_session, &tabs_session.borrow(), false),
+            );
+        },
+    );
 
-    if paragraphs.len() == 1 {
-        // Single paragraph - put in main content
-        let text = paragraphs[0].trim();
-        println!("📝 Conteúdo único - carregando como conteúdo principal");
-        ui.set_main_content_text(text.into());
-        let mut main_content = MainContent::new();
-        main_content.instructions = text.to_string();
-        data.main_content = Some(main_content);
-    } else {
-        // Multiple paragraphs - distribute intelligently
-        println!(
-            "📝 Múltiplos parágrafos ({}) - distribuindo inteligentemente",
-            paragraphs.len()
+    // Offer to save unsaved changes before the window closes
+    let ui_weak37 = ui.as_weak();
+    let editor_close = editor.clone();
+    let tabs_close = tabs.clone();
+    let session_store_close = session_store.clone();
+    ui.window().on_close_requested(move || {
+        let ui = ui_weak37.unwrap();
+        let store = session_store_close.as_ref();
+        ui.set_document_dirty(
+            editor_close
+                .autosave
+                .borrow()
+                .is_dirty(&editor_snapshot(&ui)),
         );
+        sync_active_tab(&ui, &editor_close, &mut tabs_close.borrow_mut());
 
-        for (i, paragraph) in paragraphs.iter().enumerate() {
-            let text = paragraph.trim();
-            if text.is_empty() {
-                continue;
-            }
+        let dirty: Vec<usize> = tabs_close
+            .borrow()
+            .tabs()
+            .iter()
+            .enumerate()
+            .filter(|(_, tab)| tab.dirty)
+            .map(|(index, _)| index)
+            .collect();
+        if dirty.is_empty() {
+            save_session(
+                &ui,
+                store,
+                &session_of(&ui, &editor_close, &tabs_close.borrow(), false),
+            );
+            return CloseRequestResponse::HideWindow;
+        }
 
-            // Distribute based on position and content
-            match i {
-                0 => {
-                    // First paragraph - likely context or introduction
-                    if text.len() > 200
-                        && (text.contains("você é")
-                            || text.contains("assistente")
-                            || text.contains("especializado"))
+        let description = if dirty.len() == 1 {
+            "Deseja salvar as alterações antes de fechar?".to_string()
+        } else {
+            format!(
+                "{} abas têm alterações não salvas. Deseja salvá-las antes de fechar?",
+                dirty.len()
+            )
+        };
+        let answer = MessageDialog::new()
+            .set_level(MessageLevel::Warning)
+            .set_title("Alterações não salvas")
+            .set_description(description)
+            .set_buttons(MessageButtons::YesNoCancel)
+            .show();
+        match answer {
+            MessageDialogResult::Yes => {
+                for index in dirty {
+                    select_tab(&ui, &editor_close, &tabs_close, index);
+                    ui.invoke_save_prompt();
+                    // Closing only once every save went through
+                    if editor_close
+                        .autosave
+                        .borrow()
+                        .is_dirty(&editor_snapshot(&ui))
                     {
-                        // Looks like context
-                        println!("📝 Parágrafo 1 -> Contexto (detecção automática)");
-                        ui.set_context_text(text.into());
-                        let mut context = Context::new();
-                        context.description = text.to_string();
-                        data.context = Some(context);
-                    } else {
-                        // Short or different content - main content
-                        println!("📝 Parágrafo 1 -> Conteúdo Principal");
-                        ui.set_main_content_text(text.into());
-                        let mut main_content = MainContent::new();
-                        main_content.instructions = text.to_string();
-                        data.main_content = Some(main_content);
-                    }
-                }
-                1 => {
-                    // Second paragraph
-                    if data.main_content.is_none() {
-                        // If main content wasn't set, use this
-                        println!("📝 Parágrafo 2 -> Conteúdo Principal");
-                        ui.set_main_content_text(text.into());
-                        let mut main_content = MainContent::new();
-                        main_content.instructions = text.to_string();
-                        data.main_content = Some(main_content);
-                    } else {
-                        // Use as auxiliary content
-                        println!("📝 Parágrafo 2 -> Conteúdo Auxiliar");
-                        ui.set_auxiliary_content_text(text.into());
-                        let mut auxiliary = AuxiliaryContent::new();
-                        auxiliary.data = text.to_string();
-                        data.auxiliary_content = Some(auxiliary);
+                        return CloseRequestResponse::KeepWindowShown;
                     }
+                    ui.set_document_dirty(false);
+                    sync_active_tab(&ui, &editor_close, &mut tabs_close.borrow_mut());
                 }
-                2 => {
-                    // Third paragraph - auxiliary or guidance
-                    if text.contains("sempre") || text.contains("quando") || text.contains("use") {
-                        println!("📝 Parágrafo 3 -> Orientações (detecção automática)");
-                        ui.set_guidance_text(text.into());
-                        let mut guidance = Guidance::new();
-                        guidance.text = text.to_string();
-                        data.guidance = Some(guidance);
-                    } else {
-                        println!("📝 Parágrafo 3 -> Conteúdo Auxiliar");
-                        ui.set_auxiliary_content_text(text.into());
-                        let mut auxiliary = AuxiliaryContent::new();
-                        auxiliary.data = text.to_string();
-                        data.auxiliary_content = Some(auxiliary);
-                    }
-                }
-                _ => {
-                    // Additional paragraphs - put in auxiliary content
-                    println!("📝 Parágrafo {} -> Conteúdo Auxiliar", i + 1);
-                    let current_aux = ui.get_auxiliary_content_text().to_string();
-                    let new_content = if current_aux.is_empty() {
-                        text.to_string()
-                    } else {
-                        format!("{}\n\n{}", current_aux, text)
-                    };
-                    ui.set_auxiliary_content_text(new_content.clone().into());
-                    let mut auxiliary = AuxiliaryContent::new();
-                    auxiliary.data = new_content;
-                    data.auxiliary_content = Some(auxiliary);
+                save_session(
+                    &ui,
+                    store,
+                    &session_of(&ui, &editor_close, &tabs_close.borrow(), false),
+                );
+                CloseRequestResponse::HideWindow
+            }
+            MessageDialogResult::No => {
+                let autosave = editor_close.autosave.borrow();
+                if let Some(Err(e)) = autosave.store().map(RecoveryStore::clear) {
+                    eprintln!("❌ Erro ao remover rascunho de recuperação: {}", e);
                 }
+                save_session(
+                    &ui,
+                    store,
+                    &session_of(&ui, &editor_close, &tabs_close.borrow(), true),
+                );
+                CloseRequestResponse::HideWindow
             }
+            _ => CloseRequestResponse::KeepWindowShown,
         }
-    }
-}
+    });
 
-// Helper function to save section content to UI and data
-fn save_current_section(section: &str, content: &str, ui: &AppWindow, data: &mut PromptData) {
-    let trimmed_content = content.trim();
-    if trimmed_content.is_empty() {
-        return;
-    }
+    // Open an empty document in a new tab (Ctrl+T)
+    let ui_weak43 = ui.as_weak();
+    let editor_new_tab = editor.clone();
+    let tabs_new = tabs.clone();
+    ui.on_new_tab(move || {
+        let ui = ui_weak43.unwrap();
+        sync_active_tab(&ui, &editor_new_tab, &mut tabs_new.borrow_mut());
+        let shown = stash_editor(&ui, &editor_new_tab);
+        tabs_new.borrow_mut().open(None, shown);
+        show_tab_session(&ui, &editor_new_tab, None, empty_tab_session());
+        show_tabs(&ui, &tabs_new.borrow());
+    });
 
-    match section {
-        "few_shot" => {
-            ui.set_few_shot_text(trimmed_content.into());
-            let mut few_shot = FewShot::new();
-            few_shot.content = trimmed_content.to_string();
-            data.few_shot = Some(few_shot);
-        }
-        "context" => {
-            ui.set_context_text(trimmed_content.into());
-            let mut context = Context::new();
-            context.description = trimmed_content.to_string();
-            data.context = Some(context);
-        }
-        "main_content" => {
-            ui.set_main_content_text(trimmed_content.into());
-            let mut main_content = MainContent::new();
-            main_content.instructions = trimmed_content.to_string();
-            data.main_content = Some(main_content);
-        }
-        "auxiliary_content" => {
-            ui.set_auxiliary_content_text(trimmed_content.into());
-            let mut auxiliary = AuxiliaryContent::new();
-            auxiliary.data = trimmed_content.to_string();
-            data.auxiliary_content = Some(auxiliary);
+    // Show the clicked tab
+    let ui_weak44 = ui.as_weak();
+    let editor_select = editor.clone();
+    let tabs_select = tabs.clone();
+    ui.on_select_tab(move |index| {
+        let ui = ui_weak44.unwrap();
+        if let Ok(index) = usize::try_from(index) {
+            select_tab(&ui, &editor_select, &tabs_select, index);
         }
-        "limitations" => {
-            ui.set_limitations_text(trimmed_content.into());
-            let mut limitations = Limitations::new();
-            limitations.text = trimmed_content.to_string();
-            data.limitations = Some(limitations);
+    });
+
+    // Switch to the next or previous tab (Ctrl+Tab, Ctrl+Shift+Tab)
+    let ui_weak45 = ui.as_weak();
+    let editor_next = editor.clone();
+    let tabs_next = tabs.clone();
+    ui.on_next_tab(move || {
+        let ui = ui_weak45.unwrap();
+        let index = tabs_next.borrow().next_index();
+        select_tab(&ui, &editor_next, &tabs_next, index);
+    });
+
+    let ui_weak46 = ui.as_weak();
+    let editor_previous = editor.clone();
+    let tabs_previous = tabs.clone();
+    ui.on_previous_tab(move || {
+        let ui = ui_weak46.unwrap();
+        let index = tabs_previous.borrow().previous_index();
+        select_tab(&ui, &editor_previous, &tabs_previous, index);
+    });
+
+    // Close a tab (Ctrl+W), asking first when it has unsaved changes
+    let ui_weak47 = ui.as_weak();
+    let editor_close_tab = editor.clone();
+    let tabs_close_tab = tabs.clone();
+    ui.on_close_tab(move |index| {
+        let ui = ui_weak47.unwrap();
+        let Ok(index) = usize::try_from(index) else {
+            return;
+        };
+        sync_active_tab(&ui, &editor_close_tab, &mut tabs_close_tab.borrow_mut());
+        let Some((title, dirty)) = tabs_close_tab
+            .borrow()
+            .tabs()
+            .get(index)
+            .map(|tab| (tab.title(), tab.dirty))
+        else {
+            return;
+        };
+        if dirty {
+            let answer = MessageDialog::new()
+                .set_level(MessageLevel::Warning)
+                .set_title("Fechar aba")
+                .set_description(format!(
+                    "\"{}\" tem alterações não salvas. Fechar e descartar as alterações?",
+                    title
+                ))
+                .set_buttons(MessageButtons::YesNo)
+                .show();
+            if answer != MessageDialogResult::Yes {
+                return;
+            }
         }
-        "refactoring" => {
-            ui.set_refactoring_text(trimmed_content.into());
-            let mut refactoring = Refactoring::new();
-            refactoring.text = trimmed_content.to_string();
-            data.refactoring = Some(refactoring);
+
+        let shown = index == tabs_close_tab.borrow().active();
+        let closed = tabs_close_tab.borrow_mut().close(index);
+        match closed {
+            Some(Closed::Shown(contents)) => {
+                let path = tabs_close_tab.borrow().active_tab().path.clone();
+                show_tab_session(
+                    &ui,
+                    &editor_close_tab,
+                    path,
+                    contents.unwrap_or_else(empty_tab_session),
+                );
+            }
+            Some(Closed::Background) => {}
+            // The last tab is replaced by an empty document
+            None => {
+                *tabs_close_tab.borrow_mut() = Tabs::new(None);
+                show_tab_session(&ui, &editor_close_tab, None, empty_tab_session());
+            }
         }
-        "guidance" => {
-            ui.set_guidance_text(trimmed_content.into());
-            let mut guidance = Guidance::new();
-            guidance.text = trimmed_content.to_string();
-            data.guidance = Some(guidance);
+        // Unsaved work of a closed tab is not offered for recovery
+        if shown {
+            let autosave = editor_close_tab.autosave.borrow();
+            if let Some(Err(e)) = autosave.store().map(RecoveryStore::clear) {
+                show_error(
+                    &ui,
+                    &format!("Erro ao remover rascunho de recuperação: {}", e),
+                );
+            }
         }
-        "tests" => {
-            ui.set_tests_text(trimmed_content.into());
-            let mut tests = Tests::new();
-            tests.text = trimmed_content.to_string();
-            data.tests = Some(tests);
+        show_tabs(&ui, &tabs_close_tab.borrow());
+        println!("📑 Aba fechada: {}", title);
+    });
+
+    // Close every tab except the shown one
+    let ui_weak48 = ui.as_weak();
+    let editor_close_others = editor.clone();
+    let tabs_close_others = tabs.clone();
+    ui.on_close_other_tabs(move || {
+        let ui = ui_weak48.unwrap();
+        let (active, unsaved) = {
+            let tabs = tabs_close_others.borrow();
+            let unsaved = tabs
+                .tabs()
+                .iter()
+                .enumerate()
+                .filter(|(index, tab)| *index != tabs.active() && tab.dirty)
+                .count();
+            (tabs.active(), unsaved)
+        };
+        if unsaved > 0 {
+            let answer = MessageDialog::new()
+                .set_level(MessageLevel::Warning)
+                .set_title("Fechar outras abas")
+                .set_description(format!(
+                    "{} aba(s) com alterações não salvas serão fechadas. Descartar as alterações?",
+                    unsaved
+                ))
+                .set_buttons(MessageButtons::YesNo)
+                .show();
+            if answer != MessageDialogResult::Yes {
+                return;
+            }
         }
-        "output_format" => {
-            ui.set_output_format_text(trimmed_content.into());
-            let mut output_format = OutputFormat::new();
-            output_format.text = trimmed_content.to_string();
-            data.output_format = Some(output_format);
+
+        tabs_close_others.borrow_mut().close_others(active);
+        sync_active_tab(
+            &ui,
+            &editor_close_others,
+            &mut tabs_close_others.borrow_mut(),
+        );
+        show_tabs(&ui, &tabs_close_others.borrow());
+    });
+
+    // Open a file of the recent files menu; missing files leave the list
+    let ui_weak49 = ui.as_weak();
+    let editor_recent = editor.clone();
+    let tabs_recent = tabs.clone();
+    let recent_open_file = recent_files.clone();
+    ui.on_open_recent_file(move |path| {
+        let ui = ui_weak49.unwrap();
+        let path = PathBuf::from(path.as_str());
+        if !path.is_file() {
+            show_error(
+                &ui,
+                &format!("Arquivo recente não encontrado: {}", path.display()),
+            );
+            recent_open_file.borrow_mut().remove(&path);
+            store_recent_files(&ui, &recent_open_file.borrow());
+            return;
         }
-        _ => {}
-    }
-}
+        open_prompt_file(&ui, &editor_recent, &tabs_recent, &recent_open_file, &path);
+    });
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let ui = AppWindow::new()?;
+    // Pin or unpin a recent file
+    let ui_weak50 = ui.as_weak();
+    let recent_pin = recent_files.clone();
+    ui.on_toggle_recent_pin(move |path| {
+        let ui = ui_weak50.unwrap();
+        let path = PathBuf::from(path.as_str());
+        let pinned = recent_pin
+            .borrow()
+            .entries()
+            .iter()
+            .any(|entry| entry.path == path && entry.pinned);
+        recent_pin.borrow_mut().set_pinned(&path, !pinned);
+        store_recent_files(&ui, &recent_pin.borrow());
+    });
 
-    // Initialize prompt data in a shared state
-    let prompt_data = Rc::new(RefCell::new(PromptData::new()));
+    // Forget the recent files that are not pinned
+    let ui_weak51 = ui.as_weak();
+    let recent_clear = recent_files.clone();
+    ui.on_clear_recent_files(move || {
+        let ui = ui_weak51.unwrap();
+        recent_clear.borrow_mut().clear_unpinned();
+        store_recent_files(&ui, &recent_clear.borrow());
+    });
 
-    // Set up callbacks for UI events
-    let ui_weak = ui.as_weak();
-    let prompt_data_clone = prompt_data.clone();
+    // Workspace folder shown as a tree in the sidebar
+    let workspace: Rc<RefCell<Option<Workspace>>> = Rc::new(RefCell::new(None));
 
-    // Generate prompt callback
-    ui.on_generate_prompt(move || {
-        let ui = ui_weak.unwrap();
-        let mut data = prompt_data_clone.borrow_mut();
+    // Choose the workspace folder
+    let ui_weak52 = ui.as_weak();
+    ui.on_pick_workspace_dir(move || {
+        let ui = ui_weak52.unwrap();
+        if let Some(path) = FileDialog::new()
+            .set_title("Selecionar Pasta de Trabalho")
+            .pick_folder()
+        {
+            ui.set_workspace_path(path.display().to_string().into());
+            ui.invoke_refresh_workspace();
+        } else {
+            println!("💭 Seleção cancelada pelo usuário");
+        }
+    });
 
-        // Clear previous data
-        *data = PromptData::new();
+    // (Re)scan the workspace folder
+    let ui_weak53 = ui.as_weak();
+    let workspace_refresh = workspace.clone();
+    ui.on_refresh_workspace(move || {
+        let ui = ui_weak53.unwrap();
 
-        println!("🔄 Gerando preview do prompt...");
+        let root = ui.get_workspace_path().trim().to_string();
+        if root.is_empty() {
+            ui.set_workspace_rows(ModelRc::new(VecModel::from(Vec::<WorkspaceRow>::new())));
+            ui.set_workspace_status("Informe a pasta de trabalho".into());
+            return;
+        }
 
-        // Collect all field values first
-        let few_shot_val = ui.get_few_shot_text().to_string();
-        let context_val = ui.get_context_text().to_string();
-        let main_content_val = ui.get_main_content_text().to_string();
-        let auxiliary_val = ui.get_auxiliary_content_text().to_string();
-        let limitations_val = ui.get_limitations_text().to_string();
-        let refactoring_val = ui.get_refactoring_text().to_string();
-        let guidance_val = ui.get_guidance_text().to_string();
-        let tests_val = ui.get_tests_text().to_string();
-        let output_format_val = ui.get_output_format_text().to_string();
+        // Collapsed folders are kept when the same folder is scanned again
+        let mut current = workspace_refresh.borrow_mut();
+        let result = match current.as_mut() {
+            Some(opened) if opened.root() == Path::new(&root) => opened.refresh(),
+            _ => Workspace::open(Path::new(&root)).map(|opened| *current = Some(opened)),
+        };
+        match (result, current.as_ref()) {
+            (Ok(()), Some(opened)) => {
+                println!(
+                    "🗂️ Pasta de trabalho carregada: {} item(ns) em {}",
+                    opened.nodes().len(),
+                    root
+                );
+                show_workspace(&ui, opened);
+            }
+            (Err(e), _) => {
+                eprintln!("❌ Erro ao abrir pasta de trabalho: {}", e);
+                ui.set_workspace_status(format!("Erro ao abrir pasta: {}", e).into());
+            }
+            (Ok(()), None) => {}
+        }
+    });
 
-        // Debug: Check if UI values are being read
-        println!("📝 Valores dos campos:");
-        println!("  Few-shot: '{}'", few_shot_val);
-        println!("  Context: '{}'", context_val);
-        println!("  Main content: '{}'", main_content_val);
-        println!("  Auxiliary: '{}'", auxiliary_val);
-        println!("  Limitations: '{}'", limitations_val);
-        println!("  Refactoring: '{}'", refactoring_val);
-        println!("  Guidance: '{}'", guidance_val);
-        println!("  Tests: '{}'", tests_val);
-        println!("  Output format: '{}'", output_format_val);
+    // Filter the workspace tree by file name
+    let ui_weak54 = ui.as_weak();
+    let workspace_filter = workspace.clone();
+    ui.on_filter_workspace(move || {
+        let ui = ui_weak54.unwrap();
+        if let Some(opened) = workspace_filter.borrow().as_ref() {
+            show_workspace(&ui, opened);
+        }
+    });
 
-        // Update prompt data with UI values
-        if !few_shot_val.trim().is_empty() {
-            println!("✅ Adicionando Few-shot");
-            let mut few_shot = FewShot::new();
-            few_shot.content = few_shot_val;
-            data.few_shot = Some(few_shot);
+    // Expand or collapse a workspace folder
+    let ui_weak55 = ui.as_weak();
+    let workspace_toggle = workspace.clone();
+    ui.on_toggle_workspace_folder(move |path| {
+        let ui = ui_weak55.unwrap();
+        if let Some(opened) = workspace_toggle.borrow_mut().as_mut() {
+            opened.toggle(Path::new(path.as_str()));
+            show_workspace(&ui, opened);
         }
+    });
 
-        if !context_val.trim().is_empty() {
-            println!("✅ Adicionando Context");
-            let mut context = Context::new();
-            context.description = context_val;
-            data.context = Some(context);
+    // Open a workspace file with a single click
+    let ui_weak56 = ui.as_weak();
+    let editor_workspace = editor.clone();
+    let tabs_workspace = tabs.clone();
+    let recent_workspace = recent_files.clone();
+    ui.on_open_workspace_file(move |path| {
+        let ui = ui_weak56.unwrap();
+        open_prompt_file(
+            &ui,
+            &editor_workspace,
+            &tabs_workspace,
+            &recent_workspace,
+            Path::new(path.as_str()),
+        );
+    });
+
+    // Show the settings dialog with the saved settings (Ctrl+,)
+    let ui_weak57 = ui.as_weak();
+    let settings_open = settings.clone();
+    ui.on_open_settings(move || {
+        let ui = ui_weak57.unwrap();
+        show_settings(&ui, &settings_open.borrow());
+        ui.set_settings_visible(true);
+    });
+
+    // Put the default settings in the dialog; they apply only when saved
+    let ui_weak58 = ui.as_weak();
+    ui.on_reset_settings(move || {
+        let ui = ui_weak58.unwrap();
+        show_settings(&ui, &Settings::default());
+    });
+
+    // Choose the save (0), library (1) or workspace (2) folder of the settings
+    let ui_weak59 = ui.as_weak();
+    ui.on_pick_settings_dir(move |field| {
+        let ui = ui_weak59.unwrap();
+        let Some(path) = FileDialog::new()
+            .set_title("Selecionar Pasta")
+            .pick_folder()
+        else {
+            println!("💭 Seleção cancelada pelo usuário");
+            return;
+        };
+        let path = SharedString::from(path.display().to_string());
+        match field {
+            0 => ui.set_settings_save_dir(path),
+            1 => ui.set_settings_library_dir(path),
+            _ => ui.set_settings_workspace_dir(path),
         }
+    });
 
-        if !main_content_val.trim().is_empty() {
-            println!("✅ Adicionando Main Content");
-            let mut main_content = MainContent::new();
-            main_content.instructions = main_content_val;
-            data.main_content = Some(main_content);
+    // Save the settings and apply them to the open window
+    let ui_weak60 = ui.as_weak();
+    let settings_apply = settings.clone();
+    let autosave_timer_settings = autosave_timer.clone();
+    ui.on_save_settings(move || {
+        let ui = ui_weak60.unwrap();
+        let updated = settings_from_ui(&ui);
+        if let Some(Err(e)) = settings_store.as_ref().map(|store| store.save(&updated)) {
+            eprintln!("❌ Erro ao salvar configurações: {}", e);
+            ui.set_settings_status(format!("Erro ao salvar configurações: {}", e).into());
+            return;
         }
 
-        if !auxiliary_val.trim().is_empty() {
-            println!("✅ Adicionando Auxiliary Content");
-            let mut aux_content = AuxiliaryContent::new();
-            aux_content.data = auxiliary_val;
-            data.auxiliary_content = Some(aux_content);
+        let previous = settings_apply.replace(updated.clone());
+        apply_appearance(&ui, &updated);
+        autosave_timer_settings.set_interval(Duration::from_secs(updated.autosave_seconds));
+        if updated.library_dir != previous.library_dir {
+            ui.set_library_path(updated.library_dir.display().to_string().into());
+            ui.invoke_refresh_library();
         }
+        if updated.workspace_dir != previous.workspace_dir {
+            let path = updated
+                .workspace_dir
+                .as_ref()
+                .map(|dir| dir.display().to_string());
+            ui.set_workspace_path(path.unwrap_or_default().into());
+            ui.invoke_refresh_workspace();
+        }
+        ui.set_settings_visible(false);
+        println!("⚙️ Configurações salvas");
+    });
 
-        if !limitations_val.trim().is_empty() {
-            println!("✅ Adicionando Limitations");
-            let mut limitations = Limitations::new();
-            limitations.text = limitations_val;
-            data.limitations = Some(limitations);
+    // Retrain the section classifier on the bundled corpus and the library
+    let ui_weak61 = ui.as_weak();
+    let library_train = library.clone();
+    let editor_train = editor.clone();
+    ui.on_train_classifier(move || {
+        let ui = ui_weak61.unwrap();
+        if library_train.borrow().is_none() {
+            ui.invoke_refresh_library();
         }
+        let library = library_train.borrow();
+        let Some(library) = library.as_ref() else {
+            return;
+        };
 
-        if !refactoring_val.trim().is_empty() {
-            println!("✅ Adicionando Refactoring");
-            let mut refactoring = Refactoring::new();
-            refactoring.text = refactoring_val;
-            data.refactoring = Some(refactoring);
+        let mut classifier = SectionClassifier::bundled();
+        let used = classifier.train_library(library, &editor_train.aliases);
+        if let Some(Err(e)) = classifier_store
+            .as_ref()
+            .map(|store| store.save(&classifier))
+        {
+            eprintln!("❌ Erro ao salvar classificador de seções: {}", e);
+            ui.set_library_status(format!("Erro ao salvar o classificador: {}", e).into());
+            return;
         }
+        *editor_train.classifier.borrow_mut() = classifier;
+        println!("🧠 Classificador treinado com {} prompt(s)", used);
+        ui.set_library_status(
+            format!(
+                "🧠 Classificador treinado com {} prompt(s) da biblioteca",
+                used
+            )
+            .into(),
+        );
+    });
 
-        if !guidance_val.trim().is_empty() {
-            println!("✅ Adicionando Guidance");
-            let mut guidance = Guidance::new();
-            guidance.text = guidance_val;
-            data.guidance = Some(guidance);
+    // Move a block of the import review to the section picked for it
+    let ui_weak62 = ui.as_weak();
+    let editor_assign = editor.clone();
+    ui.on_assign_review_section(move |index, section| {
+        let ui = ui_weak62.unwrap();
+        let mut pending = editor_assign.review.borrow_mut();
+        let (Some(pending), Some(kind)) =
+            (pending.as_mut(), SectionKind::ALL.get(section as usize))
+        else {
+            return;
+        };
+        pending.review.assign(index as usize, *kind);
+        show_review_rows(&ui, &pending.review);
+    });
+
+    // Append a block of the import review to the one before it
+    let ui_weak64 = ui.as_weak();
+    let editor_merge_block = editor.clone();
+    ui.on_merge_review_block(move |index| {
+        let ui = ui_weak64.unwrap();
+        if let Some(pending) = editor_merge_block.review.borrow_mut().as_mut() {
+            pending.review.merge_with_previous(index as usize);
+            show_review_rows(&ui, &pending.review);
         }
+    });
 
-        if !tests_val.trim().is_empty() {
-            println!("✅ Adicionando Tests");
-            let mut tests = Tests::new();
-            tests.text = tests_val;
-            data.tests = Some(tests);
+    // Split a block of the import review into lines or sentences
+    let ui_weak65 = ui.as_weak();
+    let editor_split_block = editor.clone();
+    ui.on_split_review_block(move |index| {
+        let ui = ui_weak65.unwrap();
+        if let Some(pending) = editor_split_block.review.borrow_mut().as_mut() {
+            let classifier = editor_split_block.classifier.borrow();
+            if pending.review.split(index as usize, &classifier) {
+                show_review_rows(&ui, &pending.review);
+            }
         }
+    });
 
-        if !output_format_val.trim().is_empty() {
-            println!("✅ Adicionando Output Format");
-            let mut output_format = OutputFormat::new();
-            output_format.text = output_format_val;
-            data.output_format = Some(output_format);
+    // Leave a block out of the import
+    let ui_weak66 = ui.as_weak();
+    let editor_remove_block = editor.clone();
+    ui.on_remove_review_block(move |index| {
+        let ui = ui_weak66.unwrap();
+        if let Some(pending) = editor_remove_block.review.borrow_mut().as_mut() {
+            pending.review.remove(index as usize);
+            show_review_rows(&ui, &pending.review);
         }
+    });
 
-        // Generate the prompt with clean formatting for preview
-        let generated_prompt = data.build_preview_prompt();
+    // Open the reviewed file with its blocks in the sections picked
+    let ui_weak63 = ui.as_weak();
+    let editor_review = editor.clone();
+    let tabs_review = tabs.clone();
+    ui.on_apply_review(move || {
+        let ui = ui_weak63.unwrap();
+        ui.set_review_visible(false);
+        let Some(pending) = editor_review.review.take() else {
+            return;
+        };
 
-        // Debug: print generated prompt to console
-        println!(
-            "📝 Prompt gerado ({} caracteres):\n{}",
-            generated_prompt.len(),
-            generated_prompt
+        let Some(new_tab) = prepare_tab_for(&ui, &editor_review, &tabs_review, &pending.path)
+        else {
+            return;
+        };
+        load_prompt_into_editor(
+            pending.review.into_prompt(),
+            &ui,
+            &mut editor_review.data.borrow_mut(),
         );
-
-        // Force update the preview regardless of content
-        println!("🔄 Atualizando preview...");
-        ui.set_preview_text(generated_prompt.into());
-        println!("✅ Preview atualizado!");
+        finish_open(
+            &ui,
+            &editor_review,
+            &tabs_review,
+            &pending.path,
+            pending.disk,
+            new_tab,
+            pending.label,
+        );
+        println!("📥 Arquivo importado: {}", pending.path.display());
     });
 
-    // Save prompt callback
-    let ui_weak2 = ui.as_weak();
-    let prompt_data_clone2 = prompt_data.clone();
-    ui.on_save_prompt(move || {
-        let _ui = ui_weak2.unwrap();
-        let data = prompt_data_clone2.borrow();
+    // Close the import review without opening the file
+    let ui_weak67 = ui.as_weak();
+    let editor_cancel_review = editor.clone();
+    ui.on_cancel_review(move || {
+        let ui = ui_weak67.unwrap();
+        ui.set_review_visible(false);
+        if let Some(pending) = editor_cancel_review.review.take() {
+            println!("↩️ Importação cancelada: {}", pending.path.display());
+        }
+    });
 
-        let prompt_text = data.build_prompt(false); // Save without markers
+    // Typing becomes an undo step once it pauses for a check interval
+    let ui_weak38 = ui.as_weak();
+    let undo_timer_state = undo.clone();
+    let undo_timer = Timer::default();
+    undo_timer.start(TimerMode::Repeated, Duration::from_secs(1), move || {
+        let Some(ui) = ui_weak38.upgrade() else {
+            return;
+        };
+        let committed = undo_timer_state
+            .borrow_mut()
+            .observe(editor_state(&ui), change_label);
+        if committed {
+            show_undo_history(&ui, &undo_timer_state.borrow());
+        }
+    });
 
-        // Open file dialog to choose save location
-        if let Some(file_path) = FileDialog::new()
-            .set_title("Salvar Prompt")
-            .set_file_name("generated_prompt.txt")
-            .add_filter("Arquivo de Texto", &["txt"])
-            .add_filter("Todos os Arquivos", &["*"])
-            .save_file()
-        {
-            match save_prompt_to_specific_path(&prompt_text, &file_path.to_string_lossy()) {
-                Ok(_) => println!("✅ Prompt salvo com sucesso em: {:?}", file_path),
-                Err(e) => eprintln!("❌ Erro ao salvar prompt: {}", e),
-            }
-        } else {
-            println!("💭 Salvamento cancelado pelo usuário");
+    // Undo the last change to the document (Ctrl+Z)
+    let ui_weak39 = ui.as_weak();
+    let undo_back = undo.clone();
+    ui.on_undo_edit(move || {
+        let ui = ui_weak39.unwrap();
+        flush_typing(&ui, &undo_back);
+        let state = undo_back.borrow_mut().undo();
+        if let Some(state) = state {
+            apply_editor_state(&state, &ui);
+            show_undo_history(&ui, &undo_back.borrow());
         }
     });
 
-    // Clear all callback
-    let ui_weak3 = ui.as_weak();
-    let prompt_data_clone3 = prompt_data.clone();
-    ui.on_clear_all(move || {
-        let ui = ui_weak3.unwrap();
-        let mut data = prompt_data_clone3.borrow_mut();
+    // Redo the last undone change (Ctrl+Y or Ctrl+Shift+Z)
+    let ui_weak40 = ui.as_weak();
+    let undo_forward = undo.clone();
+    ui.on_redo_edit(move || {
+        let ui = ui_weak40.unwrap();
+        flush_typing(&ui, &undo_forward);
+        let state = undo_forward.borrow_mut().redo();
+        if let Some(state) = state {
+            apply_editor_state(&state, &ui);
+            show_undo_history(&ui, &undo_forward.borrow());
+        }
+    });
 
-        // Clear all data
-        *data = PromptData::new();
+    // Go back to the state before an entry of the edit history list
+    let ui_weak41 = ui.as_weak();
+    let undo_to = undo.clone();
+    ui.on_undo_to(move |index| {
+        let ui = ui_weak41.unwrap();
+        flush_typing(&ui, &undo_to);
+        let mut state = None;
+        for _ in 0..=index.max(0) {
+            match undo_to.borrow_mut().undo() {
+                Some(previous) => state = Some(previous),
+                None => break,
+            }
+        }
+        if let Some(state) = state {
+            apply_editor_state(&state, &ui);
+            show_undo_history(&ui, &undo_to.borrow());
+        }
+    });
 
-        // Clear UI fields
-        ui.set_few_shot_text("".into());
-        ui.set_context_text("".into());
-        ui.set_main_content_text("".into());
-        ui.set_auxiliary_content_text("".into());
-        ui.set_limitations_text("".into());
-        ui.set_refactoring_text("".into());
-        ui.set_guidance_text("".into());
-        ui.set_tests_text("".into());
-        ui.set_output_format_text("".into());
-        ui.set_preview_text("O preview do prompt aparecerá aqui...".into());
+    // Reapply the undone changes up to an entry of the redo list
+    let ui_weak42 = ui.as_weak();
+    let redo_to = undo.clone();
+    ui.on_redo_to(move |index| {
+        let ui = ui_weak42.unwrap();
+        flush_typing(&ui, &redo_to);
+        let mut state = None;
+        for _ in 0..=index.max(0) {
+            match redo_to.borrow_mut().redo() {
+                Some(next) => state = Some(next),
+                None => break,
+            }
+        }
+        if let Some(state) = state {
+            apply_editor_state(&state, &ui);
+            show_undo_history(&ui, &redo_to.borrow());
+        }
     });
 
-    // Copy to clipboard callback
-    let ui_weak4 = ui.as_weak();
-    let prompt_data_clone4 = prompt_data.clone();
-    ui.on_copy_to_clipboard(move || {
-        let _ui = ui_weak4.unwrap();
-        let data = prompt_data_clone4.borrow();
+    #[cfg(feature = "drag-and-drop")]
+    enable_file_drop(&ui);
 
-        let prompt_text = data.build_prompt(false); // Copy without markers
+    // Add an explicit test case from the given/when/then inputs
+    let ui_weak8 = ui.as_weak();
+    let undo_add_case = undo.clone();
+    ui.on_add_test_case(move || {
+        let ui = ui_weak8.unwrap();
 
-        // Try to copy to clipboard
-        match ClipboardContext::new() {
-            Ok(mut ctx) => match ctx.set_contents(prompt_text) {
-                Ok(_) => println!("✅ Prompt copiado para a área de transferência!"),
-                Err(e) => eprintln!("❌ Erro ao copiar: {}", e),
-            },
-            Err(e) => eprintln!("❌ Erro ao acessar área de transferência: {}", e),
+        let name = ui.get_tests_case_name().trim().to_string();
+        if name.is_empty() {
+            show_error(&ui, "Informe um nome para o caso de teste");
+            return;
         }
-    });
-
-    // Open prompt callback
-    let ui_weak5 = ui.as_weak();
-    let prompt_data_clone5 = prompt_data.clone();
-    ui.on_open_prompt(move || {
-        let ui = ui_weak5.unwrap();
-        let mut data = prompt_data_clone5.borrow_mut();
 
-        println!("📂 Abrindo dialog para selecionar arquivo...");
+        flush_typing(&ui, &undo_add_case);
+        let mut rows: Vec<TestCaseRow> = ui.get_tests_cases().iter().collect();
+        rows.push(TestCaseRow {
+            name: name.into(),
+            given: ui.get_tests_case_given().trim().into(),
+            when: ui.get_tests_case_when().trim().into(),
+            then: ui.get_tests_case_then().trim().into(),
+        });
+        ui.set_tests_cases(ModelRc::new(VecModel::from(rows)));
 
-        // Open file dialog
-        if let Some(path) = FileDialog::new()
-            .add_filter("Arquivos de texto", &["txt"])
-            .set_title("Abrir Prompt Salvo")
-            .pick_file()
-        {
-            println!("📄 Arquivo selecionado: {:?}", path);
+        ui.set_tests_case_name("".into());
+        ui.set_tests_case_given("".into());
+        ui.set_tests_case_when("".into());
+        ui.set_tests_case_then("".into());
+        record_undo_step(&ui, &undo_add_case, "Adicionar caso de teste");
+    });
 
-            // Read file content
-            match std::fs::read_to_string(&path) {
-                Ok(content) => {
-                    println!("✅ Arquivo carregado com sucesso!");
-
-                    // Clear current data and UI
-                    *data = PromptData::new();
-                    ui.set_few_shot_text("".into());
-                    ui.set_context_text("".into());
-                    ui.set_main_content_text("".into());
-                    ui.set_auxiliary_content_text("".into());
-                    ui.set_limitations_text("".into());
-                    ui.set_refactoring_text("".into());
-                    ui.set_guidance_text("".into());
-                    ui.set_tests_text("".into());
-                    ui.set_output_format_text("".into());
-                    ui.set_preview_text("O preview do prompt aparecerá aqui...".into());
-
-                    // Parse the content to extract sections
-                    parse_prompt_content(&content, &ui, &mut data);
-
-                    // Generate preview automatically after loading
-                    println!("� Gerando preview automaticamente...");
-                    let generated_prompt = data.build_preview_prompt();
-                    ui.set_preview_text(generated_prompt.into());
-
-                    println!("�📝 Prompt carregado, campos preenchidos e preview atualizado!");
-                }
-                Err(e) => {
-                    eprintln!("❌ Erro ao ler arquivo: {}", e);
-                }
-            }
-        } else {
-            println!("❌ Nenhum arquivo selecionado.");
+    // Remove a test case
+    let ui_weak9 = ui.as_weak();
+    let undo_remove_case = undo.clone();
+    ui.on_remove_test_case(move |index| {
+        let ui = ui_weak9.unwrap();
+        let mut rows: Vec<TestCaseRow> = ui.get_tests_cases().iter().collect();
+        if index >= 0 && (index as usize) < rows.len() {
+            flush_typing(&ui, &undo_remove_case);
+            rows.remove(index as usize);
+            ui.set_tests_cases(ModelRc::new(VecModel::from(rows)));
+            record_undo_step(&ui, &undo_remove_case, "Remover caso de teste");
         }
     });
 
+    // Reopen the tabs of the last session, then offer the work left unsaved by
+    // a crash before the window opens
+    let last_session = match session_store.as_ref().map(SessionStore::load) {
+        Some(Ok(session)) => session,
+        Some(Err(e)) => {
+            show_error(&ui, &format!("Erro ao ler sessão anterior: {}", e));
+            None
+        }
+        None => None,
+    };
+    if let Some(session) = last_session {
+        restore_session(&ui, &editor, &tabs, &session);
+    }
+    show_tabs(&ui, &tabs.borrow());
+    if !ui.get_workspace_path().is_empty() {
+        ui.invoke_refresh_workspace();
+    }
+
+    offer_recovery(
+        &ui,
+        &autosave,
+        &undo,
+        &mut prompt_data.borrow_mut(),
+        &current_document,
+    );
+
     ui.run()?;
 
     Ok(())
@@ -0,0 +1,230 @@
+use std::fmt;
+use std::path::PathBuf;
+
+const INCLUDE_LABEL: &str = "incluir:";
+const EXCLUDE_LABEL: &str = "excluir:";
+const NO_GITIGNORE_LABEL: &str = "sem .gitignore";
+
+/// What an attachment points to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachmentKind {
+    /// A single file
+    File,
+    /// A directory, walked recursively
+    Directory,
+    /// A glob pattern such as `src/**/*.rs`
+    Glob,
+}
+
+impl AttachmentKind {
+    /// Stable identifier used when storing attachments in the UI
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttachmentKind::File => "file",
+            AttachmentKind::Directory => "dir",
+            AttachmentKind::Glob => "glob",
+        }
+    }
+
+    /// Parses an identifier produced by [`AttachmentKind::as_str`]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "file" => Some(AttachmentKind::File),
+            "dir" => Some(AttachmentKind::Directory),
+            "glob" => Some(AttachmentKind::Glob),
+            _ => None,
+        }
+    }
+}
+
+/// Size limits applied when attachments are read at render time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttachmentLimits {
+    /// Files larger than this are skipped
+    pub max_file_bytes: u64,
+    /// Files are skipped once the attachments reach this many tokens in total
+    pub max_total_tokens: usize,
+}
+
+impl Default for AttachmentLimits {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: 256 * 1024,
+            max_total_tokens: 50_000,
+        }
+    }
+}
+
+/// Reference to local files attached to the auxiliary content.
+/// Attachments stay references in the document and are read when the prompt is rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    /// File, directory or glob pattern
+    pub path: PathBuf,
+    pub kind: AttachmentKind,
+    /// Glob patterns a file must match to be included (empty includes everything)
+    pub include: Vec<String>,
+    /// Glob patterns that exclude files
+    pub exclude: Vec<String>,
+    /// Whether `.gitignore` files found while walking are honoured
+    pub respect_gitignore: bool,
+}
+
+impl Attachment {
+    /// Creates an attachment without include/exclude patterns that honours `.gitignore`
+    pub fn new(path: impl Into<PathBuf>, kind: AttachmentKind) -> Self {
+        Self {
+            path: path.into(),
+            kind,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            respect_gitignore: true,
+        }
+    }
+
+    /// Splits a comma-separated list of patterns, dropping empty entries
+    pub fn parse_patterns(patterns: &str) -> Vec<String> {
+        patterns
+            .split(',')
+            .map(|pattern| pattern.trim().to_string())
+            .filter(|pattern| !pattern.is_empty())
+            .collect()
+    }
+
+    /// Formats the attachment as a single line stored in the document, e.g.
+    /// `dir: src | incluir: *.rs | excluir: target/** | sem .gitignore`
+    pub fn to_spec(&self) -> String {
+        let mut spec = format!("{}: {}", self.kind.as_str(), self.path.display());
+        if !self.include.is_empty() {
+            spec.push_str(&format!(" | {} {}", INCLUDE_LABEL, self.include.join(", ")));
+        }
+        if !self.exclude.is_empty() {
+            spec.push_str(&format!(" | {} {}", EXCLUDE_LABEL, self.exclude.join(", ")));
+        }
+        if !self.respect_gitignore {
+            spec.push_str(&format!(" | {}", NO_GITIGNORE_LABEL));
+        }
+        spec
+    }
+
+    /// Parses a line produced by [`Attachment::to_spec`]
+    pub fn parse_spec(spec: &str) -> Option<Self> {
+        let mut parts = spec.split(" | ");
+        let (kind, path) = parts.next()?.split_once(": ")?;
+        let path = path.trim();
+        if path.is_empty() {
+            return None;
+        }
+
+        let mut attachment = Attachment::new(path, AttachmentKind::parse(kind.trim())?);
+        for part in parts {
+            let part = part.trim();
+            if let Some(include) = part.strip_prefix(INCLUDE_LABEL) {
+                attachment.include = Attachment::parse_patterns(include);
+            } else if let Some(exclude) = part.strip_prefix(EXCLUDE_LABEL) {
+                attachment.exclude = Attachment::parse_patterns(exclude);
+            } else if part == NO_GITIGNORE_LABEL {
+                attachment.respect_gitignore = false;
+            } else {
+                return None;
+            }
+        }
+        Some(attachment)
+    }
+}
+
+impl fmt::Display for Attachment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let icon = match self.kind {
+            AttachmentKind::File => "📄",
+            AttachmentKind::Directory => "📁",
+            AttachmentKind::Glob => "🔎",
+        };
+        write!(f, "{} {}", icon, self.path.display())?;
+        if !self.include.is_empty() {
+            write!(f, " (incluir: {})", self.include.join(", "))?;
+        }
+        if !self.exclude.is_empty() {
+            write!(f, " (excluir: {})", self.exclude.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachment_new_defaults() {
+        let attachment = Attachment::new("src", AttachmentKind::Directory);
+        assert_eq!(attachment.path, PathBuf::from("src"));
+        assert!(attachment.include.is_empty());
+        assert!(attachment.exclude.is_empty());
+        assert!(attachment.respect_gitignore);
+    }
+
+    #[test]
+    fn test_attachment_kind_round_trip() {
+        for kind in [
+            AttachmentKind::File,
+            AttachmentKind::Directory,
+            AttachmentKind::Glob,
+        ] {
+            assert_eq!(AttachmentKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(AttachmentKind::parse("outro"), None);
+    }
+
+    #[test]
+    fn test_parse_patterns() {
+        assert_eq!(
+            Attachment::parse_patterns(" *.rs, ,src/**/*.toml "),
+            vec!["*.rs".to_string(), "src/**/*.toml".to_string()]
+        );
+        assert!(Attachment::parse_patterns("").is_empty());
+    }
+
+    #[test]
+    fn test_attachment_display() {
+        let mut attachment = Attachment::new("src", AttachmentKind::Directory);
+        attachment.include = vec!["*.rs".to_string()];
+        attachment.exclude = vec!["target/**".to_string()];
+
+        let label = attachment.to_string();
+        assert_eq!(label, "📁 src (incluir: *.rs) (excluir: target/**)");
+    }
+
+    #[test]
+    fn test_attachment_spec_round_trip() {
+        let mut attachment = Attachment::new("src", AttachmentKind::Directory);
+        attachment.include = vec!["*.rs".to_string(), "*.toml".to_string()];
+        attachment.exclude = vec!["target/**".to_string()];
+        attachment.respect_gitignore = false;
+
+        let spec = attachment.to_spec();
+        assert_eq!(
+            spec,
+            "dir: src | incluir: *.rs, *.toml | excluir: target/** | sem .gitignore"
+        );
+        assert_eq!(Attachment::parse_spec(&spec), Some(attachment));
+
+        let file = Attachment::new(r"C:\projeto\notas.md", AttachmentKind::File);
+        assert_eq!(Attachment::parse_spec(&file.to_spec()), Some(file));
+    }
+
+    #[test]
+    fn test_attachment_parse_spec_rejects_other_lines() {
+        assert_eq!(Attachment::parse_spec("Use a versão estável"), None);
+        assert_eq!(Attachment::parse_spec("nota: algo importante"), None);
+        assert_eq!(Attachment::parse_spec("file: "), None);
+        assert_eq!(Attachment::parse_spec("dir: src | outra coisa"), None);
+    }
+
+    #[test]
+    fn test_attachment_limits_default() {
+        let limits = AttachmentLimits::default();
+        assert_eq!(limits.max_file_bytes, 256 * 1024);
+        assert_eq!(limits.max_total_tokens, 50_000);
+    }
+}
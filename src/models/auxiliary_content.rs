@@ -1,28 +1,114 @@
+use crate::models::attachment::{Attachment, AttachmentLimits};
+
+const ATTACHMENTS_LABEL: &str = "Anexos:";
+
 /// Represents additional auxiliary data for improving AI responses.
 #[derive(Debug, Clone)]
 pub struct AuxiliaryContent {
     /// Supporting information, examples, or attachments description
     pub data: String,
+    /// Local files, directories and globs; the document stores only the references
+    pub attachments: Vec<Attachment>,
+    /// Size and token limits applied to the attachments
+    pub limits: AttachmentLimits,
 }
 
 impl AuxiliaryContent {
     pub fn new() -> Self {
         Self {
             data: String::new(),
+            attachments: Vec::new(),
+            limits: AttachmentLimits::default(),
         }
     }
 
-    pub fn generate_text(&self) -> String {
+    /// Returns true when there is neither text nor attachments
+    pub fn is_empty(&self) -> bool {
+        self.data.trim().is_empty() && self.attachments.is_empty()
+    }
+
+    /// Renders the section body as stored in the document: the text followed
+    /// by the list of attachments
+    pub fn render_body(&self) -> String {
+        if self.attachments.is_empty() {
+            return self.data.clone();
+        }
+
+        let mut attachments = String::from(ATTACHMENTS_LABEL);
+        for attachment in &self.attachments {
+            attachments.push_str(&format!("\n- {}", attachment.to_spec()));
+        }
+        self.render_with_files(&attachments)
+    }
+
+    /// Renders the text followed by the given block of attached files
+    pub fn render_with_files(&self, files: &str) -> String {
+        if self.data.trim().is_empty() {
+            files.to_string()
+        } else if files.is_empty() {
+            self.data.clone()
+        } else {
+            format!("{}\n\n{}", self.data.trim_end(), files)
+        }
+    }
+
+    /// Parses a section body produced by [`AuxiliaryContent::render_body`] back
+    /// into text and attachments
+    pub fn parse(body: &str) -> Self {
+        let mut auxiliary = AuxiliaryContent::new();
+        let mut text_lines: Vec<&str> = Vec::new();
+        let mut in_attachments = false;
+        // Heading kept aside until an attachment follows it
+        let mut heading: Option<&str> = None;
+
+        for raw_line in body.lines() {
+            let line = raw_line.trim();
+
+            if line == ATTACHMENTS_LABEL {
+                text_lines.extend(heading.replace(raw_line));
+                in_attachments = true;
+                continue;
+            }
+
+            let attachment = line
+                .strip_prefix("- ")
+                .filter(|_| in_attachments)
+                .and_then(Attachment::parse_spec);
+
+            if let Some(attachment) = attachment {
+                heading = None;
+                auxiliary.attachments.push(attachment);
+            } else {
+                if !line.is_empty() {
+                    in_attachments = false;
+                }
+                text_lines.extend(heading.take());
+                text_lines.push(raw_line);
+            }
+        }
+        text_lines.extend(heading);
+
+        auxiliary.data = text_lines.join("\n").trim().to_string();
+        auxiliary
+    }
+
+    /// Wraps a rendered section body in the auxiliary content markers
+    pub fn with_markers(body: &str) -> String {
         format!(
             "<START_AUXILIARY_CONTENT>\n{}\n<END_AUXILIARY_CONTENT>\n",
-            self.data
+            body
         )
     }
+
+    pub fn generate_text(&self) -> String {
+        Self::with_markers(&self.render_body())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::attachment::AttachmentKind;
 
     #[test]
     fn test_auxiliary_content_new() {
@@ -116,4 +202,55 @@ mod tests {
         aux_content.data.clear();
         assert!(aux_content.data.is_empty());
     }
+
+    #[test]
+    fn test_auxiliary_content_is_empty() {
+        let mut aux_content = AuxiliaryContent::new();
+        assert!(aux_content.is_empty());
+
+        aux_content
+            .attachments
+            .push(Attachment::new("src", AttachmentKind::Directory));
+        assert!(!aux_content.is_empty());
+    }
+
+    #[test]
+    fn test_auxiliary_content_render_body_lists_attachments() {
+        let mut aux_content = AuxiliaryContent::new();
+        aux_content.data = "Configuração do projeto:".to_string();
+        aux_content
+            .attachments
+            .push(Attachment::new("config.toml", AttachmentKind::File));
+
+        // O documento guarda apenas as referências, não o conteúdo dos arquivos
+        assert_eq!(
+            aux_content.render_body(),
+            "Configuração do projeto:\n\nAnexos:\n- file: config.toml"
+        );
+    }
+
+    #[test]
+    fn test_auxiliary_content_parse_round_trip() {
+        let mut directory = Attachment::new("src", AttachmentKind::Directory);
+        directory.include = vec!["*.rs".to_string()];
+        directory.respect_gitignore = false;
+
+        let mut original = AuxiliaryContent::new();
+        original.data = "Material de apoio\n\n- item da lista".to_string();
+        original.attachments = vec![
+            Attachment::new("Cargo.toml", AttachmentKind::File),
+            directory,
+        ];
+
+        let parsed = AuxiliaryContent::parse(&original.render_body());
+        assert_eq!(parsed.data, original.data);
+        assert_eq!(parsed.attachments, original.attachments);
+    }
+
+    #[test]
+    fn test_auxiliary_content_parse_plain_text() {
+        let parsed = AuxiliaryContent::parse("Anexos:\n- a planilha de custos");
+        assert_eq!(parsed.data, "Anexos:\n- a planilha de custos");
+        assert!(parsed.attachments.is_empty());
+    }
 }
@@ -0,0 +1,147 @@
+use crate::models::section::SectionKind;
+use std::collections::HashMap;
+
+/// How a section (or an attached file) is shortened when it exceeds its budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TruncationStrategy {
+    /// Keeps the beginning and cuts the end
+    Head,
+    /// Keeps the end and cuts the beginning
+    Tail,
+    /// Keeps the beginning and the end and cuts the middle
+    MiddleElision,
+    /// Drops whole attached files, largest first, before cutting any text
+    DropWholeFiles,
+}
+
+impl TruncationStrategy {
+    /// All strategies, in the order shown in the UI
+    pub const ALL: [TruncationStrategy; 4] = [
+        TruncationStrategy::Head,
+        TruncationStrategy::Tail,
+        TruncationStrategy::MiddleElision,
+        TruncationStrategy::DropWholeFiles,
+    ];
+
+    /// Label shown in the UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            TruncationStrategy::Head => "Manter início",
+            TruncationStrategy::Tail => "Manter fim",
+            TruncationStrategy::MiddleElision => "Cortar o meio",
+            TruncationStrategy::DropWholeFiles => "Remover arquivos inteiros",
+        }
+    }
+}
+
+/// Budget rules for one section.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SectionBudget {
+    /// Sections with higher priority receive spare tokens first and are cut last
+    pub priority: u8,
+    /// Share of the target the section keeps whenever it needs it (0.0 to 1.0)
+    pub min_share: f32,
+    /// Share of the target the section may never exceed (0.0 to 1.0)
+    pub max_share: f32,
+    pub strategy: TruncationStrategy,
+}
+
+impl SectionBudget {
+    pub fn new(priority: u8, min_share: f32, max_share: f32, strategy: TruncationStrategy) -> Self {
+        Self {
+            priority,
+            min_share,
+            max_share,
+            strategy,
+        }
+    }
+}
+
+/// Target size of the prompt and the budget rules of every section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetPolicy {
+    /// Maximum number of tokens of the rendered prompt
+    pub target_tokens: usize,
+    pub sections: HashMap<SectionKind, SectionBudget>,
+}
+
+impl BudgetPolicy {
+    /// Creates a policy with the default rules: instructions are protected and
+    /// supporting material (examples, attachments, code) is cut first
+    pub fn new(target_tokens: usize) -> Self {
+        use TruncationStrategy::*;
+
+        let sections = SectionKind::ALL
+            .into_iter()
+            .map(|kind| {
+                let budget = match kind {
+                    SectionKind::MainContent => SectionBudget::new(100, 0.2, 1.0, Head),
+                    SectionKind::Context => SectionBudget::new(90, 0.1, 0.5, Head),
+                    SectionKind::OutputFormat => SectionBudget::new(85, 0.05, 0.3, Head),
+                    SectionKind::Limitations => SectionBudget::new(80, 0.05, 0.3, Head),
+                    SectionKind::Guidance => SectionBudget::new(75, 0.05, 0.3, Head),
+                    SectionKind::Tests => SectionBudget::new(70, 0.05, 0.4, Head),
+                    SectionKind::Refactoring => SectionBudget::new(60, 0.1, 0.8, MiddleElision),
+                    SectionKind::FewShot => SectionBudget::new(50, 0.05, 0.4, Head),
+                    SectionKind::AuxiliaryContent => {
+                        SectionBudget::new(10, 0.0, 0.8, DropWholeFiles)
+                    }
+                };
+                (kind, budget)
+            })
+            .collect();
+
+        Self {
+            target_tokens,
+            sections,
+        }
+    }
+
+    /// Rules for a section, falling back to a neutral budget when none is configured
+    pub fn for_section(&self, kind: SectionKind) -> SectionBudget {
+        self.sections
+            .get(&kind)
+            .copied()
+            .unwrap_or(SectionBudget::new(50, 0.0, 1.0, TruncationStrategy::Head))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_policy_defaults_cover_every_section() {
+        let policy = BudgetPolicy::new(4_000);
+        assert_eq!(policy.target_tokens, 4_000);
+        for kind in SectionKind::ALL {
+            let budget = policy.for_section(kind);
+            assert!(budget.min_share <= budget.max_share);
+        }
+    }
+
+    #[test]
+    fn test_budget_policy_protects_main_content() {
+        let policy = BudgetPolicy::new(1_000);
+        let main = policy.for_section(SectionKind::MainContent);
+        let auxiliary = policy.for_section(SectionKind::AuxiliaryContent);
+        assert!(main.priority > auxiliary.priority);
+        assert_eq!(auxiliary.strategy, TruncationStrategy::DropWholeFiles);
+    }
+
+    #[test]
+    fn test_budget_policy_fallback() {
+        let mut policy = BudgetPolicy::new(1_000);
+        policy.sections.clear();
+        let budget = policy.for_section(SectionKind::Context);
+        assert_eq!(budget.max_share, 1.0);
+        assert_eq!(budget.strategy, TruncationStrategy::Head);
+    }
+
+    #[test]
+    fn test_truncation_strategy_labels() {
+        let labels: Vec<&str> = TruncationStrategy::ALL.iter().map(|s| s.label()).collect();
+        assert_eq!(labels.len(), 4);
+        assert_eq!(labels[2], "Cortar o meio");
+    }
+}
@@ -0,0 +1,58 @@
+/// Language of the interface or of the generated prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    Portuguese,
+    English,
+    Spanish,
+}
+
+impl Language {
+    /// All supported languages, in the order shown in the UI
+    pub const ALL: [Language; 3] = [Language::Portuguese, Language::English, Language::Spanish];
+
+    /// Name of the language in itself, as shown in the language lists
+    pub fn label(&self) -> &'static str {
+        match self {
+            Language::Portuguese => "Português",
+            Language::English => "English",
+            Language::Spanish => "Español",
+        }
+    }
+
+    /// ISO 639-1 code stored in the settings file
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Language::Portuguese => "pt",
+            Language::English => "en",
+            Language::Spanish => "es",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|language| language.as_str() == value)
+    }
+
+    /// Position of the language in `ALL`, as used by the UI lists
+    pub fn index(&self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|language| language == self)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_code_round_trip() {
+        for language in Language::ALL {
+            assert_eq!(Language::parse(language.as_str()), Some(language));
+            assert_eq!(Language::ALL[language.index()], language);
+        }
+        assert_eq!(Language::parse("fr"), None);
+    }
+}
@@ -0,0 +1,419 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Line that opens and closes the metadata header of a document
+pub const HEADER_DELIMITER: &str = "---";
+
+/// Keys with a dedicated field; any other key goes to `extra`
+const KNOWN_KEYS: [&str; 10] = [
+    "title",
+    "description",
+    "author",
+    "tags",
+    "created",
+    "modified",
+    "model",
+    "temperature",
+    "max_tokens",
+    "stop",
+];
+
+/// Per-document metadata, saved as a `---` header at the top of the native format.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentMetadata {
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    pub tags: Vec<String>,
+    /// Creation time, in seconds since the Unix epoch
+    pub created: Option<u64>,
+    /// Last modification time, in seconds since the Unix epoch
+    pub modified: Option<u64>,
+    /// Model the prompt is written for (e.g. "gpt-4o")
+    pub model: String,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub stop_sequences: Vec<String>,
+    /// Free-form key/value pairs, in the order they were written
+    pub extra: Vec<(String, String)>,
+}
+
+impl DocumentMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true when no field is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Sets the creation time when missing and the modification time to `now`
+    pub fn touch(&mut self, now: u64) {
+        self.created.get_or_insert(now);
+        self.modified = Some(now);
+    }
+
+    /// Renders the header block, or an empty string when there is no metadata.
+    /// Values are kept on one line; backslashes and line breaks are escaped.
+    pub fn to_header(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+
+        let mut lines = vec![HEADER_DELIMITER.to_string()];
+        let mut push = |key: &str, value: &str| {
+            if !value.is_empty() {
+                lines.push(format!("{}: {}", key, escape_value(value)));
+            }
+        };
+        push("title", &self.title);
+        push("description", &self.description);
+        push("author", &self.author);
+        push("tags", &self.tags.join(", "));
+        push("created", &self.created.map(format_iso).unwrap_or_default());
+        push(
+            "modified",
+            &self.modified.map(format_iso).unwrap_or_default(),
+        );
+        push("model", &self.model);
+        push(
+            "temperature",
+            &self.temperature.map(|t| t.to_string()).unwrap_or_default(),
+        );
+        push(
+            "max_tokens",
+            &self.max_tokens.map(|t| t.to_string()).unwrap_or_default(),
+        );
+        for stop in &self.stop_sequences {
+            push("stop", stop);
+        }
+        for (key, value) in &self.extra {
+            if is_valid_extra_key(key) {
+                push(key, value);
+            }
+        }
+        lines.push(HEADER_DELIMITER.to_string());
+        lines.join("\n")
+    }
+
+    /// Splits a document into its metadata header and the remaining body.
+    /// Documents without a complete header are returned unchanged.
+    pub fn split_header(content: &str) -> (Self, &str) {
+        let text = content.trim_start_matches('\u{feff}');
+        let Some(rest) = text.strip_prefix(HEADER_DELIMITER).and_then(|rest| {
+            rest.strip_prefix("\r\n")
+                .or_else(|| rest.strip_prefix('\n'))
+        }) else {
+            return (Self::new(), content);
+        };
+
+        let mut metadata = Self::new();
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            offset += line.len();
+            let line = line.trim_end_matches(['\r', '\n']);
+            if line.trim() == HEADER_DELIMITER {
+                return (metadata, &rest[offset..]);
+            }
+            match line.split_once(':') {
+                Some((key, value)) => metadata.set(key.trim(), &unescape_value(value.trim())),
+                None if line.trim().is_empty() => {}
+                // Not a header after all: the document starts with a separator
+                None => return (Self::new(), content),
+            }
+        }
+
+        (Self::new(), content)
+    }
+
+    /// Parses `chave: valor` lines into the free-form pairs, ignoring reserved keys
+    pub fn parse_extra(text: &str) -> Vec<(String, String)> {
+        text.lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .filter(|(key, _)| is_valid_extra_key(key))
+            .collect()
+    }
+
+    /// Formats the free-form pairs as `chave: valor` lines
+    pub fn format_extra(&self) -> String {
+        self.extra
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, value))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// File name stem derived from the title, without characters that are
+    /// invalid on common file systems; None when the title is blank
+    pub fn file_stem(&self) -> Option<String> {
+        let stem: String = self
+            .title
+            .trim()
+            .chars()
+            .map(|c| match c {
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+                c if c.is_control() => '_',
+                c => c,
+            })
+            .collect();
+        let stem = stem.trim_start_matches('.').trim();
+        (!stem.is_empty()).then(|| stem.to_string())
+    }
+
+    // Stores one header entry
+    fn set(&mut self, key: &str, value: &str) {
+        match key {
+            "title" => self.title = value.to_string(),
+            "description" => self.description = value.to_string(),
+            "author" => self.author = value.to_string(),
+            "tags" => {
+                self.tags = value
+                    .split(',')
+                    .map(|tag| tag.trim().to_string())
+                    .filter(|tag| !tag.is_empty())
+                    .collect()
+            }
+            "created" => self.created = parse_iso(value),
+            "modified" => self.modified = parse_iso(value),
+            "model" => self.model = value.to_string(),
+            "temperature" => self.temperature = value.parse().ok(),
+            "max_tokens" => self.max_tokens = value.parse().ok(),
+            "stop" if !value.is_empty() => self.stop_sequences.push(value.to_string()),
+            key if is_valid_extra_key(key) => self.extra.push((key.to_string(), value.to_string())),
+            _ => {}
+        }
+    }
+}
+
+/// Current time in seconds since the Unix epoch
+pub fn now_seconds() -> u64 {
+    unix_seconds(SystemTime::now())
+}
+
+/// Converts a system time into seconds since the Unix epoch
+pub fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Formats seconds since the Unix epoch as `AAAA-MM-DD HH:MM` (UTC)
+pub fn format_timestamp(seconds: u64) -> String {
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let minutes_of_day = (seconds % 86_400) / 60;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        minutes_of_day / 60,
+        minutes_of_day % 60
+    )
+}
+
+// Formats a timestamp as RFC 3339 in UTC, e.g. `2024-02-29T12:34:56Z`
+fn format_iso(seconds: u64) -> String {
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let second_of_day = seconds % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        second_of_day / 3_600,
+        second_of_day % 3_600 / 60,
+        second_of_day % 60
+    )
+}
+
+// Parses the RFC 3339 UTC form written by `format_iso`, or plain epoch seconds
+fn parse_iso(value: &str) -> Option<u64> {
+    if let Ok(seconds) = value.parse() {
+        return Some(seconds);
+    }
+    let (date, time) = value.trim_end_matches('Z').split_once('T')?;
+    let date: Vec<i64> = date
+        .split('-')
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    let time: Vec<u64> = time
+        .split(':')
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    let (&[year, month, day], &[hour, minute, second]) = (date.as_slice(), time.as_slice()) else {
+        return None;
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+    let days = u64::try_from(days_from_civil(year, month, day)).ok()?;
+    Some(days * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+// Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
+
+// Days since 1970-01-01 for a civil date (inverse of `civil_from_days`)
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * mp + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn is_valid_extra_key(key: &str) -> bool {
+    !key.is_empty()
+        && !KNOWN_KEYS.contains(&key)
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
+fn escape_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+}
+
+fn unescape_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_metadata() -> DocumentMetadata {
+        DocumentMetadata {
+            title: "Revisão de API".to_string(),
+            description: "Revisa rotas\nREST".to_string(),
+            author: "Ana".to_string(),
+            tags: vec!["rust".to_string(), "api".to_string()],
+            created: Some(1_709_210_096),
+            modified: Some(1_709_296_496),
+            model: "gpt-4o".to_string(),
+            temperature: Some(0.2),
+            max_tokens: Some(2048),
+            stop_sequences: vec!["###".to_string(), "\n\nFIM".to_string()],
+            extra: vec![("projeto".to_string(), "crate".to_string())],
+        }
+    }
+
+    #[test]
+    fn test_header_round_trip() {
+        let metadata = sample_metadata();
+        let document = format!("{}\n\n## Contexto\n\nolá", metadata.to_header());
+
+        let (parsed, body) = DocumentMetadata::split_header(&document);
+
+        assert_eq!(parsed, metadata);
+        assert_eq!(body, "\n## Contexto\n\nolá");
+    }
+
+    #[test]
+    fn test_header_format() {
+        let header = sample_metadata().to_header();
+        assert!(
+            header.starts_with("---\ntitle: Revisão de API\ndescription: Revisa rotas\\nREST\n")
+        );
+        assert!(header.contains("\ncreated: 2024-02-29T12:34:56Z\n"));
+        assert!(header.contains("\nstop: ###\nstop: \\n\\nFIM\nprojeto: crate\n---"));
+    }
+
+    #[test]
+    fn test_empty_metadata_has_no_header() {
+        assert!(DocumentMetadata::new().is_empty());
+        assert_eq!(DocumentMetadata::new().to_header(), "");
+    }
+
+    #[test]
+    fn test_split_header_without_metadata() {
+        // Um separador seguido de texto comum não é um cabeçalho
+        let content = "---\nTexto livre sem chave\n---\nfim";
+        let (metadata, body) = DocumentMetadata::split_header(content);
+        assert!(metadata.is_empty());
+        assert_eq!(body, content);
+
+        let (metadata, body) = DocumentMetadata::split_header("## Contexto\n\nolá");
+        assert!(metadata.is_empty());
+        assert_eq!(body, "## Contexto\n\nolá");
+    }
+
+    #[test]
+    fn test_parse_extra_ignores_reserved_and_invalid_keys() {
+        let extra = DocumentMetadata::parse_extra(
+            "projeto: crate\ntitle: x\nsem dois pontos\nchave inválida: y\nversão: 2",
+        );
+        assert_eq!(
+            extra,
+            vec![
+                ("projeto".to_string(), "crate".to_string()),
+                ("versão".to_string(), "2".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_touch_keeps_creation_time() {
+        let mut metadata = DocumentMetadata::new();
+        metadata.touch(10);
+        metadata.touch(20);
+        assert_eq!(metadata.created, Some(10));
+        assert_eq!(metadata.modified, Some(20));
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(1_709_210_096), "2024-02-29 12:34");
+    }
+
+    #[test]
+    fn test_parse_iso_timestamps() {
+        assert_eq!(parse_iso("2024-02-29T12:34:56Z"), Some(1_709_210_096));
+        assert_eq!(parse_iso("1709210096"), Some(1_709_210_096));
+        assert_eq!(parse_iso("2024-13-01T00:00:00Z"), None);
+        assert_eq!(parse_iso("ontem"), None);
+    }
+
+    #[test]
+    fn test_file_stem_sanitizes_title() {
+        let mut metadata = DocumentMetadata::new();
+        metadata.title = "API: CRUD/Rust?".to_string();
+        assert_eq!(metadata.file_stem().as_deref(), Some("API_ CRUD_Rust_"));
+        metadata.title = "  ...  ".to_string();
+        assert_eq!(metadata.file_stem(), None);
+    }
+}
@@ -1,10 +1,15 @@
 // This module contains the definitions and implementations for various models used in the application.
+pub mod attachment;
 pub mod auxiliary_content;
+pub mod budget;
 pub mod context;
 pub mod few_shot;
 pub mod guidance;
+pub mod language;
 pub mod limitations;
 pub mod main_content;
+pub mod metadata;
 pub mod output_format;
 pub mod refactoring;
+pub mod section;
 pub mod tests;
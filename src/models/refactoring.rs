@@ -1,20 +1,260 @@
+use std::fmt;
+use std::path::PathBuf;
+
+const GOALS_LABEL: &str = "Objetivos da refatoração:";
+const FILES_LABEL: &str = "Arquivos de código:";
+
+/// Refactoring goals that are rendered as explicit instructions for the AI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefactoringGoal {
+    Solid,
+    Performance,
+    Readability,
+    ErrorHandling,
+}
+
+impl RefactoringGoal {
+    /// All goals, in the order they are rendered
+    pub const ALL: [RefactoringGoal; 4] = [
+        RefactoringGoal::Solid,
+        RefactoringGoal::Performance,
+        RefactoringGoal::Readability,
+        RefactoringGoal::ErrorHandling,
+    ];
+
+    /// Short label shown in the UI checklist
+    pub fn label(&self) -> &'static str {
+        match self {
+            RefactoringGoal::Solid => "SOLID",
+            RefactoringGoal::Performance => "Performance",
+            RefactoringGoal::Readability => "Legibilidade",
+            RefactoringGoal::ErrorHandling => "Tratamento de erros",
+        }
+    }
+
+    /// Instruction sentence emitted in the prompt for this goal
+    pub fn instruction(&self) -> &'static str {
+        match self {
+            RefactoringGoal::Solid => {
+                "Aplique os princípios SOLID: responsabilidade única, aberto/fechado, substituição de Liskov, segregação de interfaces e inversão de dependências."
+            }
+            RefactoringGoal::Performance => {
+                "Melhore a performance: evite alocações e cópias desnecessárias, reduza a complexidade algorítmica e elimine trabalho repetido."
+            }
+            RefactoringGoal::Readability => {
+                "Melhore a legibilidade: use nomes claros, extraia funções pequenas, remova duplicação e simplifique o fluxo de controle."
+            }
+            RefactoringGoal::ErrorHandling => {
+                "Melhore o tratamento de erros: propague erros com contexto, elimine panics e unwraps evitáveis e trate todos os casos de falha."
+            }
+        }
+    }
+
+    /// Finds the goal whose instruction sentence is `instruction`
+    pub fn from_instruction(instruction: &str) -> Option<Self> {
+        let instruction = instruction.trim();
+        RefactoringGoal::ALL
+            .into_iter()
+            .find(|goal| goal.instruction() == instruction)
+    }
+}
+
+/// Reference to a source file, optionally restricted to a line range.
+/// The document stores only the reference; the code is read when the prompt is rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeReference {
+    /// Path to the source file
+    pub path: PathBuf,
+    /// 1-based inclusive line range; `None` means the whole file
+    pub line_range: Option<(usize, usize)>,
+}
+
+impl CodeReference {
+    /// Creates a reference to a whole file
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            line_range: None,
+        }
+    }
+
+    /// Creates a reference to a line range of a file
+    pub fn with_lines(path: impl Into<PathBuf>, start: usize, end: usize) -> Self {
+        Self {
+            path: path.into(),
+            line_range: Some((start, end)),
+        }
+    }
+
+    /// Parses a reference in the form `path`, `path:line` or `path:start-end`
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim();
+        if spec.is_empty() {
+            return None;
+        }
+
+        if let Some((path, range)) = spec.rsplit_once(':') {
+            if let Some(line_range) = parse_line_range(range) {
+                if !path.is_empty() {
+                    return Some(Self {
+                        path: PathBuf::from(path),
+                        line_range: Some(line_range),
+                    });
+                }
+            }
+        }
+
+        Some(Self::new(spec))
+    }
+}
+
+impl fmt::Display for CodeReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line_range {
+            Some((start, end)) => write!(f, "{}:{}-{}", self.path.display(), start, end),
+            None => write!(f, "{}", self.path.display()),
+        }
+    }
+}
+
+/// Parses `start-end` or a single line number into a 1-based inclusive range
+pub fn parse_line_range(range: &str) -> Option<(usize, usize)> {
+    let range = range.trim();
+    let (start, end) = match range.split_once('-') {
+        Some((start, end)) => (start.trim().parse().ok()?, end.trim().parse().ok()?),
+        None => {
+            let line = range.parse().ok()?;
+            (line, line)
+        }
+    };
+
+    if start == 0 || start > end {
+        None
+    } else {
+        Some((start, end))
+    }
+}
+
 /// Represents refactoring instructions for developers.
 /// Used mainly in code-related prompts.
 #[derive(Debug, Clone)]
 pub struct Refactoring {
     /// Instructions for refactoring
     pub text: String,
+    /// Source files whose code is attached to the instructions
+    pub files: Vec<CodeReference>,
+    /// Checklist of refactoring goals
+    pub goals: Vec<RefactoringGoal>,
 }
 
 impl Refactoring {
     pub fn new() -> Self {
         Self {
             text: String::new(),
+            files: Vec::new(),
+            goals: Vec::new(),
+        }
+    }
+
+    /// Returns true when there are no instructions, files or goals
+    pub fn is_empty(&self) -> bool {
+        self.text.trim().is_empty() && self.files.is_empty() && self.goals.is_empty()
+    }
+
+    /// Renders the section body as stored in the document: free text, goal
+    /// instructions and the list of referenced files
+    pub fn render_body(&self) -> String {
+        if self.files.is_empty() {
+            return self.render_with_files(&[]);
         }
+
+        let mut files = String::from(FILES_LABEL);
+        for file in &self.files {
+            files.push_str(&format!("\n- {}", file));
+        }
+        self.render_with_files(&[files])
+    }
+
+    /// Renders the free text and goal instructions followed by the given file blocks
+    pub fn render_with_files(&self, files: &[String]) -> String {
+        let mut parts: Vec<String> = Vec::new();
+
+        if !self.text.trim().is_empty() || (files.is_empty() && self.goals.is_empty()) {
+            parts.push(self.text.clone());
+        }
+
+        if !self.goals.is_empty() {
+            let mut goals = String::from(GOALS_LABEL);
+            for goal in RefactoringGoal::ALL {
+                if self.goals.contains(&goal) {
+                    goals.push_str(&format!("\n- {}", goal.instruction()));
+                }
+            }
+            parts.push(goals);
+        }
+
+        parts.extend(files.iter().cloned());
+        parts.join("\n\n")
+    }
+
+    /// Parses a section body produced by [`Refactoring::render_body`] back into
+    /// goals and file references. Other lines are kept as free text.
+    pub fn parse(body: &str) -> Self {
+        let mut refactoring = Refactoring::new();
+        let mut text_lines: Vec<&str> = Vec::new();
+        let mut in_goals = false;
+        let mut in_files = false;
+        // Heading kept aside until a goal or file follows it
+        let mut heading: Option<&str> = None;
+
+        for raw_line in body.lines() {
+            let line = raw_line.trim();
+
+            if line == GOALS_LABEL || line == FILES_LABEL {
+                text_lines.extend(heading.replace(raw_line));
+                in_goals = line == GOALS_LABEL;
+                in_files = line == FILES_LABEL;
+                continue;
+            }
+
+            let item = line.strip_prefix("- ");
+            let goal = item
+                .filter(|_| in_goals)
+                .and_then(RefactoringGoal::from_instruction);
+            let file = item.filter(|_| in_files).and_then(CodeReference::parse);
+
+            if goal.is_some() || file.is_some() {
+                heading = None;
+            }
+
+            if let Some(goal) = goal {
+                if !refactoring.goals.contains(&goal) {
+                    refactoring.goals.push(goal);
+                }
+            } else if let Some(file) = file {
+                refactoring.files.push(file);
+            } else {
+                if !line.is_empty() {
+                    in_goals = false;
+                    in_files = false;
+                }
+                text_lines.extend(heading.take());
+                text_lines.push(raw_line);
+            }
+        }
+        text_lines.extend(heading);
+
+        refactoring.text = text_lines.join("\n").trim().to_string();
+        refactoring
+    }
+
+    /// Wraps a rendered section body in the refactoring markers
+    pub fn with_markers(body: &str) -> String {
+        format!("<START_REFACTORING>\n{}\n<END_REFACTORING>\n", body)
     }
 
     pub fn generate_text(&self) -> String {
-        format!("<START_REFACTORING>\n{}\n<END_REFACTORING>\n", self.text)
+        Self::with_markers(&self.render_body())
     }
 }
 
@@ -128,4 +368,116 @@ mod tests {
         assert!(generated.contains("Depois:"));
         assert!(generated.contains("vec![1, 2, 3]"));
     }
+
+    #[test]
+    fn test_refactoring_is_empty() {
+        let mut refactoring = Refactoring::new();
+        assert!(refactoring.is_empty());
+
+        refactoring.goals.push(RefactoringGoal::Performance);
+        assert!(!refactoring.is_empty());
+    }
+
+    #[test]
+    fn test_refactoring_goals_rendered_as_instructions() {
+        let mut refactoring = Refactoring::new();
+        refactoring.text = "Refatore o módulo de parsing".to_string();
+        refactoring.goals = vec![RefactoringGoal::ErrorHandling, RefactoringGoal::Solid];
+
+        let generated = refactoring.generate_text();
+        assert!(generated.contains("Refatore o módulo de parsing"));
+        assert!(generated.contains("Objetivos da refatoração:"));
+
+        // Objetivos seguem a ordem canônica, independente da ordem de seleção
+        let solid_pos = generated.find("princípios SOLID").unwrap();
+        let errors_pos = generated.find("tratamento de erros").unwrap();
+        assert!(solid_pos < errors_pos);
+    }
+
+    #[test]
+    fn test_refactoring_render_body_lists_file_references() {
+        let mut refactoring = Refactoring::new();
+        refactoring.text = "Simplifique o parser".to_string();
+        refactoring
+            .files
+            .push(CodeReference::with_lines("src/lib.rs", 2, 3));
+        refactoring.files.push(CodeReference::new("src/main.rs"));
+
+        // O documento guarda apenas as referências, não o código
+        assert_eq!(
+            refactoring.render_body(),
+            "Simplifique o parser\n\nArquivos de código:\n- src/lib.rs:2-3\n- src/main.rs"
+        );
+    }
+
+    #[test]
+    fn test_refactoring_parse_round_trip() {
+        let mut original = Refactoring::new();
+        original.text = "Refatore o módulo de parsing\n\nMantenha a API pública".to_string();
+        original.goals = vec![RefactoringGoal::Solid, RefactoringGoal::ErrorHandling];
+        original.files = vec![
+            CodeReference::with_lines("src/parser.rs", 10, 42),
+            CodeReference::new("src/lib.rs"),
+        ];
+
+        let parsed = Refactoring::parse(&original.render_body());
+        assert_eq!(parsed.text, original.text);
+        assert_eq!(parsed.goals, original.goals);
+        assert_eq!(parsed.files, original.files);
+    }
+
+    #[test]
+    fn test_refactoring_parse_plain_text() {
+        let parsed = Refactoring::parse("- Elimine duplicação\n- Use iterators");
+        assert_eq!(parsed.text, "- Elimine duplicação\n- Use iterators");
+        assert!(parsed.goals.is_empty());
+        assert!(parsed.files.is_empty());
+
+        // Um título sem objetivos reconhecidos continua sendo texto
+        let parsed = Refactoring::parse("Objetivos da refatoração:\n- deixar o código mais curto");
+        assert_eq!(
+            parsed.text,
+            "Objetivos da refatoração:\n- deixar o código mais curto"
+        );
+        assert!(parsed.goals.is_empty());
+        assert!(parsed.files.is_empty());
+    }
+
+    #[test]
+    fn test_code_reference_parse() {
+        assert_eq!(
+            CodeReference::parse("src/main.rs:10-42"),
+            Some(CodeReference::with_lines("src/main.rs", 10, 42))
+        );
+        assert_eq!(
+            CodeReference::parse("src/main.rs:7"),
+            Some(CodeReference::with_lines("src/main.rs", 7, 7))
+        );
+        assert_eq!(
+            CodeReference::parse("src/main.rs"),
+            Some(CodeReference::new("src/main.rs"))
+        );
+        assert_eq!(
+            CodeReference::parse(r"C:\projeto\main.rs:1-2"),
+            Some(CodeReference::with_lines(r"C:\projeto\main.rs", 1, 2))
+        );
+        assert_eq!(CodeReference::parse("   "), None);
+    }
+
+    #[test]
+    fn test_code_reference_display_round_trip() {
+        let reference = CodeReference::with_lines("src/lib.rs", 3, 9);
+        let label = reference.to_string();
+        assert_eq!(label, "src/lib.rs:3-9");
+        assert_eq!(CodeReference::parse(&label), Some(reference));
+    }
+
+    #[test]
+    fn test_parse_line_range() {
+        assert_eq!(parse_line_range("10-20"), Some((10, 20)));
+        assert_eq!(parse_line_range(" 5 "), Some((5, 5)));
+        assert_eq!(parse_line_range("0-3"), None);
+        assert_eq!(parse_line_range("9-3"), None);
+        assert_eq!(parse_line_range("abc"), None);
+    }
 }
@@ -0,0 +1,127 @@
+use crate::models::language::Language;
+
+/// Identifies one of the nine prompt sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SectionKind {
+    FewShot,
+    Context,
+    MainContent,
+    AuxiliaryContent,
+    Limitations,
+    Refactoring,
+    Guidance,
+    Tests,
+    OutputFormat,
+}
+
+impl SectionKind {
+    /// All sections, in prompt order
+    pub const ALL: [SectionKind; 9] = [
+        SectionKind::FewShot,
+        SectionKind::Context,
+        SectionKind::MainContent,
+        SectionKind::AuxiliaryContent,
+        SectionKind::Limitations,
+        SectionKind::Refactoring,
+        SectionKind::Guidance,
+        SectionKind::Tests,
+        SectionKind::OutputFormat,
+    ];
+
+    /// Stable identifier, matching the section names used by the parser
+    pub fn id(&self) -> &'static str {
+        match self {
+            SectionKind::FewShot => "few_shot",
+            SectionKind::Context => "context",
+            SectionKind::MainContent => "main_content",
+            SectionKind::AuxiliaryContent => "auxiliary_content",
+            SectionKind::Limitations => "limitations",
+            SectionKind::Refactoring => "refactoring",
+            SectionKind::Guidance => "guidance",
+            SectionKind::Tests => "tests",
+            SectionKind::OutputFormat => "output_format",
+        }
+    }
+
+    /// Finds the section with the given identifier
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.id() == id)
+    }
+
+    /// Heading used in the preview, in Portuguese
+    pub fn title(&self) -> &'static str {
+        self.title_in(Language::Portuguese)
+    }
+
+    /// Heading used in the preview and in documents written in `language`
+    pub fn title_in(&self, language: Language) -> &'static str {
+        match (language, self) {
+            (Language::Portuguese, SectionKind::FewShot) => "Few-Shot Examples",
+            (Language::Portuguese, SectionKind::Context) => "Contexto",
+            (Language::Portuguese, SectionKind::MainContent) => "Conteúdo Principal",
+            (Language::Portuguese, SectionKind::AuxiliaryContent) => "Conteúdo Auxiliar",
+            (Language::Portuguese, SectionKind::Limitations) => "Limitações",
+            (Language::Portuguese, SectionKind::Refactoring) => "Refatoração (Código)",
+            (Language::Portuguese, SectionKind::Guidance) => "Orientações",
+            (Language::Portuguese, SectionKind::Tests) => "Testes",
+            (Language::Portuguese, SectionKind::OutputFormat) => "Formato de Saída",
+            (Language::English, SectionKind::FewShot) => "Few-Shot Examples",
+            (Language::English, SectionKind::Context) => "Context",
+            (Language::English, SectionKind::MainContent) => "Main Content",
+            (Language::English, SectionKind::AuxiliaryContent) => "Auxiliary Content",
+            (Language::English, SectionKind::Limitations) => "Limitations",
+            (Language::English, SectionKind::Refactoring) => "Refactoring (Code)",
+            (Language::English, SectionKind::Guidance) => "Guidance",
+            (Language::English, SectionKind::Tests) => "Tests",
+            (Language::English, SectionKind::OutputFormat) => "Output Format",
+            (Language::Spanish, SectionKind::FewShot) => "Ejemplos Few-Shot",
+            (Language::Spanish, SectionKind::Context) => "Contexto",
+            (Language::Spanish, SectionKind::MainContent) => "Contenido Principal",
+            (Language::Spanish, SectionKind::AuxiliaryContent) => "Contenido Auxiliar",
+            (Language::Spanish, SectionKind::Limitations) => "Limitaciones",
+            (Language::Spanish, SectionKind::Refactoring) => "Refactorización (Código)",
+            (Language::Spanish, SectionKind::Guidance) => "Orientaciones",
+            (Language::Spanish, SectionKind::Tests) => "Pruebas",
+            (Language::Spanish, SectionKind::OutputFormat) => "Formato de Salida",
+        }
+    }
+
+    /// Position of the section in prompt order
+    pub fn index(&self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|kind| kind == self)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_section_kind_id_round_trip() {
+        for kind in SectionKind::ALL {
+            assert_eq!(SectionKind::from_id(kind.id()), Some(kind));
+        }
+        assert_eq!(SectionKind::from_id("desconhecida"), None);
+    }
+
+    #[test]
+    fn test_section_kind_titles() {
+        assert_eq!(SectionKind::Context.title(), "Contexto");
+        assert_eq!(SectionKind::OutputFormat.title(), "Formato de Saída");
+        assert_eq!(
+            SectionKind::OutputFormat.title_in(Language::English),
+            "Output Format"
+        );
+        assert_eq!(SectionKind::Tests.title_in(Language::Spanish), "Pruebas");
+    }
+
+    #[test]
+    fn test_section_kind_index_follows_prompt_order() {
+        assert_eq!(SectionKind::FewShot.index(), 0);
+        assert_eq!(SectionKind::AuxiliaryContent.index(), 3);
+        assert_eq!(SectionKind::OutputFormat.index(), 8);
+    }
+}
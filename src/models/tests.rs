@@ -1,19 +1,206 @@
+/// Kinds of tests the AI is required to write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestKind {
+    Unit,
+    Integration,
+    Property,
+}
+
+impl TestKind {
+    /// All kinds, in the order they are rendered
+    pub const ALL: [TestKind; 3] = [TestKind::Unit, TestKind::Integration, TestKind::Property];
+
+    /// Label used both in the UI and in the rendered instructions
+    pub fn label(&self) -> &'static str {
+        match self {
+            TestKind::Unit => "unitários",
+            TestKind::Integration => "integração",
+            TestKind::Property => "propriedades",
+        }
+    }
+
+    /// Finds the kind matching a rendered label (case-insensitive)
+    pub fn from_label(label: &str) -> Option<Self> {
+        let label = label.trim().to_lowercase();
+        Self::ALL.into_iter().find(|kind| kind.label() == label)
+    }
+}
+
+/// Test frameworks offered in the UI; any other name can still be used
+pub const KNOWN_FRAMEWORKS: [&str; 8] = [
+    "cargo test",
+    "proptest",
+    "pytest",
+    "unittest",
+    "jest",
+    "vitest",
+    "JUnit",
+    "go test",
+];
+
+/// An explicit test case in given/when/then form.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TestCase {
+    /// Short name of the test case
+    pub name: String,
+    /// Preconditions
+    pub given: String,
+    /// Action under test
+    pub when: String,
+    /// Expected outcome
+    pub then: String,
+}
+
+const FRAMEWORK_LABEL: &str = "Framework de testes:";
+const KINDS_LABEL: &str = "Tipos de teste obrigatórios:";
+const COVERAGE_LABEL: &str = "Cobertura mínima:";
+const CASES_LABEL: &str = "Casos de teste obrigatórios:";
+const GIVEN_LABEL: &str = "- Dado:";
+const WHEN_LABEL: &str = "- Quando:";
+const THEN_LABEL: &str = "- Então:";
+
 /// Represents testing requirements for developer-related AI outputs.
 #[derive(Debug, Clone)]
 pub struct Tests {
     /// Testing instructions like unit tests, integration tests, etc.
     pub text: String,
+    /// Test framework to use (e.g. "cargo test", "pytest"); empty when unspecified
+    pub framework: String,
+    /// Required kinds of tests
+    pub kinds: Vec<TestKind>,
+    /// Minimum coverage percentage
+    pub coverage_target: Option<u8>,
+    /// Explicit test cases
+    pub cases: Vec<TestCase>,
 }
 
 impl Tests {
     pub fn new() -> Self {
         Self {
             text: String::new(),
+            framework: String::new(),
+            kinds: Vec::new(),
+            coverage_target: None,
+            cases: Vec::new(),
+        }
+    }
+
+    /// Returns true when neither free text nor structured requirements are set
+    pub fn is_empty(&self) -> bool {
+        self.text.trim().is_empty()
+            && self.framework.trim().is_empty()
+            && self.kinds.is_empty()
+            && self.coverage_target.is_none()
+            && self.cases.is_empty()
+    }
+
+    /// Renders the section body: free text followed by the structured requirements
+    pub fn render_body(&self) -> String {
+        let mut lines: Vec<String> = Vec::new();
+
+        if !self.framework.trim().is_empty() {
+            lines.push(format!("{} {}", FRAMEWORK_LABEL, self.framework.trim()));
+        }
+
+        if !self.kinds.is_empty() {
+            let kinds: Vec<&str> = TestKind::ALL
+                .iter()
+                .filter(|kind| self.kinds.contains(kind))
+                .map(|kind| kind.label())
+                .collect();
+            lines.push(format!("{} {}", KINDS_LABEL, kinds.join(", ")));
+        }
+
+        if let Some(coverage) = self.coverage_target {
+            lines.push(format!("{} {}%", COVERAGE_LABEL, coverage));
+        }
+
+        if !self.cases.is_empty() {
+            lines.push(CASES_LABEL.to_string());
+            for (i, case) in self.cases.iter().enumerate() {
+                lines.push(format!("{}. {}", i + 1, case.name.trim()));
+                lines.push(format!("   {} {}", GIVEN_LABEL, case.given.trim()));
+                lines.push(format!("   {} {}", WHEN_LABEL, case.when.trim()));
+                lines.push(format!("   {} {}", THEN_LABEL, case.then.trim()));
+            }
+        }
+
+        if lines.is_empty() {
+            return self.text.clone();
+        }
+
+        let structured = lines.join("\n");
+        if self.text.trim().is_empty() {
+            structured
+        } else {
+            format!("{}\n\n{}", self.text.trim_end(), structured)
+        }
+    }
+
+    /// Parses a rendered section body back into structured requirements.
+    /// Lines that are not part of the structured block are kept as free text.
+    pub fn parse(body: &str) -> Self {
+        let mut tests = Tests::new();
+        let mut text_lines: Vec<&str> = Vec::new();
+        let mut in_cases = false;
+
+        for raw_line in body.lines() {
+            let line = raw_line.trim();
+
+            if let Some(framework) = line.strip_prefix(FRAMEWORK_LABEL) {
+                tests.framework = framework.trim().to_string();
+                in_cases = false;
+            } else if let Some(kinds) = line.strip_prefix(KINDS_LABEL) {
+                tests.kinds = kinds.split(',').filter_map(TestKind::from_label).collect();
+                in_cases = false;
+            } else if let Some(coverage) = line.strip_prefix(COVERAGE_LABEL) {
+                tests.coverage_target = coverage.trim().trim_end_matches('%').trim().parse().ok();
+                in_cases = false;
+            } else if line == CASES_LABEL {
+                in_cases = true;
+            } else if in_cases {
+                if let Some(given) = line.strip_prefix(GIVEN_LABEL) {
+                    if let Some(case) = tests.cases.last_mut() {
+                        case.given = given.trim().to_string();
+                    }
+                } else if let Some(when) = line.strip_prefix(WHEN_LABEL) {
+                    if let Some(case) = tests.cases.last_mut() {
+                        case.when = when.trim().to_string();
+                    }
+                } else if let Some(then) = line.strip_prefix(THEN_LABEL) {
+                    if let Some(case) = tests.cases.last_mut() {
+                        case.then = then.trim().to_string();
+                    }
+                } else if let Some(name) = parse_numbered_item(line) {
+                    tests.cases.push(TestCase {
+                        name: name.to_string(),
+                        ..TestCase::default()
+                    });
+                } else if !line.is_empty() {
+                    in_cases = false;
+                    text_lines.push(raw_line);
+                }
+            } else {
+                text_lines.push(raw_line);
+            }
         }
+
+        tests.text = text_lines.join("\n").trim().to_string();
+        tests
     }
 
     pub fn generate_text(&self) -> String {
-        format!("<START_TESTS>\n{}\n<END_TESTS>\n", self.text)
+        format!("<START_TESTS>\n{}\n<END_TESTS>\n", self.render_body())
+    }
+}
+
+// Extracts the item text from a line like "3. nome do caso"
+fn parse_numbered_item(line: &str) -> Option<&str> {
+    let (number, rest) = line.split_once(". ")?;
+    if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) {
+        Some(rest.trim())
+    } else {
+        None
     }
 }
 
@@ -147,4 +334,104 @@ mod tests {
         assert!(generated.contains("80%"));
         assert!(generated.contains("automatizados"));
     }
+
+    fn create_structured_tests() -> Tests {
+        let mut tests = Tests::new();
+        tests.text = "Mantenha os testes independentes entre si.".to_string();
+        tests.framework = "cargo test".to_string();
+        tests.kinds = vec![TestKind::Property, TestKind::Unit];
+        tests.coverage_target = Some(85);
+        tests.cases = vec![
+            TestCase {
+                name: "Parse de arquivo vazio".to_string(),
+                given: "um arquivo sem conteúdo".to_string(),
+                when: "o parser é executado".to_string(),
+                then: "nenhuma seção é preenchida".to_string(),
+            },
+            TestCase {
+                name: "Salvar com caminho inválido".to_string(),
+                given: "um diretório inexistente".to_string(),
+                when: "o prompt é salvo".to_string(),
+                then: "um erro é retornado".to_string(),
+            },
+        ];
+        tests
+    }
+
+    #[test]
+    fn test_tests_is_empty() {
+        let mut tests = Tests::new();
+        assert!(tests.is_empty());
+
+        tests.coverage_target = Some(70);
+        assert!(!tests.is_empty());
+    }
+
+    #[test]
+    fn test_tests_structured_rendering() {
+        let tests = create_structured_tests();
+        let body = tests.render_body();
+
+        assert!(body.starts_with("Mantenha os testes independentes entre si."));
+        assert!(body.contains("Framework de testes: cargo test"));
+        // Tipos seguem a ordem canônica
+        assert!(body.contains("Tipos de teste obrigatórios: unitários, propriedades"));
+        assert!(body.contains("Cobertura mínima: 85%"));
+        assert!(body.contains("Casos de teste obrigatórios:"));
+        assert!(body.contains("1. Parse de arquivo vazio"));
+        assert!(body.contains("   - Dado: um arquivo sem conteúdo"));
+        assert!(body.contains("   - Quando: o parser é executado"));
+        assert!(body.contains("   - Então: nenhuma seção é preenchida"));
+        assert!(body.contains("2. Salvar com caminho inválido"));
+    }
+
+    #[test]
+    fn test_tests_parse_round_trip() {
+        let original = create_structured_tests();
+        let parsed = Tests::parse(&original.render_body());
+
+        assert_eq!(parsed.text, original.text);
+        assert_eq!(parsed.framework, original.framework);
+        assert_eq!(parsed.kinds, vec![TestKind::Unit, TestKind::Property]);
+        assert_eq!(parsed.coverage_target, Some(85));
+        assert_eq!(parsed.cases, original.cases);
+    }
+
+    #[test]
+    fn test_tests_parse_trimmed_lines() {
+        // O parser de arquivos remove indentação e linhas vazias
+        let original = create_structured_tests();
+        let body = original.render_body();
+        let trimmed: Vec<&str> = body
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        let trimmed = trimmed.join("\n");
+
+        let parsed = Tests::parse(&trimmed);
+        assert_eq!(parsed.cases, original.cases);
+        assert_eq!(parsed.coverage_target, Some(85));
+    }
+
+    #[test]
+    fn test_tests_parse_plain_text() {
+        let text = "Testes obrigatórios:\n1. Testes unitários\n2. Testes de integração";
+        let parsed = Tests::parse(text);
+
+        // Listas numeradas fora do bloco de casos continuam como texto livre
+        assert_eq!(parsed.text, text);
+        assert!(parsed.cases.is_empty());
+        assert!(parsed.framework.is_empty());
+    }
+
+    #[test]
+    fn test_test_kind_from_label() {
+        assert_eq!(TestKind::from_label(" Unitários "), Some(TestKind::Unit));
+        assert_eq!(
+            TestKind::from_label("integração"),
+            Some(TestKind::Integration)
+        );
+        assert_eq!(TestKind::from_label("carga"), None);
+    }
 }
@@ -0,0 +1,73 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Name of the application folder inside the per-user directories
+pub const APP_DIR_NAME: &str = "prompt_builder_gui";
+
+/// Per-user data directory of the application: `%LOCALAPPDATA%` on Windows,
+/// `~/Library/Application Support` on macOS and `$XDG_DATA_HOME` (by default
+/// `~/.local/share`) elsewhere. None when no home directory is known.
+pub fn data_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        env_path("LOCALAPPDATA").or_else(|| env_path("APPDATA"))
+    } else if cfg!(target_os = "macos") {
+        home_dir().map(|home| home.join("Library").join("Application Support"))
+    } else {
+        xdg_base(env_path("XDG_DATA_HOME"), home_dir(), ".local/share")
+    };
+    base.map(|dir| dir.join(APP_DIR_NAME))
+}
+
+/// Per-user configuration directory of the application: `%APPDATA%` on
+/// Windows, `~/Library/Application Support` on macOS and `$XDG_CONFIG_HOME`
+/// (by default `~/.config`) elsewhere. None when no home directory is known.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        env_path("APPDATA").or_else(|| env_path("LOCALAPPDATA"))
+    } else if cfg!(target_os = "macos") {
+        home_dir().map(|home| home.join("Library").join("Application Support"))
+    } else {
+        xdg_base(env_path("XDG_CONFIG_HOME"), home_dir(), ".config")
+    };
+    base.map(|dir| dir.join(APP_DIR_NAME))
+}
+
+// Home directory of the current user
+fn home_dir() -> Option<PathBuf> {
+    env_path("HOME").or_else(|| env_path("USERPROFILE"))
+}
+
+// Reads a path from the environment, ignoring empty values
+fn env_path(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+// XDG base directory: the variable when it holds an absolute path, otherwise
+// the default below the home directory
+fn xdg_base(variable: Option<PathBuf>, home: Option<PathBuf>, default: &str) -> Option<PathBuf> {
+    variable
+        .filter(|path| path.is_absolute())
+        .or_else(|| home.map(|home| home.join(Path::new(default))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xdg_base_prefers_absolute_variable() {
+        let home = Some(PathBuf::from("/home/ana"));
+        assert_eq!(
+            xdg_base(Some(PathBuf::from("/dados")), home.clone(), ".local/share"),
+            Some(PathBuf::from("/dados"))
+        );
+        // Caminhos relativos são ignorados, como pede a especificação XDG
+        assert_eq!(
+            xdg_base(Some(PathBuf::from("dados")), home.clone(), ".local/share"),
+            Some(PathBuf::from("/home/ana/.local/share"))
+        );
+        assert_eq!(xdg_base(None, None, ".local/share"), None);
+    }
+}
@@ -0,0 +1,753 @@
+use crate::models::attachment::{Attachment, AttachmentKind, AttachmentLimits};
+use crate::models::auxiliary_content::AuxiliaryContent;
+use crate::services::source_reader::{detect_language, fenced_block};
+use crate::services::token_counter::estimate_tokens;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Number of leading bytes inspected when looking for binary content
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// A text file read from an attachment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedFile {
+    /// Path shown in the prompt, relative to the attachment
+    pub relative_path: String,
+    pub path: PathBuf,
+    pub content: String,
+    pub tokens: usize,
+}
+
+/// Why a file matched by an attachment was left out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    Binary,
+    TooLarge(u64),
+    TokenLimit,
+    Unreadable(String),
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::Binary => write!(f, "binário"),
+            SkipReason::TooLarge(bytes) => write!(f, "muito grande: {} bytes", bytes),
+            SkipReason::TokenLimit => write!(f, "limite de tokens atingido"),
+            SkipReason::Unreadable(error) => write!(f, "erro de leitura: {}", error),
+        }
+    }
+}
+
+/// A file left out of the prompt and the reason.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedFile {
+    pub relative_path: String,
+    pub reason: SkipReason,
+}
+
+/// Result of reading a set of attachments.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttachmentReport {
+    pub files: Vec<ResolvedFile>,
+    pub skipped: Vec<SkippedFile>,
+}
+
+impl AttachmentReport {
+    /// Total estimated tokens of the included files
+    pub fn total_tokens(&self) -> usize {
+        self.files.iter().map(|file| file.tokens).sum()
+    }
+
+    /// Renders every included file as a fenced block labelled with its relative path
+    pub fn render(&self) -> String {
+        self.files
+            .iter()
+            .map(|file| {
+                format!(
+                    "Arquivo: `{}`\n{}",
+                    file.relative_path,
+                    fenced_block(detect_language(&file.path), &file.content)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// One-line summary for the UI
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} arquivo(s) anexado(s), ~{} tokens",
+            self.files.len(),
+            self.total_tokens()
+        );
+        if !self.skipped.is_empty() {
+            let skipped: Vec<String> = self
+                .skipped
+                .iter()
+                .map(|file| format!("{} ({})", file.relative_path, file.reason))
+                .collect();
+            summary.push_str(&format!(
+                "; {} ignorado(s): {}",
+                self.skipped.len(),
+                skipped.join(", ")
+            ));
+        }
+        summary
+    }
+}
+
+/// Reads every attachment, applying include/exclude patterns, `.gitignore` rules and limits.
+/// Files are visited in a stable (sorted) order and each file is included at most once.
+pub fn read_attachments(attachments: &[Attachment], limits: &AttachmentLimits) -> AttachmentReport {
+    let mut report = AttachmentReport::default();
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+    let mut total_tokens = 0;
+
+    for attachment in attachments {
+        for (path, relative_path) in collect_files(attachment) {
+            let key = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+            if !seen.insert(key) {
+                continue;
+            }
+
+            match read_text_file(&path, limits) {
+                Ok(content) => {
+                    let tokens = estimate_tokens(&content);
+                    if total_tokens + tokens > limits.max_total_tokens {
+                        report.skipped.push(SkippedFile {
+                            relative_path,
+                            reason: SkipReason::TokenLimit,
+                        });
+                        continue;
+                    }
+                    total_tokens += tokens;
+                    report.files.push(ResolvedFile {
+                        relative_path,
+                        path,
+                        content,
+                        tokens,
+                    });
+                }
+                Err(reason) => report.skipped.push(SkippedFile {
+                    relative_path,
+                    reason,
+                }),
+            }
+        }
+    }
+
+    report
+}
+
+/// Renders the auxiliary content as it goes into the prompt, reading the
+/// attached files from disk
+pub fn render_auxiliary(auxiliary: &AuxiliaryContent) -> String {
+    if auxiliary.attachments.is_empty() {
+        return auxiliary.data.clone();
+    }
+    auxiliary
+        .render_with_files(&read_attachments(&auxiliary.attachments, &auxiliary.limits).render())
+}
+
+/// Reads a file as UTF-8 text, rejecting large and binary files
+pub fn read_text_file(path: &Path, limits: &AttachmentLimits) -> Result<String, SkipReason> {
+    let metadata = fs::metadata(path).map_err(|e| SkipReason::Unreadable(e.to_string()))?;
+    if metadata.len() > limits.max_file_bytes {
+        return Err(SkipReason::TooLarge(metadata.len()));
+    }
+
+    let bytes = fs::read(path).map_err(|e| SkipReason::Unreadable(e.to_string()))?;
+    if bytes.iter().take(BINARY_SNIFF_LEN).any(|byte| *byte == 0) {
+        return Err(SkipReason::Binary);
+    }
+    String::from_utf8(bytes).map_err(|_| SkipReason::Binary)
+}
+
+/// Lists the files an attachment refers to as (path, label) pairs, in sorted order.
+/// Include/exclude patterns and, when enabled, `.gitignore` rules are applied.
+pub fn collect_files(attachment: &Attachment) -> Vec<(PathBuf, String)> {
+    let mut files = Vec::new();
+
+    match attachment.kind {
+        AttachmentKind::File => {
+            let label = attachment
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| attachment.path.display().to_string());
+            files.push((attachment.path.clone(), label));
+        }
+        AttachmentKind::Directory => {
+            let prefix = attachment
+                .path
+                .file_name()
+                .map(|name| format!("{}/", name.to_string_lossy()))
+                .unwrap_or_default();
+            // Walk the canonical path so it lines up with the ancestor .gitignore bases
+            let root = fs::canonicalize(&attachment.path).unwrap_or(attachment.path.clone());
+            let ignores = if attachment.respect_gitignore {
+                ancestor_gitignores(&root)
+            } else {
+                Vec::new()
+            };
+            walk_directory(&root, "", attachment, ignores, &mut |path, rel| {
+                files.push((path.to_path_buf(), format!("{}{}", prefix, rel)));
+            });
+        }
+        AttachmentKind::Glob => {
+            let pattern = attachment.path.to_string_lossy().replace('\\', "/");
+            let (base, rest) = split_glob_base(&pattern);
+            let base = fs::canonicalize(&base).unwrap_or(base);
+            let ignores = if attachment.respect_gitignore {
+                ancestor_gitignores(&base)
+            } else {
+                Vec::new()
+            };
+            walk_directory(&base, "", attachment, ignores, &mut |path, rel| {
+                if glob_match(&rest, rel) {
+                    files.push((path.to_path_buf(), rel.to_string()));
+                }
+            });
+        }
+    }
+
+    files
+}
+
+// Recursively visits the files of a directory in sorted order. Symlinked
+// directories are skipped so that a link back to an ancestor cannot recurse
+// forever
+fn walk_directory(
+    dir: &Path,
+    relative_dir: &str,
+    attachment: &Attachment,
+    mut ignores: Vec<GitIgnore>,
+    visit: &mut dyn FnMut(&Path, &str),
+) {
+    if attachment.respect_gitignore {
+        if let Some(gitignore) = GitIgnore::load(dir) {
+            ignores.push(gitignore);
+        }
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<(PathBuf, bool)> = entries
+        .flatten()
+        .map(|entry| {
+            let is_symlink = entry.file_type().is_ok_and(|kind| kind.is_symlink());
+            (entry.path(), is_symlink)
+        })
+        .collect();
+    entries.sort();
+
+    for (path, is_symlink) in entries {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if name == ".git" {
+            continue;
+        }
+
+        let relative_path = if relative_dir.is_empty() {
+            name.clone()
+        } else {
+            format!("{}/{}", relative_dir, name)
+        };
+        let is_dir = path.is_dir();
+
+        if is_dir && is_symlink {
+            continue;
+        }
+        if is_ignored(&ignores, &path, is_dir) {
+            continue;
+        }
+
+        if is_dir {
+            walk_directory(&path, &relative_path, attachment, ignores.clone(), visit);
+        } else if matches_filters(attachment, &relative_path) {
+            visit(&path, &relative_path);
+        }
+    }
+}
+
+// Applies the include/exclude patterns of an attachment to a relative path
+fn matches_filters(attachment: &Attachment, relative_path: &str) -> bool {
+    let name = relative_path.rsplit('/').next().unwrap_or(relative_path);
+    let matches = |pattern: &String| {
+        if pattern.contains('/') {
+            glob_match(pattern.trim_start_matches('/'), relative_path)
+        } else {
+            glob_match(pattern, name)
+        }
+    };
+
+    (attachment.include.is_empty() || attachment.include.iter().any(matches))
+        && !attachment.exclude.iter().any(matches)
+}
+
+// Splits a glob into the directory before the first wildcard and the remaining pattern
+fn split_glob_base(pattern: &str) -> (PathBuf, String) {
+    let mut base: Vec<&str> = Vec::new();
+    let mut rest: Vec<&str> = Vec::new();
+
+    for segment in pattern.split('/') {
+        if rest.is_empty() && !segment.contains(['*', '?', '[']) {
+            base.push(segment);
+        } else {
+            rest.push(segment);
+        }
+    }
+
+    // A pattern without wildcards names a single file
+    if rest.is_empty() {
+        if let Some(last) = base.pop() {
+            rest.push(last);
+        }
+    }
+
+    let base = if base.is_empty() {
+        PathBuf::from(".")
+    } else if base == [""] {
+        PathBuf::from("/")
+    } else {
+        PathBuf::from(base.join("/"))
+    };
+    (base, rest.join("/"))
+}
+
+/// Matches a path against a glob pattern, segment by segment.
+/// Supports `*`, `?`, `[abc]` classes and `**` for any number of directories.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let path: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match_segments(&pattern, &path)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.first() {
+        None => path.is_empty(),
+        Some(&"**") => (0..=path.len()).any(|skip| match_segments(&pattern[1..], &path[skip..])),
+        Some(segment) => {
+            !path.is_empty()
+                && match_segment(
+                    &segment.chars().collect::<Vec<_>>(),
+                    &path[0].chars().collect::<Vec<_>>(),
+                )
+                && match_segments(&pattern[1..], &path[1..])
+        }
+    }
+}
+
+fn match_segment(pattern: &[char], name: &[char]) -> bool {
+    match pattern.first() {
+        None => name.is_empty(),
+        Some('*') => (0..=name.len()).any(|skip| match_segment(&pattern[1..], &name[skip..])),
+        Some('?') => !name.is_empty() && match_segment(&pattern[1..], &name[1..]),
+        Some('[') => {
+            let Some(close) = pattern.iter().position(|c| *c == ']') else {
+                return name.first() == Some(&'[') && match_segment(&pattern[1..], &name[1..]);
+            };
+            let Some(c) = name.first() else {
+                return false;
+            };
+            let class = &pattern[1..close];
+            let (negated, class) = match class.first() {
+                Some('!') | Some('^') => (true, &class[1..]),
+                _ => (false, class),
+            };
+            let mut matched = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == '-' {
+                    matched |= class[i] <= *c && *c <= class[i + 2];
+                    i += 3;
+                } else {
+                    matched |= class[i] == *c;
+                    i += 1;
+                }
+            }
+            matched != negated && match_segment(&pattern[close + 1..], &name[1..])
+        }
+        Some(literal) => name.first() == Some(literal) && match_segment(&pattern[1..], &name[1..]),
+    }
+}
+
+/// A single rule of a `.gitignore` file.
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    pattern: String,
+    negated: bool,
+    directory_only: bool,
+    anchored: bool,
+}
+
+/// Rules of one `.gitignore` file, relative to the directory containing it.
+#[derive(Debug, Clone)]
+struct GitIgnore {
+    base: PathBuf,
+    rules: Vec<IgnoreRule>,
+}
+
+impl GitIgnore {
+    fn load(dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(dir.join(".gitignore")).ok()?;
+        Some(Self::parse(dir, &content))
+    }
+
+    fn parse(base: &Path, content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(|line| line.trim_end())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let (directory_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                IgnoreRule {
+                    anchored: line.contains('/'),
+                    pattern: line.trim_start_matches('/').to_string(),
+                    negated,
+                    directory_only,
+                }
+            })
+            .collect();
+
+        Self {
+            base: base.to_path_buf(),
+            rules,
+        }
+    }
+
+    // Returns Some(true/false) when a rule decides, None when no rule matches
+    fn decide(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.base).ok()?;
+        let relative: Vec<String> = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+                _ => None,
+            })
+            .collect();
+        let relative = relative.join("/");
+        let name = relative.rsplit('/').next().unwrap_or(&relative);
+
+        let mut decision = None;
+        for rule in &self.rules {
+            if rule.directory_only && !is_dir {
+                continue;
+            }
+            let matched = if rule.anchored {
+                glob_match(&rule.pattern, &relative)
+            } else {
+                glob_match(&rule.pattern, name)
+            };
+            if matched {
+                decision = Some(!rule.negated);
+            }
+        }
+        decision
+    }
+}
+
+// Later (deeper) .gitignore files take precedence over earlier ones
+fn is_ignored(ignores: &[GitIgnore], path: &Path, is_dir: bool) -> bool {
+    ignores
+        .iter()
+        .rev()
+        .find_map(|gitignore| gitignore.decide(path, is_dir))
+        .unwrap_or(false)
+}
+
+// Loads the .gitignore files of the ancestors of a directory, up to the repository root
+fn ancestor_gitignores(dir: &Path) -> Vec<GitIgnore> {
+    let mut ignores = Vec::new();
+    for ancestor in dir.ancestors().skip(1) {
+        if let Some(gitignore) = GitIgnore::load(ancestor) {
+            ignores.push(gitignore);
+        }
+        if ancestor.join(".git").exists() {
+            break;
+        }
+    }
+
+    // Outermost first, so that nearer files override them
+    ignores.reverse();
+    ignores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, relative: &str, content: &[u8]) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn labels(report: &AttachmentReport) -> Vec<String> {
+        report
+            .files
+            .iter()
+            .map(|file| file.relative_path.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.rs", "main.rs"));
+        assert!(!glob_match("*.rs", "main.py"));
+        assert!(glob_match("src/**/*.rs", "src/models/context.rs"));
+        assert!(glob_match("src/**/*.rs", "src/main.rs"));
+        assert!(glob_match("**/target", "a/b/target"));
+        assert!(glob_match("file?.txt", "file1.txt"));
+        assert!(glob_match("[a-c]*.md", "blog.md"));
+        assert!(!glob_match("[!a-c]*.md", "blog.md"));
+        assert!(!glob_match("src/*.rs", "src/models/context.rs"));
+    }
+
+    #[test]
+    fn test_read_single_file() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        write(temp_dir.path(), "main.rs", b"fn main() {}\n");
+
+        let attachment = Attachment::new(temp_dir.path().join("main.rs"), AttachmentKind::File);
+        let report = read_attachments(&[attachment], &AttachmentLimits::default());
+
+        assert_eq!(labels(&report), vec!["main.rs"]);
+        assert_eq!(
+            report.render(),
+            "Arquivo: `main.rs`\n```rust\nfn main() {}\n```"
+        );
+    }
+
+    #[test]
+    fn test_read_directory_with_filters() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let project = temp_dir.path().join("projeto");
+        write(&project, "src/main.rs", b"fn main() {}");
+        write(&project, "src/lib.rs", b"pub mod a;");
+        write(&project, "src/gerado.rs", b"// gerado");
+        write(&project, "README.md", b"# Projeto");
+
+        let mut attachment = Attachment::new(&project, AttachmentKind::Directory);
+        attachment.include = vec!["*.rs".to_string()];
+        attachment.exclude = vec!["gerado.rs".to_string()];
+
+        let report = read_attachments(&[attachment], &AttachmentLimits::default());
+        assert_eq!(
+            labels(&report),
+            vec!["projeto/src/lib.rs", "projeto/src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn test_read_directory_respects_gitignore() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let project = temp_dir.path().join("projeto");
+        write(
+            &project,
+            ".gitignore",
+            b"target/\n*.log\n!importante.log\n/build.txt\n",
+        );
+        write(&project, "src/main.rs", b"fn main() {}");
+        write(&project, "target/debug/app.rs", b"// compilado");
+        write(&project, "debug.log", b"log");
+        write(&project, "importante.log", b"manter");
+        write(&project, "build.txt", b"raiz");
+        write(&project, "docs/build.txt", b"aninhado");
+
+        let attachment = Attachment::new(&project, AttachmentKind::Directory);
+        let report = read_attachments(
+            std::slice::from_ref(&attachment),
+            &AttachmentLimits::default(),
+        );
+        assert_eq!(
+            labels(&report),
+            vec![
+                "projeto/.gitignore",
+                "projeto/docs/build.txt",
+                "projeto/importante.log",
+                "projeto/src/main.rs",
+            ]
+        );
+
+        let mut without_gitignore = attachment;
+        without_gitignore.respect_gitignore = false;
+        let report = read_attachments(&[without_gitignore], &AttachmentLimits::default());
+        assert!(labels(&report).contains(&"projeto/target/debug/app.rs".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_loop_is_not_followed() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let project = temp_dir.path().join("projeto");
+        write(&project, "src/main.rs", b"fn main() {}");
+        // Link de volta para a raiz do projeto, que recursaria para sempre
+        std::os::unix::fs::symlink(&project, project.join("src").join("loop")).unwrap();
+
+        let directory = Attachment::new(&project, AttachmentKind::Directory);
+        let report = read_attachments(&[directory], &AttachmentLimits::default());
+        assert_eq!(labels(&report), vec!["projeto/src/main.rs"]);
+
+        let pattern = project.join("**").join("*.rs");
+        let glob = Attachment::new(pattern, AttachmentKind::Glob);
+        let report = read_attachments(&[glob], &AttachmentLimits::default());
+        assert_eq!(labels(&report), vec!["src/main.rs"]);
+    }
+
+    #[test]
+    fn test_read_glob() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        write(temp_dir.path(), "src/main.rs", b"fn main() {}");
+        write(
+            temp_dir.path(),
+            "src/models/context.rs",
+            b"pub struct Context;",
+        );
+        write(temp_dir.path(), "src/notas.txt", b"notas");
+
+        let pattern = temp_dir.path().join("src").join("**").join("*.rs");
+        let attachment = Attachment::new(pattern, AttachmentKind::Glob);
+        let report = read_attachments(&[attachment], &AttachmentLimits::default());
+
+        assert_eq!(labels(&report), vec!["main.rs", "models/context.rs"]);
+    }
+
+    #[test]
+    fn test_binary_and_large_files_are_skipped() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        write(
+            temp_dir.path(),
+            "imagem.png",
+            &[0x89, b'P', b'N', b'G', 0, 1, 2],
+        );
+        write(temp_dir.path(), "grande.txt", "x".repeat(2048).as_bytes());
+        write(temp_dir.path(), "ok.txt", b"texto");
+
+        let limits = AttachmentLimits {
+            max_file_bytes: 1024,
+            ..AttachmentLimits::default()
+        };
+        let attachment = Attachment::new(temp_dir.path(), AttachmentKind::Directory);
+        let report = read_attachments(&[attachment], &limits);
+
+        assert_eq!(report.files.len(), 1);
+        assert!(report.files[0].relative_path.ends_with("ok.txt"));
+        assert_eq!(report.skipped.len(), 2);
+        assert!(report
+            .skipped
+            .iter()
+            .any(|file| file.relative_path.ends_with("grande.txt")
+                && file.reason == SkipReason::TooLarge(2048)));
+        assert!(report
+            .skipped
+            .iter()
+            .any(|file| file.relative_path.ends_with("imagem.png")
+                && file.reason == SkipReason::Binary));
+    }
+
+    #[test]
+    fn test_token_limit() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        write(temp_dir.path(), "a.txt", "a".repeat(40).as_bytes());
+        write(temp_dir.path(), "b.txt", "b".repeat(40).as_bytes());
+        write(temp_dir.path(), "c.txt", "c".repeat(8).as_bytes());
+
+        let limits = AttachmentLimits {
+            max_total_tokens: 12,
+            ..AttachmentLimits::default()
+        };
+        let attachment = Attachment::new(temp_dir.path(), AttachmentKind::Directory);
+        let report = read_attachments(&[attachment], &limits);
+
+        // a.txt (10 tokens) cabe, b.txt (10) estoura o limite, c.txt (2) ainda cabe
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.total_tokens(), 12);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].reason, SkipReason::TokenLimit);
+    }
+
+    #[test]
+    fn test_duplicate_files_included_once() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        write(temp_dir.path(), "main.rs", b"fn main() {}");
+
+        let file = Attachment::new(temp_dir.path().join("main.rs"), AttachmentKind::File);
+        let dir = Attachment::new(temp_dir.path(), AttachmentKind::Directory);
+        let report = read_attachments(&[file, dir], &AttachmentLimits::default());
+
+        assert_eq!(report.files.len(), 1);
+    }
+
+    #[test]
+    fn test_report_summary() {
+        let report = AttachmentReport {
+            files: vec![ResolvedFile {
+                relative_path: "a.rs".to_string(),
+                path: PathBuf::from("a.rs"),
+                content: "abcdefgh".to_string(),
+                tokens: 2,
+            }],
+            skipped: vec![SkippedFile {
+                relative_path: "b.png".to_string(),
+                reason: SkipReason::Binary,
+            }],
+        };
+
+        assert_eq!(
+            report.summary(),
+            "1 arquivo(s) anexado(s), ~2 tokens; 1 ignorado(s): b.png (binário)"
+        );
+    }
+
+    #[test]
+    fn test_render_auxiliary_reads_attachments() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, "[package]\nname = \"demo\"\n").unwrap();
+
+        let mut auxiliary = AuxiliaryContent::new();
+        auxiliary.data = "Configuração do projeto:".to_string();
+        auxiliary
+            .attachments
+            .push(Attachment::new(&path, AttachmentKind::File));
+
+        assert_eq!(
+            render_auxiliary(&auxiliary),
+            "Configuração do projeto:\n\nArquivo: `config.toml`\n```toml\n[package]\nname = \"demo\"\n```"
+        );
+    }
+
+    #[test]
+    fn test_render_auxiliary_reads_attachments_at_render_time() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("notas.md");
+        fs::write(&path, "versão 1").unwrap();
+
+        let mut auxiliary = AuxiliaryContent::new();
+        auxiliary
+            .attachments
+            .push(Attachment::new(&path, AttachmentKind::File));
+
+        fs::write(&path, "versão 2").unwrap();
+        let rendered = render_auxiliary(&auxiliary);
+        assert!(rendered.contains("versão 2"));
+        assert!(!rendered.contains("versão 1"));
+
+        // O corpo do documento continua guardando só a referência
+        assert!(!auxiliary.render_body().contains("versão"));
+    }
+}
@@ -0,0 +1,679 @@
+use crate::models::auxiliary_content::AuxiliaryContent;
+use crate::models::budget::{BudgetPolicy, TruncationStrategy};
+use crate::models::section::SectionKind;
+use crate::services::attachment_reader::read_attachments;
+use crate::services::markdown::FenceTracker;
+use crate::services::prompt_generator::PromptData;
+use crate::services::source_reader::{detect_language, fenced_block};
+use crate::services::token_counter::Tokenizer;
+
+/// Characters per token assumed for a text without any token, matching
+/// [`crate::services::token_counter::estimate_tokens`]
+const CHARS_PER_TOKEN: f64 = 4.0;
+
+/// Visible note left in place of the text removed by a cut
+pub fn omission_note(tokens: usize) -> String {
+    format!("[... {} tokens omitted ...]", tokens)
+}
+
+/// A cut applied to a section (or to one of its attached files).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut {
+    pub section: SectionKind,
+    /// Attached file the cut applies to, when it is not the section text
+    pub file: Option<String>,
+    pub strategy: TruncationStrategy,
+    pub omitted_tokens: usize,
+}
+
+impl Cut {
+    /// Short description for the UI
+    pub fn describe(&self) -> String {
+        let action = match self.strategy {
+            TruncationStrategy::Head => "fim cortado",
+            TruncationStrategy::Tail => "início cortado",
+            TruncationStrategy::MiddleElision => "meio cortado",
+            TruncationStrategy::DropWholeFiles => "removido",
+        };
+        match &self.file {
+            Some(file) => format!(
+                "{}: `{}` {} (~{} tokens)",
+                self.section.title(),
+                file,
+                action,
+                self.omitted_tokens
+            ),
+            None => format!(
+                "{}: {} (~{} tokens)",
+                self.section.title(),
+                action,
+                self.omitted_tokens
+            ),
+        }
+    }
+}
+
+/// Outcome of applying a budget policy to a prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetReport {
+    pub target_tokens: usize,
+    pub original_tokens: usize,
+    pub final_tokens: usize,
+    pub cuts: Vec<Cut>,
+}
+
+impl BudgetReport {
+    /// Returns true when at least one section was cut
+    pub fn is_truncated(&self) -> bool {
+        !self.cuts.is_empty()
+    }
+
+    /// Total tokens removed by all cuts
+    pub fn omitted_tokens(&self) -> usize {
+        self.cuts.iter().map(|cut| cut.omitted_tokens).sum()
+    }
+
+    /// One-line summary for the UI
+    pub fn summary(&self) -> String {
+        if !self.is_truncated() {
+            return format!(
+                "~{} tokens (meta: {})",
+                self.final_tokens, self.target_tokens
+            );
+        }
+
+        let cuts: Vec<String> = self.cuts.iter().map(Cut::describe).collect();
+        format!(
+            "Prompt reduzido de ~{} para ~{} tokens (meta: {}). Cortes: {}",
+            self.original_tokens,
+            self.final_tokens,
+            self.target_tokens,
+            cuts.join("; ")
+        )
+    }
+}
+
+/// Shortens a text to at most `max_tokens` tokens as counted by `tokenizer`,
+/// leaving an omission note where text was removed. Returns the new text and
+/// the number of omitted tokens.
+pub fn truncate_text(
+    text: &str,
+    max_tokens: usize,
+    strategy: TruncationStrategy,
+    tokenizer: Tokenizer,
+) -> (String, usize) {
+    let max_chars = (max_tokens as f64 * chars_per_token(text, tokenizer)) as usize;
+    truncate_chars(text, max_chars, strategy, tokenizer)
+}
+
+// Average characters per token of a text, used to turn token budgets into
+// character budgets
+fn chars_per_token(text: &str, tokenizer: Tokenizer) -> f64 {
+    match tokenizer.count(text) {
+        0 => CHARS_PER_TOKEN,
+        tokens => text.chars().count() as f64 / tokens as f64,
+    }
+}
+
+// Closing line of the fenced block left open at the end of a text, if any
+fn open_fence(text: &str) -> Option<String> {
+    let mut tracker = FenceTracker::new();
+    for line in text.lines() {
+        tracker.feed(line);
+    }
+    tracker.closing_fence()
+}
+
+// Character-based truncation; cuts snap to line boundaries when possible
+fn truncate_chars(
+    text: &str,
+    max_chars: usize,
+    strategy: TruncationStrategy,
+    tokenizer: Tokenizer,
+) -> (String, usize) {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= max_chars {
+        return (text.to_string(), 0);
+    }
+
+    // The note can never be longer than the one for the whole text. It takes
+    // at least the room of as many characters of the text as its own tokens
+    let note = omission_note(tokenizer.count(text));
+    let note_tokens = tokenizer.count(&note) as f64 * chars_per_token(text, tokenizer);
+    let note_len = note.chars().count().max(note_tokens.ceil() as usize);
+    let keep = max_chars.saturating_sub(note_len + 2);
+    if keep == 0 {
+        let omitted = tokenizer.count(text);
+        return (omission_note(omitted), omitted);
+    }
+
+    let head_end = |limit: usize| {
+        let head: String = chars[..limit].iter().collect();
+        match head.rfind('\n') {
+            Some(pos) if pos >= limit / 2 => head[..pos].to_string(),
+            _ => head,
+        }
+    };
+    let tail_start = |limit: usize| {
+        let tail: String = chars[chars.len() - limit..].iter().collect();
+        match tail.find('\n') {
+            Some(pos) if pos <= limit / 2 => tail[pos + 1..].to_string(),
+            _ => tail,
+        }
+    };
+    // Tokens of the text removed between two character positions
+    let omitted_between =
+        |start: usize, end: usize| tokenizer.count(&chars[start..end].iter().collect::<String>());
+
+    match strategy {
+        TruncationStrategy::Tail => {
+            let tail = tail_start(keep);
+            let omitted = omitted_between(0, chars.len() - tail.chars().count());
+            (format!("{}\n{}", omission_note(omitted), tail), omitted)
+        }
+        TruncationStrategy::MiddleElision => {
+            let head = head_end(keep / 2);
+            let tail = tail_start(keep - keep / 2);
+            let omitted = omitted_between(head.chars().count(), chars.len() - tail.chars().count());
+            (
+                format!("{}\n{}\n{}", head, omission_note(omitted), tail),
+                omitted,
+            )
+        }
+        TruncationStrategy::Head | TruncationStrategy::DropWholeFiles => {
+            // A cut inside a fenced block closes it before the note, so that the
+            // note and the sections after it are not read as code
+            let mut head = head_end(keep);
+            if let Some(fence) = open_fence(&head) {
+                head = head_end(keep.saturating_sub(fence.chars().count() + 1));
+            }
+            let omitted = omitted_between(head.chars().count(), chars.len());
+            match open_fence(&head) {
+                Some(fence) => (
+                    format!("{}\n{}\n{}", head, fence, omission_note(omitted)),
+                    omitted,
+                ),
+                None => (format!("{}\n{}", head, omission_note(omitted)), omitted),
+            }
+        }
+    }
+}
+
+/// Applies a budget policy to the prompt. Sections receive their minimum share,
+/// spare tokens go to the highest priorities up to their maximum share, and
+/// sections over their allocation are cut with their strategy. Tokens are
+/// counted with `tokenizer`.
+/// Returns the (possibly) shortened prompt data and a report of every cut.
+pub fn apply_budget(
+    data: &PromptData,
+    policy: &BudgetPolicy,
+    tokenizer: Tokenizer,
+) -> (PromptData, BudgetReport) {
+    let original = data.build_prompt(false);
+    let original_chars = original.chars().count();
+    let original_tokens = tokenizer.count(&original);
+    let mut report = BudgetReport {
+        target_tokens: policy.target_tokens,
+        original_tokens,
+        final_tokens: original_tokens,
+        cuts: Vec::new(),
+    };
+
+    if policy.target_tokens == 0 || original_tokens <= policy.target_tokens {
+        return (data.clone(), report);
+    }
+
+    let bodies: Vec<(SectionKind, String)> = SectionKind::ALL
+        .into_iter()
+        .filter_map(|kind| data.prompt_body(kind).map(|body| (kind, body)))
+        .collect();
+
+    // Markers, separators and headings are not cut, so they come off the budget first
+    let body_chars: usize = bodies.iter().map(|(_, body)| body.chars().count()).sum();
+    let overhead = original_chars.saturating_sub(body_chars);
+    // The target becomes characters at the average ratio of the whole prompt
+    let target_chars =
+        (policy.target_tokens as f64 * chars_per_token(&original, tokenizer)) as usize;
+    let available = target_chars.saturating_sub(overhead);
+
+    let sizes: Vec<(SectionKind, usize)> = bodies
+        .iter()
+        .map(|(kind, body)| (*kind, body.chars().count()))
+        .collect();
+    let allocations = allocate(&sizes, policy, target_chars, available);
+
+    let mut result = data.clone();
+    for ((kind, body), allocation) in bodies.iter().zip(allocations) {
+        if body.chars().count() <= allocation {
+            continue;
+        }
+
+        let strategy = policy.for_section(*kind).strategy;
+        let shortened = match (&data.auxiliary_content, kind) {
+            (Some(auxiliary), SectionKind::AuxiliaryContent)
+                if !auxiliary.attachments.is_empty() =>
+            {
+                truncate_auxiliary(auxiliary, allocation, strategy, tokenizer, &mut report.cuts)
+            }
+            _ => {
+                let (text, omitted) = truncate_chars(body, allocation, strategy, tokenizer);
+                report.cuts.push(Cut {
+                    section: *kind,
+                    file: None,
+                    strategy,
+                    omitted_tokens: omitted,
+                });
+                text
+            }
+        };
+        result.set_section_body(*kind, shortened);
+    }
+
+    report.final_tokens = tokenizer.count(&result.build_prompt(false));
+    (result, report)
+}
+
+// Splits `available` characters between sections according to the policy;
+// shares are fractions of `target_chars`
+fn allocate(
+    sizes: &[(SectionKind, usize)],
+    policy: &BudgetPolicy,
+    target_chars: usize,
+    available: usize,
+) -> Vec<usize> {
+    let share = |fraction: f32| (fraction.clamp(0.0, 1.0) * target_chars as f32) as usize;
+
+    let caps: Vec<usize> = sizes
+        .iter()
+        .map(|(kind, size)| (*size).min(share(policy.for_section(*kind).max_share)))
+        .collect();
+    let mut allocations: Vec<usize> = sizes
+        .iter()
+        .zip(&caps)
+        .map(|((kind, _), cap)| (*cap).min(share(policy.for_section(*kind).min_share)))
+        .collect();
+
+    let mut by_priority: Vec<usize> = (0..sizes.len()).collect();
+    by_priority.sort_by_key(|&index| policy.for_section(sizes[index].0).priority);
+
+    // Minimum shares that do not fit are taken back from the lowest priorities
+    let mut excess = allocations.iter().sum::<usize>().saturating_sub(available);
+    for &index in &by_priority {
+        let taken = excess.min(allocations[index]);
+        allocations[index] -= taken;
+        excess -= taken;
+    }
+
+    // Spare room goes to the highest priorities, up to their maximum share
+    let mut spare = available.saturating_sub(allocations.iter().sum());
+    for &index in by_priority.iter().rev() {
+        let given = spare.min(caps[index] - allocations[index]);
+        allocations[index] += given;
+        spare -= given;
+    }
+
+    allocations
+}
+
+// Shortens the auxiliary content while keeping every attached file in its own fenced block
+fn truncate_auxiliary(
+    auxiliary: &AuxiliaryContent,
+    max_chars: usize,
+    strategy: TruncationStrategy,
+    tokenizer: Tokenizer,
+    cuts: &mut Vec<Cut>,
+) -> String {
+    let report = read_attachments(&auxiliary.attachments, &auxiliary.limits);
+    let text = auxiliary.data.trim_end().to_string();
+    let header = |path: &str| format!("Arquivo: `{}`\n", path);
+    let render = |blocks: &[String]| {
+        let mut parts: Vec<&str> = Vec::new();
+        if !text.trim().is_empty() {
+            parts.push(&text);
+        }
+        parts.extend(blocks.iter().map(String::as_str));
+        parts.join("\n\n")
+    };
+
+    let mut blocks: Vec<String> = report
+        .files
+        .iter()
+        .map(|file| {
+            format!(
+                "{}{}",
+                header(&file.relative_path),
+                fenced_block(detect_language(&file.path), &file.content)
+            )
+        })
+        .collect();
+
+    if strategy == TruncationStrategy::DropWholeFiles {
+        // Drop the largest files first, leaving a note where each one was
+        let mut order: Vec<usize> = (0..blocks.len()).collect();
+        order.sort_by_key(|&index| std::cmp::Reverse(report.files[index].tokens));
+        for index in order {
+            if render(&blocks).chars().count() <= max_chars {
+                break;
+            }
+            let file = &report.files[index];
+            let tokens = tokenizer.count(&file.content);
+            blocks[index] = format!("{}{}", header(&file.relative_path), omission_note(tokens));
+            cuts.push(Cut {
+                section: SectionKind::AuxiliaryContent,
+                file: Some(file.relative_path.clone()),
+                strategy,
+                omitted_tokens: tokens,
+            });
+        }
+    } else if !report.files.is_empty() {
+        // Everything but the file contents is kept; the rest is shared between the
+        // files, smallest first, so that small files stay whole
+        let empty: Vec<String> = report
+            .files
+            .iter()
+            .map(|file| {
+                format!(
+                    "{}{}",
+                    header(&file.relative_path),
+                    fenced_block(detect_language(&file.path), "")
+                )
+            })
+            .collect();
+        let fixed = render(&empty).chars().count();
+        let mut remaining = max_chars.saturating_sub(fixed);
+
+        let mut order: Vec<usize> = (0..blocks.len()).collect();
+        order.sort_by_key(|&index| report.files[index].content.chars().count());
+        for (position, &index) in order.iter().enumerate() {
+            let file = &report.files[index];
+            let fair_share = remaining / (order.len() - position);
+            let size = file.content.chars().count();
+            let (content, omitted) = truncate_chars(&file.content, fair_share, strategy, tokenizer);
+            remaining -= size.min(fair_share).min(remaining);
+            if omitted > 0 {
+                blocks[index] = format!(
+                    "{}{}",
+                    header(&file.relative_path),
+                    fenced_block(detect_language(&file.path), &content)
+                );
+                cuts.push(Cut {
+                    section: SectionKind::AuxiliaryContent,
+                    file: Some(file.relative_path.clone()),
+                    strategy,
+                    omitted_tokens: omitted,
+                });
+            }
+        }
+    }
+
+    // The text itself is only cut when the files alone could not make room
+    let body = render(&blocks);
+    if body.chars().count() <= max_chars {
+        return body;
+    }
+    let (shortened, omitted) =
+        truncate_chars(&body, max_chars, TruncationStrategy::Head, tokenizer);
+    cuts.push(Cut {
+        section: SectionKind::AuxiliaryContent,
+        file: None,
+        strategy: TruncationStrategy::Head,
+        omitted_tokens: omitted,
+    });
+    shortened
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::attachment::{Attachment, AttachmentKind};
+    use crate::models::context::Context;
+    use crate::models::main_content::MainContent;
+    use crate::services::token_counter::estimate_tokens;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn numbered_lines(count: usize) -> String {
+        (1..=count)
+            .map(|i| format!("linha {:03}", i))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_truncate_text_within_budget_is_unchanged() {
+        let (text, omitted) =
+            truncate_text("curto", 10, TruncationStrategy::Head, Tokenizer::Characters);
+        assert_eq!(text, "curto");
+        assert_eq!(omitted, 0);
+    }
+
+    #[test]
+    fn test_truncate_text_head() {
+        let original = numbered_lines(100);
+        let (text, omitted) = truncate_text(
+            &original,
+            50,
+            TruncationStrategy::Head,
+            Tokenizer::Characters,
+        );
+
+        assert!(text.starts_with("linha 001\n"));
+        assert!(!text.contains("linha 100"));
+        assert!(text.ends_with(&omission_note(omitted)));
+        assert!(omitted > 0);
+        assert!(estimate_tokens(&text) <= 50);
+    }
+
+    #[test]
+    fn test_truncate_text_tail() {
+        let original = numbered_lines(100);
+        let (text, omitted) = truncate_text(
+            &original,
+            50,
+            TruncationStrategy::Tail,
+            Tokenizer::Characters,
+        );
+
+        assert!(text.starts_with(&omission_note(omitted)));
+        assert!(text.ends_with("linha 100"));
+        assert!(!text.contains("linha 001"));
+        assert!(estimate_tokens(&text) <= 50);
+    }
+
+    #[test]
+    fn test_truncate_text_middle_elision() {
+        let original = numbered_lines(100);
+        let (text, omitted) = truncate_text(
+            &original,
+            50,
+            TruncationStrategy::MiddleElision,
+            Tokenizer::Characters,
+        );
+
+        assert!(text.starts_with("linha 001\n"));
+        assert!(text.ends_with("linha 100"));
+        assert!(text.contains(&format!("\n{}\n", omission_note(omitted))));
+        assert!(!text.contains("linha 050"));
+        assert!(estimate_tokens(&text) <= 50);
+    }
+
+    #[test]
+    fn test_truncate_text_tiny_budget_leaves_only_note() {
+        let (text, omitted) = truncate_text(
+            &numbered_lines(10),
+            2,
+            TruncationStrategy::Head,
+            Tokenizer::Characters,
+        );
+        assert_eq!(text, omission_note(omitted));
+    }
+
+    #[test]
+    fn test_truncate_text_head_closes_open_fence() {
+        let original = format!("Código:\n```rust\n{}\n```\nFim", numbered_lines(100));
+        let (text, omitted) = truncate_text(
+            &original,
+            50,
+            TruncationStrategy::Head,
+            Tokenizer::Characters,
+        );
+
+        // A nota fica fora do bloco de código
+        assert!(text.starts_with("Código:\n```rust\nlinha 001\n"));
+        assert!(text.ends_with(&format!("\n```\n{}", omission_note(omitted))));
+        assert_eq!(text.matches("```").count(), 2);
+        assert!(estimate_tokens(&text) <= 50);
+    }
+
+    #[test]
+    fn test_truncate_text_counts_with_tokenizer() {
+        let original = ["palavra"; 300].join(" ");
+        let (text, omitted) =
+            truncate_text(&original, 100, TruncationStrategy::Head, Tokenizer::Words);
+
+        assert!(Tokenizer::Words.count(&text) <= 100);
+        assert!(Tokenizer::Words.count(&text) > 80);
+        // A contagem omitida é a do texto removido, não a de quatro caracteres por token
+        let head = text
+            .strip_suffix(&format!("\n{}", omission_note(omitted)))
+            .unwrap();
+        assert_eq!(omitted, Tokenizer::Words.count(&original[head.len()..]));
+    }
+
+    #[test]
+    fn test_apply_budget_under_target_is_unchanged() {
+        let mut data = PromptData::new();
+        let mut context = Context::new();
+        context.description = "Você é um especialista em Rust".to_string();
+        data.context = Some(context);
+
+        let (result, report) =
+            apply_budget(&data, &BudgetPolicy::new(1_000), Tokenizer::Characters);
+
+        assert_eq!(result.build_prompt(false), data.build_prompt(false));
+        assert!(!report.is_truncated());
+        assert_eq!(
+            report.summary(),
+            format!("~{} tokens (meta: 1000)", report.final_tokens)
+        );
+    }
+
+    #[test]
+    fn test_apply_budget_without_target_is_unchanged() {
+        let mut data = PromptData::new();
+        data.set_section_body(SectionKind::FewShot, numbered_lines(500));
+
+        let (_, report) = apply_budget(&data, &BudgetPolicy::new(0), Tokenizer::Characters);
+
+        assert!(!report.is_truncated());
+    }
+
+    #[test]
+    fn test_apply_budget_cuts_lower_priority_first() {
+        let mut data = PromptData::new();
+        let mut main_content = MainContent::new();
+        main_content.instructions = "Explique o código abaixo.".to_string();
+        data.main_content = Some(main_content);
+        data.set_section_body(SectionKind::FewShot, numbered_lines(300));
+
+        let (result, report) = apply_budget(&data, &BudgetPolicy::new(200), Tokenizer::Characters);
+
+        assert!(report.final_tokens <= 200);
+        assert_eq!(report.cuts.len(), 1);
+        assert_eq!(report.cuts[0].section, SectionKind::FewShot);
+        assert_eq!(
+            result.section_body(SectionKind::MainContent).unwrap(),
+            "Explique o código abaixo."
+        );
+        assert!(result
+            .section_body(SectionKind::FewShot)
+            .unwrap()
+            .contains("tokens omitted ...]"));
+        assert!(report.summary().starts_with("Prompt reduzido de ~"));
+    }
+
+    #[test]
+    fn test_apply_budget_counts_with_tokenizer() {
+        let mut data = PromptData::new();
+        data.set_section_body(SectionKind::FewShot, ["exemplo"; 600].join(" "));
+
+        let (_, report) = apply_budget(&data, &BudgetPolicy::new(200), Tokenizer::Words);
+
+        // Com palavras, 600 palavras são 800 tokens e não 1.050
+        assert_eq!(
+            report.original_tokens,
+            Tokenizer::Words.count(&data.build_prompt(false))
+        );
+        assert!(report.final_tokens <= 200);
+        assert!(report.is_truncated());
+    }
+
+    #[test]
+    fn test_apply_budget_respects_max_share() {
+        let mut data = PromptData::new();
+        data.set_section_body(SectionKind::Context, numbered_lines(400));
+        data.set_section_body(SectionKind::MainContent, numbered_lines(400));
+
+        let (result, report) =
+            apply_budget(&data, &BudgetPolicy::new(1_000), Tokenizer::Characters);
+
+        assert!(report.final_tokens <= 1_000);
+        // O contexto pode ocupar no máximo metade da meta
+        let context = result.section_body(SectionKind::Context).unwrap();
+        assert!(estimate_tokens(&context) <= 500);
+    }
+
+    #[test]
+    fn test_apply_budget_drops_whole_attached_files() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("grande.rs"), numbered_lines(400)).unwrap();
+        fs::write(temp_dir.path().join("pequeno.rs"), "fn main() {}").unwrap();
+
+        let mut auxiliary = AuxiliaryContent::new();
+        auxiliary.data = "Arquivos do projeto".to_string();
+        auxiliary.attachments = vec![Attachment::new(temp_dir.path(), AttachmentKind::Directory)];
+        let mut data = PromptData::new();
+        data.auxiliary_content = Some(auxiliary);
+        data.set_section_body(SectionKind::MainContent, "Revise os arquivos.".to_string());
+
+        let (result, report) = apply_budget(&data, &BudgetPolicy::new(200), Tokenizer::Characters);
+        let body = result.section_body(SectionKind::AuxiliaryContent).unwrap();
+
+        assert!(report.final_tokens <= 200);
+        assert_eq!(report.cuts.len(), 1);
+        assert!(report.cuts[0]
+            .file
+            .as_deref()
+            .unwrap()
+            .ends_with("grande.rs"));
+        assert!(body.starts_with("Arquivos do projeto"));
+        assert!(body.contains("fn main() {}"));
+        assert!(!body.contains("linha 001"));
+        assert!(body.contains("tokens omitted ...]"));
+    }
+
+    #[test]
+    fn test_apply_budget_elides_attached_files_inside_fences() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("grande.rs"), numbered_lines(400)).unwrap();
+
+        let mut auxiliary = AuxiliaryContent::new();
+        auxiliary.attachments = vec![Attachment::new(temp_dir.path(), AttachmentKind::Directory)];
+        let mut data = PromptData::new();
+        data.auxiliary_content = Some(auxiliary);
+
+        let mut policy = BudgetPolicy::new(300);
+        policy
+            .sections
+            .get_mut(&SectionKind::AuxiliaryContent)
+            .unwrap()
+            .strategy = TruncationStrategy::MiddleElision;
+        let (result, report) = apply_budget(&data, &policy, Tokenizer::Characters);
+        let body = result.section_body(SectionKind::AuxiliaryContent).unwrap();
+
+        assert!(report.final_tokens <= 300);
+        assert!(body.contains("```rust\nlinha 001"));
+        assert!(body.ends_with("linha 400\n```"));
+        assert!(body.contains("tokens omitted ...]"));
+    }
+}
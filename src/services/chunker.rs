@@ -0,0 +1,248 @@
+use crate::services::markdown::{is_heading, FenceTracker};
+use crate::services::token_counter::estimate_tokens;
+
+/// Splits a text into chunks of at most `max_tokens` tokens on semantic boundaries.
+/// Attached files (`Arquivo: ...`) and Markdown headings are preferred, then
+/// paragraphs, then single lines. Fenced code blocks are never split across chunks
+/// unless a block alone is too big; its pieces are then re-fenced so each stays valid.
+pub fn split_semantic(text: &str, max_tokens: usize) -> Vec<String> {
+    let max_tokens = max_tokens.max(1);
+    if text.trim().is_empty() {
+        return Vec::new();
+    }
+    if estimate_tokens(text) <= max_tokens {
+        return vec![text.trim().to_string()];
+    }
+
+    let mut units = Vec::new();
+    for section in split_sections(text) {
+        if estimate_tokens(&section) <= max_tokens {
+            units.push(section);
+            continue;
+        }
+        for paragraph in split_paragraphs(&section) {
+            if estimate_tokens(&paragraph) <= max_tokens {
+                units.push(paragraph);
+            } else {
+                units.extend(split_lines(&paragraph, max_tokens));
+            }
+        }
+    }
+
+    pack(units, max_tokens)
+}
+
+// Joins consecutive units into chunks as long as they fit
+fn pack(units: Vec<String>, max_tokens: usize) -> Vec<String> {
+    let mut chunks: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for unit in units {
+        if current.is_empty() {
+            current = unit;
+            continue;
+        }
+        let candidate = format!("{}\n\n{}", current, unit);
+        if estimate_tokens(&candidate) <= max_tokens {
+            current = candidate;
+        } else {
+            chunks.push(std::mem::replace(&mut current, unit));
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+// Splits before every attached file header and heading that is outside a code block
+fn split_sections(text: &str) -> Vec<String> {
+    let mut tracker = FenceTracker::new();
+    let mut sections: Vec<Vec<&str>> = vec![Vec::new()];
+
+    for line in text.lines() {
+        let in_fence = tracker.feed(line);
+        let starts_section = !in_fence && (line.starts_with("Arquivo: `") || is_heading(line));
+        if starts_section && sections.last().is_some_and(|lines| !lines.is_empty()) {
+            sections.push(Vec::new());
+        }
+        if let Some(section) = sections.last_mut() {
+            section.push(line);
+        }
+    }
+
+    collect_non_blank(sections)
+}
+
+// Splits on blank lines outside code blocks, keeping each block whole
+fn split_paragraphs(text: &str) -> Vec<String> {
+    let mut tracker = FenceTracker::new();
+    let mut paragraphs: Vec<Vec<&str>> = vec![Vec::new()];
+
+    for line in text.lines() {
+        let in_fence = tracker.feed(line);
+        if !in_fence && line.trim().is_empty() {
+            paragraphs.push(Vec::new());
+        } else if let Some(paragraph) = paragraphs.last_mut() {
+            paragraph.push(line);
+        }
+    }
+
+    collect_non_blank(paragraphs)
+}
+
+fn collect_non_blank(groups: Vec<Vec<&str>>) -> Vec<String> {
+    groups
+        .into_iter()
+        .map(|lines| lines.join("\n").trim().to_string())
+        .filter(|group| !group.is_empty())
+        .collect()
+}
+
+// Last resort: splits line by line, closing and reopening code blocks at the cut
+fn split_lines(text: &str, max_tokens: usize) -> Vec<String> {
+    let mut tracker = FenceTracker::new();
+    let mut pieces: Vec<String> = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut opening_line: Option<String> = None;
+
+    for line in text.lines() {
+        let was_open = tracker.is_open();
+        let closing = tracker.closing_fence();
+        tracker.feed(line);
+        if !was_open && tracker.is_open() {
+            opening_line = Some(line.to_string());
+        }
+
+        // Room for the closing fence is kept while a block is open
+        let reserve = closing.as_deref().map(|fence| fence.len() + 1).unwrap_or(0);
+        let candidate_chars: usize = current.iter().map(|l| l.chars().count() + 1).sum::<usize>()
+            + line.chars().count()
+            + reserve;
+
+        if !current.is_empty() && candidate_chars.div_ceil(4) > max_tokens {
+            if let (true, Some(fence)) = (was_open, &closing) {
+                current.push(fence.clone());
+                pieces.push(current.join("\n"));
+                current = opening_line.iter().cloned().collect();
+            } else {
+                pieces.push(current.join("\n"));
+                current = Vec::new();
+            }
+        }
+
+        // A single line longer than the budget is cut by characters
+        let max_chars = max_tokens * 4;
+        if line.chars().count() > max_chars {
+            let chars: Vec<char> = line.chars().collect();
+            for slice in chars.chunks(max_chars) {
+                if !current.is_empty() {
+                    pieces.push(current.join("\n"));
+                    current = Vec::new();
+                }
+                current.push(slice.iter().collect());
+            }
+        } else {
+            current.push(line.to_string());
+        }
+    }
+
+    if !current.is_empty() {
+        pieces.push(current.join("\n"));
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered_lines(prefix: &str, count: usize) -> String {
+        (1..=count)
+            .map(|i| format!("{} {:03}", prefix, i))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_small_text_is_a_single_chunk() {
+        assert_eq!(split_semantic("  texto curto \n", 100), vec!["texto curto"]);
+        assert!(split_semantic("   ", 100).is_empty());
+    }
+
+    #[test]
+    fn test_splits_on_attached_files() {
+        let text = format!(
+            "Arquivo: `a.rs`\n```rust\n{}\n```\n\nArquivo: `b.rs`\n```rust\n{}\n```",
+            numbered_lines("a", 20),
+            numbered_lines("b", 20)
+        );
+
+        let chunks = split_semantic(&text, 60);
+
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].starts_with("Arquivo: `a.rs`"));
+        assert!(chunks[0].ends_with("```"));
+        assert!(chunks[1].starts_with("Arquivo: `b.rs`"));
+    }
+
+    #[test]
+    fn test_splits_on_headings_and_packs_small_sections() {
+        let text =
+            "# Um\ncurto\n\n# Dois\ncurto\n\n# Três\n".to_string() + &numbered_lines("linha", 30);
+
+        let chunks = split_semantic(&text, 80);
+
+        assert_eq!(chunks[0], "# Um\ncurto\n\n# Dois\ncurto");
+        assert!(chunks[1].starts_with("# Três"));
+    }
+
+    #[test]
+    fn test_heading_inside_code_block_is_not_a_boundary() {
+        let code = format!("```bash\n# comentário\n{}\n```", numbered_lines("echo", 10));
+        let text = format!("{}\n\n# Fim\n{}", code, numbered_lines("texto", 10));
+
+        let chunks = split_semantic(&text, 40);
+
+        assert_eq!(chunks[0], code);
+    }
+
+    #[test]
+    fn test_oversized_code_block_is_refenced() {
+        let text = format!("```rust\n{}\n```", numbered_lines("let x =", 60));
+
+        let chunks = split_semantic(&text, 50);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.starts_with("```rust\n"), "{}", chunk);
+            assert!(chunk.ends_with("\n```"), "{}", chunk);
+            assert!(estimate_tokens(chunk) <= 50);
+        }
+        assert!(chunks[0].contains("let x = 001"));
+        assert!(chunks.last().unwrap().contains("let x = 060"));
+    }
+
+    #[test]
+    fn test_chunks_respect_budget_and_keep_all_lines() {
+        let text = numbered_lines("parágrafo", 200).replace("0\n", "0\n\n");
+
+        let chunks = split_semantic(&text, 40);
+
+        assert!(chunks.iter().all(|chunk| estimate_tokens(chunk) <= 40));
+        let joined = chunks.join("\n");
+        for i in 1..=200 {
+            assert!(joined.contains(&format!("parágrafo {:03}", i)));
+        }
+    }
+
+    #[test]
+    fn test_very_long_line_is_cut() {
+        let text = "x".repeat(1_000);
+
+        let chunks = split_semantic(&text, 100);
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.concat(), text);
+    }
+}
@@ -0,0 +1,387 @@
+use crate::models::section::SectionKind;
+use crate::services::markdown::FenceTracker;
+use crate::services::prompt_generator::PromptData;
+use crate::services::token_counter::estimate_tokens;
+use std::collections::HashSet;
+
+/// Boilerplate phrases and their shorter equivalents used by default
+pub const DEFAULT_DICTIONARY: [(&str, &str); 14] = [
+    ("por favor,", ""),
+    ("é importante que você", ""),
+    ("certifique-se de que", "garanta que"),
+    ("com o objetivo de", "para"),
+    ("a fim de", "para"),
+    ("no que diz respeito a", "sobre"),
+    ("em relação a", "sobre"),
+    ("levando em consideração", "considerando"),
+    ("de forma clara e concisa", "de forma concisa"),
+    ("neste momento", "agora"),
+    ("please", ""),
+    ("make sure that", "ensure"),
+    ("in order to", "to"),
+    ("at this point in time", "now"),
+];
+
+/// Lines shorter than this are never deduplicated (separators, list markers, etc.)
+const MIN_DEDUP_LINE_CHARS: usize = 8;
+
+/// What the compact render mode does. Fenced code is never changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactOptions {
+    /// Collapses runs of spaces and blank lines
+    pub collapse_whitespace: bool,
+    /// Drops lines that already appeared in an earlier section
+    pub dedupe_lines: bool,
+    /// Phrase replacements applied to prose, as (phrase, replacement) pairs
+    pub dictionary: Vec<(String, String)>,
+}
+
+impl Default for CompactOptions {
+    fn default() -> Self {
+        Self {
+            collapse_whitespace: true,
+            dedupe_lines: true,
+            dictionary: DEFAULT_DICTIONARY
+                .iter()
+                .map(|(phrase, replacement)| (phrase.to_string(), replacement.to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// Token counts before and after compaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactReport {
+    pub before_tokens: usize,
+    pub after_tokens: usize,
+}
+
+impl CompactReport {
+    /// Before/after comparison for the UI
+    pub fn summary(&self) -> String {
+        let saved = self.before_tokens.saturating_sub(self.after_tokens);
+        let percent = (saved * 100).checked_div(self.before_tokens).unwrap_or(0);
+        format!(
+            "Modo compacto: ~{} → ~{} tokens ({} tokens a menos, {}%)",
+            self.before_tokens, self.after_tokens, saved, percent
+        )
+    }
+}
+
+/// Parses a dictionary written one entry per line as `frase => substituição`.
+/// Blank lines and lines starting with `#` are ignored; an empty replacement removes the phrase.
+pub fn parse_dictionary(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .filter_map(|line| line.split_once("=>"))
+        .map(|(phrase, replacement)| (phrase.trim().to_string(), replacement.trim().to_string()))
+        .filter(|(phrase, _)| !phrase.is_empty())
+        .collect()
+}
+
+/// Writes a dictionary in the format read by [`parse_dictionary`]
+pub fn format_dictionary(dictionary: &[(String, String)]) -> String {
+    dictionary
+        .iter()
+        .map(|(phrase, replacement)| {
+            format!("{} => {}", phrase, replacement)
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Compacts every section of the prompt and reports the token counts of the
+/// rendered prompt before and after.
+pub fn compact_prompt(data: &PromptData, options: &CompactOptions) -> (PromptData, CompactReport) {
+    let mut result = data.clone();
+    let mut seen: HashSet<String> = HashSet::new();
+
+    for kind in SectionKind::ALL {
+        let Some(body) = data.prompt_body(kind) else {
+            continue;
+        };
+        let (compacted, lines) = compact_text(&body, options, &seen);
+        seen.extend(lines);
+        if compacted != body {
+            result.set_section_body(kind, compacted);
+        }
+    }
+
+    let report = CompactReport {
+        before_tokens: estimate_tokens(&data.build_prompt(false)),
+        after_tokens: estimate_tokens(&result.build_prompt(false)),
+    };
+    (result, report)
+}
+
+/// Compacts a single text. Lines found in `seen` are dropped when deduplication is on.
+/// Returns the compacted text and its prose lines, to be deduplicated in later texts.
+pub fn compact_text(
+    text: &str,
+    options: &CompactOptions,
+    seen: &HashSet<String>,
+) -> (String, Vec<String>) {
+    let mut tracker = FenceTracker::new();
+    let mut output: Vec<String> = Vec::new();
+    let mut prose_lines: Vec<String> = Vec::new();
+
+    for line in text.lines() {
+        if tracker.feed(line) {
+            output.push(line.to_string());
+            continue;
+        }
+
+        let mut line = line.to_string();
+        if options.collapse_whitespace {
+            line = collapse_spaces(&line);
+            let previous_blank = output.last().map(|l| l.trim().is_empty());
+            if line.is_empty() && previous_blank.unwrap_or(true) {
+                continue;
+            }
+        }
+        for (phrase, replacement) in &options.dictionary {
+            line = replace_phrase(&line, phrase, replacement);
+        }
+
+        let key = line.trim().to_string();
+        if key.chars().count() >= MIN_DEDUP_LINE_CHARS {
+            if options.dedupe_lines && seen.contains(&key) {
+                continue;
+            }
+            prose_lines.push(key);
+        }
+        output.push(line);
+    }
+
+    if options.collapse_whitespace {
+        while output.last().is_some_and(|line| line.trim().is_empty()) {
+            output.pop();
+        }
+    }
+    (output.join("\n"), prose_lines)
+}
+
+// Collapses runs of spaces and tabs after the indentation and trims the end of the line
+fn collapse_spaces(line: &str) -> String {
+    let content = line.trim_start();
+    let indent = &line[..line.len() - content.len()];
+
+    let mut collapsed = String::with_capacity(line.len());
+    collapsed.push_str(indent);
+    let mut previous_space = false;
+    for c in content.chars() {
+        if c == ' ' || c == '\t' {
+            if !previous_space {
+                collapsed.push(' ');
+            }
+            previous_space = true;
+        } else {
+            collapsed.push(c);
+            previous_space = false;
+        }
+    }
+    collapsed.trim_end().to_string()
+}
+
+// Replaces whole-word occurrences of a phrase, as written or with a capitalised first
+// letter (at the start of a sentence). An empty replacement also removes the spaces
+// after the phrase and moves the capital letter to the next word.
+fn replace_phrase(line: &str, phrase: &str, replacement: &str) -> String {
+    if phrase.is_empty() {
+        return line.to_string();
+    }
+    let capitalised = capitalise(phrase);
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+    loop {
+        let found = [phrase, capitalised.as_str()]
+            .into_iter()
+            .filter_map(|candidate| rest.find(candidate).map(|index| (index, candidate)))
+            .min_by_key(|(index, _)| *index);
+        let Some((index, matched)) = found else {
+            output.push_str(rest);
+            return output;
+        };
+
+        let previous = rest[..index].chars().last().or(output.chars().last());
+        let after = &rest[index + matched.len()..];
+        let starts_on_boundary =
+            previous.is_none_or(|c| !is_word(c)) || !matched.starts_with(is_word);
+        let ends_on_boundary =
+            after.chars().next().is_none_or(|c| !is_word(c)) || !matched.ends_with(is_word);
+
+        if !(starts_on_boundary && ends_on_boundary) {
+            // Not a whole word: keep the first character and look further on
+            let skip = index + matched.chars().next().map_or(1, char::len_utf8);
+            output.push_str(&rest[..skip]);
+            rest = &rest[skip..];
+            continue;
+        }
+
+        output.push_str(&rest[..index]);
+        let capital = matched != phrase;
+        if replacement.is_empty() {
+            rest = after.trim_start_matches([' ', '\t']);
+            if capital {
+                let mut chars = rest.chars();
+                if let Some(first) = chars.next() {
+                    output.extend(first.to_uppercase());
+                    rest = chars.as_str();
+                }
+            }
+        } else {
+            if capital {
+                output.push_str(&capitalise(replacement));
+            } else {
+                output.push_str(replacement);
+            }
+            rest = after;
+        }
+    }
+}
+
+fn capitalise(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compact(text: &str) -> String {
+        compact_text(text, &CompactOptions::default(), &HashSet::new()).0
+    }
+
+    #[test]
+    fn test_collapses_whitespace_outside_fences() {
+        let text = "Texto   com    espaços  \n\n\n\n  - item   indentado\n\n";
+        assert_eq!(compact(text), "Texto com espaços\n\n  - item indentado");
+    }
+
+    #[test]
+    fn test_never_touches_fenced_code() {
+        let code = "```python\ndef f():\n    x  =  1\n\n\n\n    # por favor, não mude\n    return    x   \n```";
+        let text = format!("Por favor,   explique:\n\n{}\n\nFim.", code);
+
+        let compacted = compact(&text);
+
+        assert_eq!(compacted, format!("Explique:\n\n{}\n\nFim.", code));
+    }
+
+    #[test]
+    fn test_never_touches_tilde_fences_or_nested_fences() {
+        let code = "~~~~\n```\ncom  espaços\n```\npor favor, a fim de\n~~~~";
+        let text = format!("Intro\n{}", code);
+
+        assert_eq!(compact(&text), text);
+    }
+
+    #[test]
+    fn test_dictionary_replacements() {
+        assert_eq!(
+            compact("Faça isso a fim de testar. Certifique-se de que funciona."),
+            "Faça isso para testar. Garanta que funciona."
+        );
+        assert_eq!(compact("Please review the code"), "Review the code");
+    }
+
+    #[test]
+    fn test_dictionary_matches_whole_words_only() {
+        assert_eq!(compact("I am pleased to help"), "I am pleased to help");
+        assert_eq!(
+            compact("Fale em relação ao tema e em relação a prazos"),
+            "Fale em relação ao tema e sobre prazos"
+        );
+    }
+
+    #[test]
+    fn test_custom_dictionary() {
+        let options = CompactOptions {
+            dictionary: parse_dictionary("# comentário\nprogramação => prog.\n\ninválida"),
+            ..CompactOptions::default()
+        };
+        let (compacted, _) = compact_text("Ajuda com programação", &options, &HashSet::new());
+        assert_eq!(compacted, "Ajuda com prog.");
+    }
+
+    #[test]
+    fn test_dictionary_round_trip() {
+        let dictionary = CompactOptions::default().dictionary;
+        assert_eq!(
+            parse_dictionary(&format_dictionary(&dictionary)),
+            dictionary
+        );
+    }
+
+    #[test]
+    fn test_dedupes_lines_seen_in_earlier_text() {
+        let seen: HashSet<String> = ["Responda sempre em português.".to_string()].into();
+        let text = "Responda sempre em português.\nOutra instrução.\n```\nResponda sempre em português.\n```";
+
+        let (compacted, _) = compact_text(text, &CompactOptions::default(), &seen);
+
+        // A linha repetida dentro do bloco de código é preservada
+        assert_eq!(
+            compacted,
+            "Outra instrução.\n```\nResponda sempre em português.\n```"
+        );
+    }
+
+    #[test]
+    fn test_compact_prompt_dedupes_across_sections_only() {
+        let mut data = PromptData::new();
+        data.set_section_body(
+            SectionKind::Context,
+            "Use exemplos curtos.\nUse exemplos curtos.".to_string(),
+        );
+        data.set_section_body(
+            SectionKind::Guidance,
+            "Use exemplos curtos.\nCite as fontes.".to_string(),
+        );
+
+        let (result, report) = compact_prompt(&data, &CompactOptions::default());
+
+        assert_eq!(
+            result.section_body(SectionKind::Context).unwrap(),
+            "Use exemplos curtos.\nUse exemplos curtos."
+        );
+        assert_eq!(
+            result.section_body(SectionKind::Guidance).unwrap(),
+            "Cite as fontes."
+        );
+        assert!(report.after_tokens < report.before_tokens);
+    }
+
+    #[test]
+    fn test_compact_report_summary() {
+        let report = CompactReport {
+            before_tokens: 200,
+            after_tokens: 150,
+        };
+        assert_eq!(
+            report.summary(),
+            "Modo compacto: ~200 → ~150 tokens (50 tokens a menos, 25%)"
+        );
+    }
+
+    #[test]
+    fn test_disabled_options_leave_text_unchanged() {
+        let options = CompactOptions {
+            collapse_whitespace: false,
+            dedupe_lines: false,
+            dictionary: Vec::new(),
+        };
+        let text = "a   b\n\n\n\npor favor, c";
+        let seen: HashSet<String> = ["por favor, c".to_string()].into();
+
+        assert_eq!(compact_text(text, &options, &seen).0, text);
+    }
+}
@@ -1,45 +1,209 @@
-use std::fs::File;
+use crate::error::{Error, IoResultExt, Result};
+use crate::services::text_encoding::{LineEnding, TextEncoding, TextFormat};
+use std::fs::{self, File};
 use std::io::{self, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Previous versions of a file kept as backups when it is saved over
+pub const DEFAULT_BACKUP_COUNT: usize = 3;
+
+/// Modification time and size of a file when it was read or written, to
+/// tell whether it was changed by someone else since.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+impl FileStamp {
+    /// Stamp of a file as it is now on disk
+    pub fn of(path: &Path) -> Result<Self> {
+        let metadata = fs::metadata(path).with_path(path)?;
+        Ok(Self {
+            modified: metadata.modified().with_path(path)?,
+            len: metadata.len(),
+        })
+    }
+}
+
+/// How a prompt is written over a file.
+#[derive(Debug, Clone)]
+pub struct SaveOptions {
+    pub format: TextFormat,
+    /// Previous versions kept as `arquivo.txt.bak1`, `arquivo.txt.bak2`...,
+    /// the most recent first; 0 keeps none
+    pub backups: usize,
+    /// Stamp of the file when it was read. The save fails with
+    /// `Error::ChangedOnDisk` when the file on disk no longer matches it.
+    pub expected: Option<FileStamp>,
+}
+
+impl Default for SaveOptions {
+    fn default() -> Self {
+        Self {
+            format: TextFormat {
+                encoding: TextEncoding::Utf8,
+                line_ending: LineEnding::Lf,
+            },
+            backups: DEFAULT_BACKUP_COUNT,
+            expected: None,
+        }
+    }
+}
 
 /// Saves the given prompt text to a file with the specified title.
 /// The file will be saved with `.txt` extension in the given directory.
-pub fn save_prompt_to_file(
-    prompt_text: &str,
-    output_directory: &str,
-    title: &str,
-) -> io::Result<()> {
+pub fn save_prompt_to_file(prompt_text: &str, output_directory: &str, title: &str) -> Result<()> {
     // Ensure the output directory exists
-    std::fs::create_dir_all(output_directory)?;
+    std::fs::create_dir_all(output_directory).with_path(Path::new(output_directory))?;
 
     // Build the full file path
     let file_name = format!("{}.txt", title);
     let file_path = Path::new(output_directory).join(file_name);
 
-    // Open the file for writing
-    let mut file = File::create(&file_path)?;
-
-    // Write the prompt content to the file
-    file.write_all(prompt_text.as_bytes())?;
+    write_atomically(&file_path, prompt_text.as_bytes())?;
 
     println!("✅ Prompt successfully saved at: {:?}", file_path);
 
     Ok(())
 }
 
-/// Saves the prompt text to a specific file path chosen by the user
-pub fn save_prompt_to_specific_path(prompt_text: &str, file_path: &str) -> io::Result<()> {
-    // Open the file for writing
-    let mut file = File::create(file_path)?;
+/// Saves the prompt text to a specific file path chosen by the user, keeping
+/// the default number of backups of the previous versions
+pub fn save_prompt_to_specific_path(prompt_text: &str, file_path: &str) -> Result<()> {
+    let format = TextFormat {
+        encoding: TextEncoding::Utf8,
+        line_ending: LineEnding::Lf,
+    };
+    save_prompt_in_format(prompt_text, file_path, format, DEFAULT_BACKUP_COUNT)
+}
 
-    // Write the prompt content to the file
-    file.write_all(prompt_text.as_bytes())?;
+/// Saves the prompt text to a file path in the given encoding and line
+/// breaks, as those of the file it was opened from, keeping up to `backups`
+/// previous versions
+pub fn save_prompt_in_format(
+    prompt_text: &str,
+    file_path: &str,
+    format: TextFormat,
+    backups: usize,
+) -> Result<()> {
+    let options = SaveOptions {
+        format,
+        backups,
+        expected: None,
+    };
+    save_prompt_with(prompt_text, Path::new(file_path), &options)?;
 
     println!("✅ Prompt successfully saved at: {}", file_path);
 
     Ok(())
 }
 
+/// Saves the prompt text over a file without ever leaving it half written:
+/// the text goes to a temporary file that is flushed to disk and then
+/// renamed over the target, after the previous version is kept as a backup.
+/// Text the encoding cannot hold is an error. Returns the stamp of the saved
+/// file.
+pub fn save_prompt_with(
+    prompt_text: &str,
+    file_path: &Path,
+    options: &SaveOptions,
+) -> Result<FileStamp> {
+    if let Some(expected) = options.expected {
+        match FileStamp::of(file_path) {
+            Ok(current) if current != expected => {
+                return Err(Error::changed_on_disk(file_path));
+            }
+            // A file removed since it was read is simply written again
+            Err(e) if !e.is_not_found() => return Err(e),
+            _ => {}
+        }
+    }
+    options
+        .format
+        .encoding
+        .check_encodable(prompt_text)
+        .map_err(|e| e.with_path(file_path))?;
+
+    let temporary =
+        write_temporary(file_path, &options.format.encode(prompt_text)).with_path(file_path)?;
+    if options.backups > 0 && file_path.is_file() {
+        if let Err(e) = rotate_backups(file_path, options.backups) {
+            let _ = fs::remove_file(&temporary);
+            return Err(Error::io(e, file_path));
+        }
+    }
+    replace_with(&temporary, file_path).with_path(file_path)?;
+    FileStamp::of(file_path)
+}
+
+/// Writes a file through a temporary file flushed to disk and renamed over
+/// it, so a crash keeps either the old contents or the new ones
+pub fn write_atomically(file_path: &Path, bytes: &[u8]) -> Result<()> {
+    write_temporary(file_path, bytes)
+        .and_then(|temporary| replace_with(&temporary, file_path))
+        .with_path(file_path)
+}
+
+/// Path of the n-th most recent backup of a file, counted from 1
+pub fn backup_path(file_path: &Path, n: usize) -> PathBuf {
+    let mut name = file_path.as_os_str().to_owned();
+    name.push(format!(".bak{}", n));
+    PathBuf::from(name)
+}
+
+// Writes the bytes next to the file, under a hidden temporary name, and
+// flushes them to disk
+fn write_temporary(file_path: &Path, bytes: &[u8]) -> io::Result<PathBuf> {
+    let name = file_path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "caminho sem nome de arquivo")
+    })?;
+    let mut temporary_name = std::ffi::OsString::from(".");
+    temporary_name.push(name);
+    temporary_name.push(".tmp");
+    let temporary = file_path.with_file_name(temporary_name);
+
+    let written = File::create(&temporary).and_then(|mut file| {
+        file.write_all(bytes)?;
+        file.sync_all()
+    });
+    match written {
+        Ok(()) => Ok(temporary),
+        Err(e) => {
+            let _ = fs::remove_file(&temporary);
+            Err(e)
+        }
+    }
+}
+
+// Renames the temporary file over the target and, where the system allows
+// it, flushes the folder so the rename itself survives a crash
+fn replace_with(temporary: &Path, file_path: &Path) -> io::Result<()> {
+    if let Err(e) = fs::rename(temporary, file_path) {
+        let _ = fs::remove_file(temporary);
+        return Err(e);
+    }
+    #[cfg(unix)]
+    if let Some(dir) = file_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+// Shifts the backups of a file by one, dropping the oldest, and copies the
+// current file to the first one
+fn rotate_backups(file_path: &Path, count: usize) -> io::Result<()> {
+    for n in (1..count).rev() {
+        let older = backup_path(file_path, n);
+        if older.is_file() {
+            fs::rename(&older, backup_path(file_path, n + 1))?;
+        }
+    }
+    fs::copy(file_path, backup_path(file_path, 1))?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,6 +362,124 @@ mod tests {
 
         let result = save_prompt_to_specific_path(prompt_text, invalid_path);
         assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().context().path.as_deref(),
+            Some(Path::new(invalid_path))
+        );
+    }
+
+    #[test]
+    fn test_save_prompt_in_format_writes_encoding_and_line_breaks() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let file_path = temp_dir.path().join("windows.txt");
+        let format = TextFormat {
+            encoding: TextEncoding::Windows1252,
+            line_ending: LineEnding::CrLf,
+        };
+
+        let result = save_prompt_in_format("Olá\nAção", file_path.to_str().unwrap(), format, 0);
+        assert!(result.is_ok());
+
+        let bytes = fs::read(&file_path).expect("Failed to read file");
+        assert_eq!(bytes, b"Ol\xE1\r\nA\xE7\xE3o".to_vec());
+
+        // Um caractere sem representação impede o salvamento e o arquivo fica intacto
+        let error =
+            save_prompt_in_format("Olá\n🚀", file_path.to_str().unwrap(), format, 0).unwrap_err();
+        assert!(matches!(error, Error::Encoding { .. }));
+        assert_eq!(error.context().line, Some(2));
+        assert_eq!(fs::read(&file_path).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_public_save_functions_rotate_backups() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let file_path = temp_dir.path().join("publico.txt");
+        let path_str = file_path.to_str().unwrap();
+
+        for version in 1..=5 {
+            save_prompt_to_specific_path(&format!("versão {}", version), path_str).unwrap();
+        }
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "versão 5");
+        for n in 1..=DEFAULT_BACKUP_COUNT {
+            assert_eq!(
+                fs::read_to_string(backup_path(&file_path, n)).unwrap(),
+                format!("versão {}", 5 - n)
+            );
+        }
+        assert!(!backup_path(&file_path, DEFAULT_BACKUP_COUNT + 1).exists());
+
+        // A quantidade de cópias vem do chamador
+        let format = TextFormat {
+            encoding: TextEncoding::Utf8,
+            line_ending: LineEnding::Lf,
+        };
+        let other = temp_dir.path().join("formato.txt");
+        let other_str = other.to_str().unwrap();
+        for version in 1..=3 {
+            save_prompt_in_format(&format!("versão {}", version), other_str, format, 1).unwrap();
+        }
+        assert_eq!(
+            fs::read_to_string(backup_path(&other, 1)).unwrap(),
+            "versão 2"
+        );
+        assert!(!backup_path(&other, 2).exists());
+    }
+
+    #[test]
+    fn test_save_keeps_rotating_backups_of_previous_versions() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let file_path = temp_dir.path().join("rotacao.txt");
+        let options = SaveOptions {
+            backups: 2,
+            ..SaveOptions::default()
+        };
+
+        for version in 1..=4 {
+            save_prompt_with(&format!("versão {}", version), &file_path, &options).unwrap();
+        }
+
+        let read = |path: PathBuf| fs::read_to_string(path).unwrap();
+        assert_eq!(read(file_path.clone()), "versão 4");
+        assert_eq!(read(backup_path(&file_path, 1)), "versão 3");
+        assert_eq!(read(backup_path(&file_path, 2)), "versão 2");
+        assert!(!backup_path(&file_path, 3).exists());
+
+        // Nenhum arquivo temporário fica para trás
+        let files = fs::read_dir(temp_dir.path()).unwrap().count();
+        assert_eq!(files, 3);
+    }
+
+    #[test]
+    fn test_save_refuses_file_changed_on_disk_since_it_was_read() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let file_path = temp_dir.path().join("conflito.txt");
+        let stamp = save_prompt_with("original", &file_path, &SaveOptions::default()).unwrap();
+
+        // Outro programa reescreve o arquivo com outro tamanho
+        fs::write(&file_path, "alterado por outro programa").unwrap();
+        let options = SaveOptions {
+            expected: Some(stamp),
+            ..SaveOptions::default()
+        };
+        let error = save_prompt_with("minha versão", &file_path, &options).unwrap_err();
+        assert!(matches!(error, Error::ChangedOnDisk { .. }));
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "alterado por outro programa"
+        );
+
+        // Com o carimbo atual o salvamento é aceito
+        let options = SaveOptions {
+            expected: Some(FileStamp::of(&file_path).unwrap()),
+            ..SaveOptions::default()
+        };
+        save_prompt_with("minha versão", &file_path, &options).unwrap();
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "minha versão");
+        assert_eq!(
+            fs::read_to_string(backup_path(&file_path, 1)).unwrap(),
+            "alterado por outro programa"
+        );
     }
 
     #[test]
@@ -0,0 +1,221 @@
+use crate::error::{Error, Result};
+use crate::services::source_reader::fenced_block;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// What to read from a local Git repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitSource {
+    /// Changes staged for the next commit (`git diff --cached`)
+    StagedDiff,
+    /// Changes in the working tree not yet staged (`git diff`)
+    UnstagedDiff,
+    /// Differences between two refs (`git diff <from> <to>`)
+    RefDiff { from: String, to: String },
+    /// Summary of the most recent commits
+    RecentCommits { count: usize },
+    /// Files changed in the working tree, staged or not, including untracked files
+    ChangedFiles,
+}
+
+/// Reads the requested context from the repository at `repo_path` and formats it
+/// as text ready to be appended to a prompt section. Works entirely offline.
+pub fn ingest(repo_path: &Path, source: &GitSource) -> Result<String> {
+    match source {
+        GitSource::StagedDiff => {
+            let diff = run_git(repo_path, &["diff", "--cached"])?;
+            Ok(format_diff("Alterações staged", &diff))
+        }
+        GitSource::UnstagedDiff => {
+            let diff = run_git(repo_path, &["diff"])?;
+            Ok(format_diff("Alterações não staged", &diff))
+        }
+        GitSource::RefDiff { from, to } => {
+            validate_ref(from)?;
+            validate_ref(to)?;
+            let diff = run_git(repo_path, &["diff", from, to, "--"])?;
+            Ok(format_diff(&format!("Diff entre {} e {}", from, to), &diff))
+        }
+        GitSource::RecentCommits { count } => {
+            let count = format!("-n{}", (*count).max(1));
+            let log = run_git(
+                repo_path,
+                &[
+                    "log",
+                    &count,
+                    "--date=short",
+                    "--pretty=format:%h%x09%ad%x09%an%x09%s",
+                ],
+            )?;
+            Ok(format_commits(&log))
+        }
+        GitSource::ChangedFiles => {
+            let status = run_git(
+                repo_path,
+                &["status", "--porcelain", "--untracked-files=all"],
+            )?;
+            Ok(format_changed_files(&status))
+        }
+    }
+}
+
+// Runs a git command in the repository and returns its standard output
+fn run_git(repo_path: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args([
+            "--no-pager",
+            "-c",
+            "core.quotepath=off",
+            "-c",
+            "color.ui=never",
+        ])
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|e| {
+            let message = format!("não foi possível executar o git: {}", e);
+            Error::io(io::Error::new(e.kind(), message), repo_path)
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = format!(
+            "git {} falhou: {}",
+            args.first().unwrap_or(&""),
+            stderr.trim()
+        );
+        return Err(Error::io(io::Error::other(message), repo_path));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// Rejects refs that git would interpret as command-line options
+fn validate_ref(reference: &str) -> Result<()> {
+    if reference.trim().is_empty() || reference.starts_with('-') {
+        return Err(Error::validation(format!(
+            "referência Git inválida: '{}'",
+            reference
+        )));
+    }
+    Ok(())
+}
+
+fn format_diff(title: &str, diff: &str) -> String {
+    if diff.trim().is_empty() {
+        format!("{}: (sem alterações)", title)
+    } else {
+        format!("{}:\n{}", title, fenced_block("diff", diff.trim_end()))
+    }
+}
+
+fn format_commits(log: &str) -> String {
+    let commits: Vec<String> = log
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let fields: Vec<&str> = line.splitn(4, '\t').collect();
+            match fields.as_slice() {
+                [hash, date, author, subject] => {
+                    format!("- {} {} ({}, {})", hash, subject, author, date)
+                }
+                _ => format!("- {}", line),
+            }
+        })
+        .collect();
+
+    if commits.is_empty() {
+        "Commits recentes: (nenhum commit)".to_string()
+    } else {
+        format!("Commits recentes:\n{}", commits.join("\n"))
+    }
+}
+
+fn format_changed_files(status: &str) -> String {
+    let files: Vec<String> = status
+        .lines()
+        .filter(|line| line.len() > 3)
+        .map(|line| {
+            let (code, path) = line.split_at(2);
+            let state = match code.trim() {
+                "??" => "novo (não rastreado)",
+                "A" | "AM" => "adicionado",
+                "D" => "removido",
+                "R" | "RM" => "renomeado",
+                code if code.contains('M') => "modificado",
+                code if code.contains('D') => "removido",
+                _ => "alterado",
+            };
+            format!("- {} ({})", path.trim(), state)
+        })
+        .collect();
+
+    if files.is_empty() {
+        "Arquivos alterados: (nenhum)".to_string()
+    } else {
+        format!("Arquivos alterados:\n{}", files.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_format_diff_empty() {
+        assert_eq!(
+            format_diff("Alterações staged", "\n"),
+            "Alterações staged: (sem alterações)"
+        );
+    }
+
+    #[test]
+    fn test_format_diff_fenced() {
+        let formatted = format_diff("Alterações staged", "diff --git a/x b/x\n+linha\n");
+        assert_eq!(
+            formatted,
+            "Alterações staged:\n```diff\ndiff --git a/x b/x\n+linha\n```"
+        );
+
+        // Um diff de Markdown com blocos de código não fecha o bloco antes da hora
+        let formatted = format_diff("Alterações staged", "+```rust\n+fn a() {}\n+```\n");
+        assert_eq!(
+            formatted,
+            "Alterações staged:\n````diff\n+```rust\n+fn a() {}\n+```\n````"
+        );
+    }
+
+    #[test]
+    fn test_format_commits() {
+        let log =
+            "abc1234\t2024-05-01\tAna\tCorrige parser\ndef5678\t2024-04-30\tBruno\tAdiciona testes";
+        assert_eq!(
+            format_commits(log),
+            "Commits recentes:\n- abc1234 Corrige parser (Ana, 2024-05-01)\n- def5678 Adiciona testes (Bruno, 2024-04-30)"
+        );
+        assert_eq!(format_commits(""), "Commits recentes: (nenhum commit)");
+    }
+
+    #[test]
+    fn test_format_changed_files() {
+        let status = " M src/main.rs\nA  src/novo.rs\n?? notas.txt\n D antigo.rs\n";
+        assert_eq!(
+            format_changed_files(status),
+            "Arquivos alterados:\n- src/main.rs (modificado)\n- src/novo.rs (adicionado)\n- notas.txt (novo (não rastreado))\n- antigo.rs (removido)"
+        );
+    }
+
+    #[test]
+    fn test_ref_diff_rejects_option_like_refs() {
+        let source = GitSource::RefDiff {
+            from: "--output=/tmp/x".to_string(),
+            to: "HEAD".to_string(),
+        };
+        let result = ingest(&PathBuf::from("."), &source);
+        assert!(matches!(result.unwrap_err(), Error::Validation { .. }));
+    }
+}
@@ -0,0 +1,301 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::models::{language::Language, section::SectionKind};
+use crate::services::app_dirs;
+use crate::services::prompt_library::fold_text;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Name of the user aliases file inside the configuration directory
+pub const ALIASES_FILE_NAME: &str = "apelidos_de_titulos.txt";
+
+// Heading variants recognized out of the box, besides the section titles in
+// every language, in the same format as the user file
+const DEFAULT_ALIASES: &str = "\
+Exemplos: few_shot
+Examples: few_shot
+Ejemplos: few_shot
+Few-shot: few_shot
+Few shot: few_shot
+Samples: few_shot
+Demonstrações: few_shot
+Demonstrations: few_shot
+Role: context
+Papel: context
+Rol: context
+Persona: context
+System: context
+System prompt: context
+Sistema: context
+Background: context
+Cenário: context
+Scenario: context
+Escenario: context
+Tarefa: main_content
+Task: main_content
+Tarea: main_content
+Instruções: main_content
+Instructions: main_content
+Instrucciones: main_content
+Objetivo: main_content
+Objective: main_content
+Goal: main_content
+Pedido: main_content
+Request: main_content
+Pergunta: main_content
+Question: main_content
+Referências: auxiliary_content
+References: auxiliary_content
+Referencias: auxiliary_content
+Materiais: auxiliary_content
+Materials: auxiliary_content
+Dados: auxiliary_content
+Data: auxiliary_content
+Datos: auxiliary_content
+Anexos: auxiliary_content
+Attachments: auxiliary_content
+Entrada: auxiliary_content
+Input: auxiliary_content
+Documentos: auxiliary_content
+Documents: auxiliary_content
+Restrições: limitations
+Constraints: limitations
+Restricciones: limitations
+Regras: limitations
+Rules: limitations
+Reglas: limitations
+Limites: limitations
+Limits: limitations
+Refatoração: refactoring
+Refactor: refactoring
+Diretrizes: guidance
+Guidelines: guidance
+Directrices: guidance
+Tom: guidance
+Tone: guidance
+Tono: guidance
+Estilo: guidance
+Style: guidance
+Dicas: guidance
+Tips: guidance
+Casos de teste: tests
+Test cases: tests
+Casos de prueba: tests
+Testing: tests
+Output: output_format
+Formato: output_format
+Format: output_format
+Response format: output_format
+Formato de resposta: output_format
+Formato de respuesta: output_format
+Saída: output_format
+Salida: output_format
+";
+
+// Written to the user aliases file when it does not exist yet
+const USER_FILE_TEMPLATE: &str = "\
+# Apelidos de títulos usados ao importar prompts em Markdown.
+# Um por linha, no formato \"Título: seção\". A seção pode ser o identificador
+# (few_shot, context, main_content, auxiliary_content, limitations,
+# refactoring, guidance, tests, output_format) ou o título da seção em
+# qualquer idioma. Maiúsculas e acentos são ignorados. Exemplo:
+# Perfil do assistente: context
+";
+
+/// Table from heading variants to the sections they introduce, as in
+/// `# Role`, `### Persona` or `**Constraints:**`. Headings are compared
+/// ignoring case and accents.
+#[derive(Debug, Clone)]
+pub struct HeadingAliases {
+    /// Folded aliases, longest first so the most specific one wins
+    aliases: Vec<(String, SectionKind)>,
+}
+
+impl Default for HeadingAliases {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeadingAliases {
+    /// The built-in aliases and the section titles in every language
+    pub fn new() -> Self {
+        let mut aliases = Self {
+            aliases: Vec::new(),
+        };
+        for language in Language::ALL {
+            for kind in SectionKind::ALL {
+                aliases.add(kind.title_in(language), kind);
+            }
+        }
+        aliases.add_lines(DEFAULT_ALIASES);
+        aliases
+    }
+
+    /// The built-in aliases plus those of a user file; a missing file adds none
+    pub fn load(file: &Path) -> Result<Self> {
+        let mut aliases = Self::new();
+        match fs::read_to_string(file) {
+            Ok(text) => aliases.add_lines(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(Error::io(e, file)),
+        }
+        Ok(aliases)
+    }
+
+    /// Aliases of `apelidos_de_titulos.txt` in the user configuration
+    /// directory, which is created with usage notes when missing
+    pub fn in_user_config_dir() -> Result<Self> {
+        let Some(dir) = app_dirs::config_dir() else {
+            return Ok(Self::new());
+        };
+        let file = dir.join(ALIASES_FILE_NAME);
+        if !file.exists() {
+            fs::create_dir_all(&dir).with_path(&dir)?;
+            fs::write(&file, USER_FILE_TEMPLATE).with_path(&file)?;
+        }
+        Self::load(&file)
+    }
+
+    /// Maps a heading variant to a section, replacing an earlier mapping
+    pub fn add(&mut self, alias: &str, kind: SectionKind) {
+        let alias = fold_heading(alias);
+        if alias.is_empty() {
+            return;
+        }
+        self.aliases.retain(|(existing, _)| *existing != alias);
+        let index = self
+            .aliases
+            .iter()
+            .position(|(existing, _)| existing.len() < alias.len())
+            .unwrap_or(self.aliases.len());
+        self.aliases.insert(index, (alias, kind));
+    }
+
+    /// Adds the `Título: seção` lines of an aliases file. Comments starting
+    /// with `#` and lines naming no known section are skipped.
+    pub fn add_lines(&mut self, text: &str) {
+        for line in text.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((alias, section)) = line.rsplit_once(':') else {
+                continue;
+            };
+            if let Some(kind) = section_named(section.trim()) {
+                self.add(alias, kind);
+            }
+        }
+    }
+
+    /// Section introduced by a heading text, without its Markdown markers.
+    /// The heading matches an alias when it equals it or continues it after
+    /// a space or punctuation, as `Contexto do projeto` continues `Contexto`.
+    pub fn section_for(&self, heading: &str) -> Option<SectionKind> {
+        let heading = fold_heading(heading);
+        self.aliases
+            .iter()
+            .find(|(alias, _)| {
+                heading
+                    .strip_prefix(alias.as_str())
+                    .is_some_and(|rest| !rest.starts_with(char::is_alphanumeric))
+            })
+            .map(|(_, kind)| *kind)
+    }
+}
+
+// Section given on the right side of an aliases line: an identifier or a title
+fn section_named(name: &str) -> Option<SectionKind> {
+    SectionKind::from_id(name).or_else(|| {
+        let name = fold_heading(name);
+        Language::ALL
+            .iter()
+            .flat_map(|language| SectionKind::ALL.map(|kind| (kind.title_in(*language), kind)))
+            .find(|(title, _)| fold_heading(title) == name)
+            .map(|(_, kind)| kind)
+    })
+}
+
+// Lowercase heading without accents, surrounding emphasis or a final colon
+fn fold_heading(text: &str) -> String {
+    fold_text(
+        text.trim()
+            .trim_matches(|c: char| c == '*' || c == '_')
+            .trim_end_matches(':')
+            .trim(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_default_aliases_ignore_case_and_accents() {
+        let aliases = HeadingAliases::new();
+
+        assert_eq!(aliases.section_for("Role"), Some(SectionKind::Context));
+        assert_eq!(aliases.section_for("PERSONA"), Some(SectionKind::Context));
+        assert_eq!(
+            aliases.section_for("Instrucoes"),
+            Some(SectionKind::MainContent)
+        );
+        assert_eq!(
+            aliases.section_for("**Constraints:**"),
+            Some(SectionKind::Limitations)
+        );
+        assert_eq!(
+            aliases.section_for("Output"),
+            Some(SectionKind::OutputFormat)
+        );
+        assert_eq!(
+            aliases.section_for("Formato de Saída (JSON)"),
+            Some(SectionKind::OutputFormat)
+        );
+        // "Formatos" não continua "Formato" depois de um espaço ou pontuação
+        assert_eq!(aliases.section_for("Formatos"), None);
+        assert_eq!(aliases.section_for("Passo 1"), None);
+    }
+
+    #[test]
+    fn test_longest_alias_wins() {
+        let aliases = HeadingAliases::new();
+
+        // "Conteúdo Auxiliar" é mais específico que um apelido mais curto
+        assert_eq!(
+            aliases.section_for("Conteúdo Auxiliar"),
+            Some(SectionKind::AuxiliaryContent)
+        );
+        assert_eq!(aliases.section_for("Test cases"), Some(SectionKind::Tests));
+    }
+
+    #[test]
+    fn test_user_file_adds_and_overrides_aliases() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join(ALIASES_FILE_NAME);
+        fs::write(
+            &file,
+            "# comentário\nPerfil do assistente: context\nOutput: Conteúdo Auxiliar\nSem seção: nada\n",
+        )
+        .unwrap();
+
+        let aliases = HeadingAliases::load(&file).unwrap();
+        assert_eq!(
+            aliases.section_for("perfil do assistente"),
+            Some(SectionKind::Context)
+        );
+        assert_eq!(
+            aliases.section_for("Output"),
+            Some(SectionKind::AuxiliaryContent)
+        );
+        assert_eq!(aliases.section_for("Sem seção"), None);
+
+        let missing = HeadingAliases::load(&temp_dir.path().join("outro.txt")).unwrap();
+        assert_eq!(
+            missing.section_for("Output"),
+            Some(SectionKind::OutputFormat)
+        );
+    }
+}
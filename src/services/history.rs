@@ -0,0 +1,261 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::models::metadata::{format_timestamp, DocumentMetadata};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Name of the log listing every revision of a document
+const LOG_FILE_NAME: &str = "historico.txt";
+
+/// One saved revision of a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revision {
+    /// Sequential number, starting at 1
+    pub number: u32,
+    /// Save time, in seconds since the Unix epoch
+    pub timestamp: u64,
+    pub note: String,
+}
+
+impl Revision {
+    /// Label shown in the history list
+    pub fn label(&self) -> String {
+        let mut label = format!("#{} · {}", self.number, format_timestamp(self.timestamp));
+        if !self.note.is_empty() {
+            label.push_str(" · ");
+            label.push_str(&self.note);
+        }
+        label
+    }
+}
+
+/// Revision history of one document, kept in a hidden folder next to it
+/// (`.<arquivo>.historico/`). Revisions are written once and never changed.
+#[derive(Debug, Clone)]
+pub struct History {
+    dir: PathBuf,
+}
+
+impl History {
+    /// History of the document saved at `document`
+    pub fn for_document(document: &Path) -> Self {
+        let name = document
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let parent = document.parent().unwrap_or_else(|| Path::new("."));
+        Self {
+            dir: parent.join(format!(".{}.historico", name)),
+        }
+    }
+
+    /// Folder holding the revisions
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Lists the revisions, oldest first; a document never saved has none.
+    /// A damaged log line is a parse error naming that line.
+    pub fn revisions(&self) -> Result<Vec<Revision>> {
+        let log = self.dir.join(LOG_FILE_NAME);
+        let content = match fs::read_to_string(&log) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Error::io(e, &log)),
+        };
+
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                parse_log_line(line).ok_or_else(|| {
+                    Error::parse("revisão sem número ou data")
+                        .with_path(&log)
+                        .with_line(index + 1)
+                })
+            })
+            .collect()
+    }
+
+    /// Stores a new revision of the document text. Returns None without writing
+    /// anything when the content matches the latest revision; the modification
+    /// time in the metadata header is not compared.
+    pub fn record(&self, document: &str, note: &str, timestamp: u64) -> Result<Option<Revision>> {
+        let revisions = self.revisions()?;
+        if let Some(last) = revisions.last() {
+            if revision_content(&self.read(last.number)?) == revision_content(document) {
+                return Ok(None);
+            }
+        }
+
+        fs::create_dir_all(&self.dir).with_path(&self.dir)?;
+        let revision = Revision {
+            number: revisions.last().map(|last| last.number + 1).unwrap_or(1),
+            timestamp,
+            note: note.split_whitespace().collect::<Vec<_>>().join(" "),
+        };
+
+        // create_new guarantees an existing revision is never overwritten
+        let path = self.revision_path(revision.number);
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .with_path(&path)?;
+        file.write_all(document.as_bytes()).with_path(&path)?;
+
+        let log_path = self.dir.join(LOG_FILE_NAME);
+        let mut log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .with_path(&log_path)?;
+        writeln!(
+            log,
+            "{}\t{}\t{}",
+            revision.number, revision.timestamp, revision.note
+        )
+        .with_path(&log_path)?;
+
+        Ok(Some(revision))
+    }
+
+    /// Reads the document text of a revision
+    pub fn read(&self, number: u32) -> Result<String> {
+        let path = self.revision_path(number);
+        fs::read_to_string(&path).with_path(&path)
+    }
+
+    fn revision_path(&self, number: u32) -> PathBuf {
+        self.dir.join(format!("{:04}.txt", number))
+    }
+}
+
+// Content compared between revisions: the metadata without the modification
+// time, which every save updates, and the sections
+fn revision_content(document: &str) -> (DocumentMetadata, &str) {
+    let (mut metadata, body) = DocumentMetadata::split_header(document);
+    metadata.modified = None;
+    (metadata, body)
+}
+
+// One line of the log: number, timestamp and note, separated by tabs
+fn parse_log_line(line: &str) -> Option<Revision> {
+    let mut fields = line.splitn(3, '\t');
+    Some(Revision {
+        number: fields.next()?.parse().ok()?,
+        timestamp: fields.next()?.parse().ok()?,
+        note: fields.next().unwrap_or_default().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_history_folder_sits_next_to_document() {
+        let history = History::for_document(Path::new("/tmp/prompts/revisão.txt"));
+        assert_eq!(
+            history.dir(),
+            Path::new("/tmp/prompts/.revisão.txt.historico")
+        );
+    }
+
+    #[test]
+    fn test_record_and_read_revisions() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = History::for_document(&temp_dir.path().join("a.txt"));
+
+        let first = history
+            .record("versão 1", "primeira", 100)
+            .unwrap()
+            .unwrap();
+        let second = history.record("versão 2", "", 200).unwrap().unwrap();
+
+        assert_eq!(first.number, 1);
+        assert_eq!(second.number, 2);
+        assert_eq!(history.revisions().unwrap(), vec![first, second]);
+        assert_eq!(history.read(1).unwrap(), "versão 1");
+        assert_eq!(history.read(2).unwrap(), "versão 2");
+    }
+
+    #[test]
+    fn test_identical_save_creates_no_revision() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = History::for_document(&temp_dir.path().join("a.txt"));
+
+        history.record("igual", "", 100).unwrap();
+        assert!(history.record("igual", "de novo", 200).unwrap().is_none());
+        assert_eq!(history.revisions().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_save_changing_only_modified_creates_no_revision() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = History::for_document(&temp_dir.path().join("a.txt"));
+        let document = |modified: u64, body: &str| {
+            let mut metadata = DocumentMetadata::new();
+            metadata.title = "Revisão".to_string();
+            metadata.created = Some(100);
+            metadata.modified = Some(modified);
+            format!("{}\n\n## Contexto\n\n{}", metadata.to_header(), body)
+        };
+
+        history.record(&document(100, "texto"), "", 100).unwrap();
+        // Salvar de novo só atualiza o horário de modificação
+        assert!(history
+            .record(&document(200, "texto"), "", 200)
+            .unwrap()
+            .is_none());
+        assert!(history
+            .record(&document(300, "texto novo"), "", 300)
+            .unwrap()
+            .is_some());
+        assert_eq!(history.revisions().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_note_is_kept_on_one_line() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = History::for_document(&temp_dir.path().join("a.txt"));
+
+        let revision = history
+            .record("texto", "mais\tcurto\ne direto", 0)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(revision.note, "mais curto e direto");
+        assert_eq!(history.revisions().unwrap()[0].note, "mais curto e direto");
+        assert_eq!(
+            revision.label(),
+            "#1 · 1970-01-01 00:00 · mais curto e direto"
+        );
+    }
+
+    #[test]
+    fn test_document_without_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = History::for_document(&temp_dir.path().join("novo.txt"));
+        assert!(history.revisions().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_damaged_log_line_is_a_parse_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = History::for_document(&temp_dir.path().join("a.txt"));
+        history.record("versão 1", "", 100).unwrap();
+        let log = history.dir().join(LOG_FILE_NAME);
+        let mut content = fs::read_to_string(&log).unwrap();
+        content.push_str("dois\tontem\n");
+        fs::write(&log, content).unwrap();
+
+        // A linha danificada é apontada em vez de sumir da lista
+        let error = history.revisions().unwrap_err();
+        assert!(matches!(error, Error::Parse { .. }));
+        assert_eq!(error.context().line, Some(2));
+        assert_eq!(error.context().path.as_deref(), Some(log.as_path()));
+    }
+}
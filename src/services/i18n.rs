@@ -0,0 +1,307 @@
+use crate::models::language::Language;
+
+/// Portuguese text with its English and Spanish translations. The Portuguese
+/// text is the key, as written in `ui/app-window.slint` and in the labels of
+/// the option lists.
+type Entry = (&'static str, &'static str, &'static str);
+
+// Labels of `ui/app-window.slint`, in the order they appear
+const UI_CATALOG: &[Entry] = &[
+    ("📄 Novo documento (Ctrl+T)", "📄 New document (Ctrl+T)", "📄 Nuevo documento (Ctrl+T)"),
+    ("📂 Abrir...", "📂 Open...", "📂 Abrir..."),
+    ("💾 Salvar", "💾 Save", "💾 Guardar"),
+    ("🗂️ Pasta de trabalho", "🗂️ Workspace folder", "🗂️ Carpeta de trabajo"),
+    ("⚙️ Configurações (Ctrl+,)", "⚙️ Settings (Ctrl+,)", "⚙️ Configuración (Ctrl+,)"),
+    ("Recentes", "Recent", "Recientes"),
+    ("Nenhum arquivo recente", "No recent files", "No hay archivos recientes"),
+    ("🧹 Limpar recentes não fixados", "🧹 Clear unpinned recent files", "🧹 Borrar recientes no fijados"),
+    ("📁 Arquivo ▾", "📁 File ▾", "📁 Archivo ▾"),
+    ("Fechar outras", "Close others", "Cerrar otras"),
+    ("📚 Biblioteca", "📚 Library", "📚 Biblioteca"),
+    ("Pasta da biblioteca", "Library folder", "Carpeta de la biblioteca"),
+    ("🔍 Buscar em títulos e seções", "🔍 Search titles and sections", "🔍 Buscar en títulos y secciones"),
+    ("★ Favoritos", "★ Favorites", "★ Favoritos"),
+    ("💾 Salvar prompt atual na biblioteca", "💾 Save current prompt to the library", "💾 Guardar el prompt actual en la biblioteca"),
+    ("Título, tags e modelo vêm dos metadados do documento", "Title, tags and model come from the document metadata", "El título, las etiquetas y el modelo vienen de los metadatos del documento"),
+    ("🗂️ Pasta de Trabalho", "🗂️ Workspace Folder", "🗂️ Carpeta de Trabajo"),
+    ("Pasta de trabalho", "Workspace folder", "Carpeta de trabajo"),
+    ("🔍 Filtrar arquivos", "🔍 Filter files", "🔍 Filtrar archivos"),
+    ("Metadados", "Metadata", "Metadatos"),
+    ("(sem título)", "(untitled)", "(sin título)"),
+    ("▲ Ocultar", "▲ Hide", "▲ Ocultar"),
+    ("▼ Editar", "▼ Edit", "▼ Editar"),
+    ("Título", "Title", "Título"),
+    ("Descrição", "Description", "Descripción"),
+    ("Autor", "Author", "Autor"),
+    ("Tags (ex: rust, revisão)", "Tags (e.g. rust, review)", "Etiquetas (ej.: rust, revisión)"),
+    ("Modelo (ex: gpt-4o)", "Model (e.g. gpt-4o)", "Modelo (ej.: gpt-4o)"),
+    ("Temperatura (ex: 0.7)", "Temperature (e.g. 0.7)", "Temperatura (ej.: 0.7)"),
+    ("Máx. tokens:", "Max. tokens:", "Máx. tokens:"),
+    ("Sequências de parada (uma por linha)", "Stop sequences (one per line)", "Secuencias de parada (una por línea)"),
+    ("Outros campos (chave: valor, um por linha)", "Other fields (key: value, one per line)", "Otros campos (clave: valor, uno por línea)"),
+    ("Few-Shot Examples", "Few-Shot Examples", "Ejemplos Few-Shot"),
+    ("Faça uma pergunta e exponha seu motivo de forma simples e resumida", "Ask a question and state your reason simply and briefly", "Haz una pregunta y expón tu motivo de forma simple y resumida"),
+    ("Exemplo: Preciso de ajuda com programação Python porque estou iniciando na linguagem e quero criar um script para automatizar tarefas.", "Example: I need help with Python programming because I am new to the language and want to write a script to automate tasks.", "Ejemplo: Necesito ayuda con programación en Python porque estoy empezando con el lenguaje y quiero crear un script para automatizar tareas."),
+    ("Contexto", "Context", "Contexto"),
+    ("Defina o contexto ou persona da IA", "Define the context or persona of the AI", "Define el contexto o la persona de la IA"),
+    ("Exemplo: Você é um especialista em...", "Example: You are an expert in...", "Ejemplo: Eres un experto en..."),
+    ("Conteúdo Principal", "Main Content", "Contenido Principal"),
+    ("Instruções principais para a IA", "Main instructions for the AI", "Instrucciones principales para la IA"),
+    ("Descreva a tarefa principal que a IA deve executar...", "Describe the main task the AI should perform...", "Describe la tarea principal que la IA debe realizar..."),
+    ("Conteúdo Auxiliar", "Auxiliary Content", "Contenido Auxiliar"),
+    ("Informações adicionais e exemplos", "Additional information and examples", "Información adicional y ejemplos"),
+    ("Informações de suporte, referências, etc.", "Supporting information, references, etc.", "Información de apoyo, referencias, etc."),
+    ("📄 Arquivos", "📄 Files", "📄 Archivos"),
+    ("📁 Pasta", "📁 Folder", "📁 Carpeta"),
+    ("Glob (ex: src/**/*.rs)", "Glob (e.g. src/**/*.rs)", "Glob (ej.: src/**/*.rs)"),
+    ("🔎 Adicionar glob", "🔎 Add glob", "🔎 Añadir glob"),
+    ("Incluir (ex: *.rs, *.toml)", "Include (e.g. *.rs, *.toml)", "Incluir (ej.: *.rs, *.toml)"),
+    ("Excluir (ex: target/**, *.lock)", "Exclude (e.g. target/**, *.lock)", "Excluir (ej.: target/**, *.lock)"),
+    ("Respeitar .gitignore", "Respect .gitignore", "Respetar .gitignore"),
+    ("💡 Arraste arquivos ou pastas para a janela para anexá-los aqui", "💡 Drag files or folders onto the window to attach them here", "💡 Arrastra archivos o carpetas a la ventana para adjuntarlos aquí"),
+    ("Limitações", "Limitations", "Limitaciones"),
+    ("Restrições e limitações a serem observadas", "Restrictions and limitations to observe", "Restricciones y limitaciones a respetar"),
+    ("Não faça X, evite Y, limite Z...", "Do not do X, avoid Y, limit Z...", "No hagas X, evita Y, limita Z..."),
+    ("Refatoração (Código)", "Refactoring (Code)", "Refactorización (Código)"),
+    ("Instruções de refatoração...", "Refactoring instructions...", "Instrucciones de refactorización..."),
+    ("Linhas (ex: 10-42)", "Lines (e.g. 10-42)", "Líneas (ej.: 10-42)"),
+    ("📎 Anexar código", "📎 Attach code", "📎 Adjuntar código"),
+    ("Performance", "Performance", "Rendimiento"),
+    ("Legibilidade", "Readability", "Legibilidad"),
+    ("Tratamento de erros", "Error handling", "Manejo de errores"),
+    ("Orientações", "Guidance", "Orientaciones"),
+    ("Tom, estilo, público-alvo...", "Tone, style, target audience...", "Tono, estilo, público objetivo..."),
+    ("Testes", "Tests", "Pruebas"),
+    ("Requisitos de teste...", "Test requirements...", "Requisitos de prueba..."),
+    ("Framework:", "Framework:", "Framework:"),
+    ("Cobertura %:", "Coverage %:", "Cobertura %:"),
+    ("Unitários", "Unit", "Unitarias"),
+    ("Integração", "Integration", "Integración"),
+    ("Propriedades", "Property-based", "Propiedades"),
+    ("dado", "given", "dado"),
+    ("quando", "when", "cuando"),
+    ("então", "then", "entonces"),
+    ("Nome do caso de teste", "Test case name", "Nombre del caso de prueba"),
+    ("Dado...", "Given...", "Dado..."),
+    ("Quando...", "When...", "Cuando..."),
+    ("Então...", "Then...", "Entonces..."),
+    ("➕ Adicionar caso", "➕ Add case", "➕ Añadir caso"),
+    ("Formato de Saída", "Output Format", "Formato de Salida"),
+    ("Markdown, JSON, texto...", "Markdown, JSON, text...", "Markdown, JSON, texto..."),
+    ("Contexto do Git", "Git Context", "Contexto de Git"),
+    ("Importe diffs, commits recentes ou arquivos alterados de um repositório local", "Import diffs, recent commits or changed files from a local repository", "Importa diffs, commits recientes o archivos modificados de un repositorio local"),
+    ("Caminho do repositório", "Repository path", "Ruta del repositorio"),
+    ("📁 Repositório", "📁 Repository", "📁 Repositorio"),
+    ("Diff staged", "Staged diff", "Diff preparado"),
+    ("Diff não staged", "Unstaged diff", "Diff no preparado"),
+    ("Diff entre refs", "Diff between refs", "Diff entre refs"),
+    ("Commits recentes", "Recent commits", "Commits recientes"),
+    ("Arquivos alterados", "Changed files", "Archivos modificados"),
+    ("De (ex: main)", "From (e.g. main)", "Desde (ej.: main)"),
+    ("Para (ex: HEAD)", "To (e.g. HEAD)", "Hasta (ej.: HEAD)"),
+    ("Inserir em:", "Insert into:", "Insertar en:"),
+    ("⬇️ Importar", "⬇️ Import", "⬇️ Importar"),
+    ("Mapa do Repositório", "Repository Map", "Mapa del Repositorio"),
+    ("Gere uma árvore compacta com tamanhos, tokens e símbolos principais", "Generate a compact tree with sizes, tokens and main symbols", "Genera un árbol compacto con tamaños, tokens y símbolos principales"),
+    ("Pasta do projeto", "Project folder", "Carpeta del proyecto"),
+    ("Símbolos", "Symbols", "Símbolos"),
+    ("Orçamento (tokens):", "Budget (tokens):", "Presupuesto (tokens):"),
+    ("🗺️ Gerar mapa", "🗺️ Generate map", "🗺️ Generar mapa"),
+    ("Modo Compacto", "Compact Mode", "Modo Compacto"),
+    ("Reduz tokens sem mudar o sentido; blocos de código nunca são alterados", "Reduces tokens without changing the meaning; code blocks are never changed", "Reduce tokens sin cambiar el sentido; los bloques de código nunca se modifican"),
+    ("Ativar modo compacto", "Enable compact mode", "Activar modo compacto"),
+    ("Espaços", "Whitespace", "Espacios"),
+    ("Linhas repetidas", "Repeated lines", "Líneas repetidas"),
+    ("Uma substituição por linha: frase => substituição", "One replacement per line: phrase => replacement", "Una sustitución por línea: frase => sustitución"),
+    ("Orçamento de Tokens", "Token Budget", "Presupuesto de Tokens"),
+    ("Corta automaticamente as seções menos prioritárias quando o prompt passa da meta", "Automatically trims the lowest-priority sections when the prompt exceeds the target", "Recorta automáticamente las secciones menos prioritarias cuando el prompt supera la meta"),
+    ("Meta (0 = sem limite):", "Target (0 = no limit):", "Meta (0 = sin límite):"),
+    ("Anexos:", "Attachments:", "Adjuntos:"),
+    ("Série de Prompts", "Prompt Series", "Serie de Prompts"),
+    ("Divide um Conteúdo Auxiliar grande em partes numeradas (gere o prompt antes de exportar)", "Splits a large Auxiliary Content into numbered parts (generate the prompt before exporting)", "Divide un Contenido Auxiliar grande en partes numeradas (genera el prompt antes de exportar)"),
+    ("Tokens por parte:", "Tokens per part:", "Tokens por parte:"),
+    ("Prompt final para combinar respostas", "Final prompt to combine answers", "Prompt final para combinar respuestas"),
+    ("🗂️ Exportar arquivos numerados", "🗂️ Export numbered files", "🗂️ Exportar archivos numerados"),
+    ("🧾 Exportar JSONL", "🧾 Export JSONL", "🧾 Exportar JSONL"),
+    ("Histórico de Versões", "Version History", "Historial de Versiones"),
+    ("Salve ou abra um documento para registrar versões", "Save or open a document to record versions", "Guarda o abre un documento para registrar versiones"),
+    ("Nota da próxima versão (ex: instruções mais curtas)", "Note for the next version (e.g. shorter instructions)", "Nota de la próxima versión (ej.: instrucciones más cortas)"),
+    ("↩️ Restaurar", "↩️ Restore", "↩️ Restaurar"),
+    ("🔍 Comparar", "🔍 Compare", "🔍 Comparar"),
+    ("Histórico de Edições", "Edit History", "Historial de Ediciones"),
+    ("As alterações do documento aparecerão aqui (Ctrl+Z desfaz, Ctrl+Y refaz)", "Document changes will appear here (Ctrl+Z undoes, Ctrl+Y redoes)", "Los cambios del documento aparecerán aquí (Ctrl+Z deshace, Ctrl+Y rehace)"),
+    ("Refazer até aqui", "Redo up to here", "Rehacer hasta aquí"),
+    ("Desfazer até aqui", "Undo back to here", "Deshacer hasta aquí"),
+    ("Comparar Documentos", "Compare Documents", "Comparar Documentos"),
+    ("Alinha dois arquivos por seção e marca palavras [-removidas-] e {+adicionadas+}", "Aligns two files by section and marks [-removed-] and {+added+} words", "Alinea dos archivos por sección y marca las palabras [-eliminadas-] y {+añadidas+}"),
+    ("Arquivo antigo", "Old file", "Archivo antiguo"),
+    ("Arquivo novo", "New file", "Archivo nuevo"),
+    ("Mesclar Versões", "Merge Versions", "Fusionar Versiones"),
+    ("Junta duas cópias editadas do mesmo prompt; seções alteradas nos dois lados viram conflitos", "Joins two edited copies of the same prompt; sections changed on both sides become conflicts", "Une dos copias editadas del mismo prompt; las secciones modificadas en ambos lados se vuelven conflictos"),
+    ("Base (versão original)", "Base (original version)", "Base (versión original)"),
+    ("Nossa versão", "Our version", "Nuestra versión"),
+    ("Versão deles", "Their version", "Su versión"),
+    ("🔀 Mesclar", "🔀 Merge", "🔀 Fusionar"),
+    ("📝 Aplicar no editor", "📝 Apply to the editor", "📝 Aplicar en el editor"),
+    (" (resolvido)", " (resolved)", " (resuelto)"),
+    (" (conflito)", " (conflict)", " (conflicto)"),
+    ("⬅️ Usar nossa", "⬅️ Use ours", "⬅️ Usar la nuestra"),
+    ("Usar as duas", "Use both", "Usar ambas"),
+    ("Usar deles ➡️", "Use theirs ➡️", "Usar la suya ➡️"),
+    ("📄 Preview do Prompt", "📄 Prompt Preview", "📄 Vista previa del Prompt"),
+    ("O preview do prompt aparecerá aqui...", "The prompt preview will appear here...", "La vista previa del prompt aparecerá aquí..."),
+    ("🚀 Gerar Prompt", "🚀 Generate Prompt", "🚀 Generar Prompt"),
+    ("📂 Abrir", "📂 Open", "📂 Abrir"),
+    ("📋 Copiar", "📋 Copy", "📋 Copiar"),
+    ("🗂️ Pasta", "🗂️ Folder", "🗂️ Carpeta"),
+    ("↶ Desfazer", "↶ Undo", "↶ Deshacer"),
+    ("↷ Refazer", "↷ Redo", "↷ Rehacer"),
+    ("🗑️ Limpar Tudo", "🗑️ Clear All", "🗑️ Limpiar Todo"),
+    ("● Alterações não salvas", "● Unsaved changes", "● Cambios sin guardar"),
+    ("⚙️ Configurações", "⚙️ Settings", "⚙️ Configuración"),
+    ("Pasta inicial ao salvar (vazia: a do sistema):", "Starting folder when saving (empty: the system one):", "Carpeta inicial al guardar (vacía: la del sistema):"),
+    ("Pasta do sistema", "System folder", "Carpeta del sistema"),
+    ("Nome de arquivo sugerido:", "Suggested file name:", "Nombre de archivo sugerido:"),
+    ("Pasta da biblioteca:", "Library folder:", "Carpeta de la biblioteca:"),
+    ("Pasta de trabalho aberta ao iniciar (vazia: nenhuma):", "Workspace folder opened on launch (empty: none):", "Carpeta de trabajo abierta al iniciar (vacía: ninguna):"),
+    ("Nenhuma", "None", "Ninguna"),
+    ("Formato ao salvar e copiar:", "Format when saving and copying:", "Formato al guardar y copiar:"),
+    ("Contagem de tokens:", "Token count:", "Recuento de tokens:"),
+    ("Autosalvamento (segundos):", "Autosave (seconds):", "Autoguardado (segundos):"),
+    ("Tamanho da fonte (px):", "Font size (px):", "Tamaño de fuente (px):"),
+    ("Tema:", "Theme:", "Tema:"),
+    ("Idioma da interface:", "Interface language:", "Idioma de la interfaz:"),
+    ("Idioma do prompt gerado:", "Generated prompt language:", "Idioma del prompt generado:"),
+    ("Codificação ao abrir:", "Encoding when opening:", "Codificación al abrir:"),
+    ("Finais de linha ao salvar:", "Line endings when saving:", "Finales de línea al guardar:"),
+    ("Cópias de segurança ao salvar:", "Backups kept when saving:", "Copias de seguridad al guardar:"),
+    ("↺ Restaurar padrões", "↺ Restore defaults", "↺ Restaurar valores predeterminados"),
+    ("Cancelar", "Cancel", "Cancelar"),
+    ("🧠 Treinar classificador com a biblioteca", "🧠 Train classifier on the library", "🧠 Entrenar clasificador con la biblioteca"),
+    ("📥 Revisar importação", "📥 Review import", "📥 Revisar importación"),
+    ("O arquivo não tem títulos de seção. Confira a seção de cada bloco antes de importar:", "The file has no section headings. Check the section of each block before importing:", "El archivo no tiene títulos de sección. Revisa la sección de cada bloque antes de importar:"),
+    ("Confiança:", "Confidence:", "Confianza:"),
+    ("⤴ Juntar", "⤴ Merge", "⤴ Unir"),
+    ("✂ Dividir", "✂ Split", "✂ Dividir"),
+    ("✅ Importar", "✅ Import", "✅ Importar"),
+];
+
+// Labels of the option lists filled from Rust
+const OPTION_CATALOG: &[Entry] = &[
+    ("Manter início", "Keep the beginning", "Mantener el inicio"),
+    ("Manter fim", "Keep the end", "Mantener el final"),
+    ("Cortar o meio", "Cut the middle", "Recortar el medio"),
+    (
+        "Remover arquivos inteiros",
+        "Drop whole files",
+        "Eliminar archivos completos",
+    ),
+    (
+        "Caracteres (≈ 4 por token)",
+        "Characters (≈ 4 per token)",
+        "Caracteres (≈ 4 por token)",
+    ),
+    (
+        "Palavras (≈ 3 para 4 tokens)",
+        "Words (≈ 3 per 4 tokens)",
+        "Palabras (≈ 3 por 4 tokens)",
+    ),
+    ("Texto simples", "Plain text", "Texto simple"),
+    (
+        "Texto com marcadores de seção",
+        "Text with section markers",
+        "Texto con marcadores de sección",
+    ),
+    (
+        "Documento editável (com metadados)",
+        "Editable document (with metadata)",
+        "Documento editable (con metadatos)",
+    ),
+    ("Seguir o sistema", "Follow the system", "Seguir el sistema"),
+    ("Claro", "Light", "Claro"),
+    ("Escuro", "Dark", "Oscuro"),
+    (
+        "Detectar automaticamente",
+        "Detect automatically",
+        "Detectar automáticamente",
+    ),
+    ("UTF-8 com BOM", "UTF-8 with BOM", "UTF-8 con BOM"),
+    (
+        "Manter as do arquivo",
+        "Keep those of the file",
+        "Mantener los del archivo",
+    ),
+    (
+        "LF (Linux e macOS)",
+        "LF (Linux and macOS)",
+        "LF (Linux y macOS)",
+    ),
+    ("CRLF (Windows)", "CRLF (Windows)", "CRLF (Windows)"),
+];
+
+/// Translates a Portuguese label into `language`. Text missing from the
+/// catalog is returned unchanged, so untranslated labels stay readable.
+pub fn translate(text: &str, language: Language) -> &str {
+    let entry = UI_CATALOG
+        .iter()
+        .chain(OPTION_CATALOG)
+        .find(|(portuguese, _, _)| *portuguese == text);
+    match (entry, language) {
+        (Some((_, english, _)), Language::English) => english,
+        (Some((_, _, spanish)), Language::Spanish) => spanish,
+        _ => text,
+    }
+}
+
+/// Whether the catalog has a translation for a Portuguese label
+pub fn is_translated(text: &str) -> bool {
+    UI_CATALOG
+        .iter()
+        .chain(OPTION_CATALOG)
+        .any(|(portuguese, _, _)| *portuguese == text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::budget::TruncationStrategy;
+    use crate::services::settings::{Renderer, Theme};
+    use crate::services::token_counter::Tokenizer;
+
+    #[test]
+    fn test_translate_labels() {
+        assert_eq!(translate("💾 Salvar", Language::English), "💾 Save");
+        assert_eq!(translate("💾 Salvar", Language::Spanish), "💾 Guardar");
+        assert_eq!(translate("💾 Salvar", Language::Portuguese), "💾 Salvar");
+        // Textos fora do catálogo continuam em português
+        assert_eq!(translate("Texto novo", Language::English), "Texto novo");
+    }
+
+    #[test]
+    fn test_catalog_has_unique_complete_entries() {
+        let entries: Vec<&Entry> = UI_CATALOG.iter().chain(OPTION_CATALOG).collect();
+        for (index, (portuguese, english, spanish)) in entries.iter().enumerate() {
+            assert!(!english.is_empty() && !spanish.is_empty(), "{}", portuguese);
+            assert!(
+                entries[..index]
+                    .iter()
+                    .all(|(other, _, _)| other != portuguese),
+                "{} repetido",
+                portuguese
+            );
+        }
+    }
+
+    #[test]
+    fn test_option_labels_are_translated() {
+        let labels = TruncationStrategy::ALL
+            .iter()
+            .map(|strategy| strategy.label())
+            .chain(Tokenizer::ALL.iter().map(|tokenizer| tokenizer.label()))
+            .chain(Renderer::ALL.iter().map(|renderer| renderer.label()))
+            .chain(Theme::ALL.iter().map(|theme| theme.label()));
+        for label in labels {
+            assert!(is_translated(label), "{}", label);
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use crate::models::{metadata::DocumentMetadata, section::SectionKind};
+use crate::services::heading_aliases::HeadingAliases;
+use crate::services::prompt_generator::PromptData;
+use crate::services::prompt_parser::unstructured_paragraphs;
+use crate::services::section_classifier::{
+    assign_paragraphs, ClassifiedParagraph, SectionClassifier,
+};
+
+/// Blocks of a file without section headings, with the section proposed for
+/// each one, reviewed by the user before the file is loaded. Blocks can be
+/// moved to another section, merged, split or dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportReview {
+    /// Metadata header of the file, kept as is
+    pub metadata: DocumentMetadata,
+    blocks: Vec<ClassifiedParagraph>,
+}
+
+impl ImportReview {
+    /// Review of a file whose paragraphs go to the sections proposed by the
+    /// classifier; None when the file has section headings or no text
+    pub fn from_content(
+        content: &str,
+        aliases: &HeadingAliases,
+        classifier: &SectionClassifier,
+    ) -> Option<Self> {
+        let blocks = unstructured_paragraphs(content, aliases, classifier)?;
+        if blocks.is_empty() {
+            return None;
+        }
+        let (metadata, _) = DocumentMetadata::split_header(content);
+        Some(Self { metadata, blocks })
+    }
+
+    pub fn blocks(&self) -> &[ClassifiedParagraph] {
+        &self.blocks
+    }
+
+    /// Moves a block to a section picked by the user, who is then sure of it
+    pub fn assign(&mut self, index: usize, kind: SectionKind) {
+        if let Some(block) = self.blocks.get_mut(index) {
+            block.kind = kind;
+            block.confidence = 1.0;
+        }
+    }
+
+    /// Appends a block to the one before it, in the section of the earlier
+    /// block. Returns false for the first block.
+    pub fn merge_with_previous(&mut self, index: usize) -> bool {
+        if index == 0 || index >= self.blocks.len() {
+            return false;
+        }
+        let block = self.blocks.remove(index);
+        let previous = &mut self.blocks[index - 1];
+        previous.text = format!("{}\n\n{}", previous.text, block.text);
+        previous.confidence = previous.confidence.min(block.confidence);
+        true
+    }
+
+    /// Splits a block into its lines, or into its sentences when it has a
+    /// single line, proposing a section for each piece. Returns false when
+    /// the block cannot be split.
+    pub fn split(&mut self, index: usize, classifier: &SectionClassifier) -> bool {
+        let Some(block) = self.blocks.get(index) else {
+            return false;
+        };
+        let pieces = split_block(&block.text);
+        if pieces.len() < 2 {
+            return false;
+        }
+
+        let kind = block.kind;
+        let pieces: Vec<ClassifiedParagraph> = pieces
+            .into_iter()
+            .map(|text| {
+                let classification = classifier.classify(&text);
+                ClassifiedParagraph {
+                    kind: classification.map_or(kind, |c| c.kind),
+                    confidence: classification.map_or(0.0, |c| c.confidence),
+                    text,
+                }
+            })
+            .collect();
+        self.blocks.splice(index..=index, pieces);
+        true
+    }
+
+    /// Drops a block from the import
+    pub fn remove(&mut self, index: usize) {
+        if index < self.blocks.len() {
+            self.blocks.remove(index);
+        }
+    }
+
+    /// Prompt with every block in its section, in file order
+    pub fn into_prompt(self) -> PromptData {
+        let mut data = PromptData::new();
+        data.metadata = self.metadata;
+        assign_paragraphs(&self.blocks, &mut data);
+        data
+    }
+}
+
+// Lines of a block, or its sentences when it has a single line
+fn split_block(text: &str) -> Vec<String> {
+    let lines: Vec<String> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    if lines.len() > 1 {
+        return lines;
+    }
+
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((_, c)) = chars.next() {
+        if matches!(c, '.' | '!' | '?') {
+            if let Some(&(next, ' ')) = chars.peek() {
+                sentences.push(text[start..next].trim().to_string());
+                start = next;
+            }
+        }
+    }
+    sentences.push(text[start..].trim().to_string());
+    sentences.retain(|sentence| !sentence.is_empty());
+    sentences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review(content: &str) -> ImportReview {
+        ImportReview::from_content(
+            content,
+            &HeadingAliases::new(),
+            &SectionClassifier::bundled(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_only_files_without_headings_are_reviewed() {
+        let aliases = HeadingAliases::new();
+        let classifier = SectionClassifier::bundled();
+
+        assert!(ImportReview::from_content("## Contexto\nabc", &aliases, &classifier).is_none());
+        assert!(ImportReview::from_content("  \n\n ", &aliases, &classifier).is_none());
+
+        let review = review("---\ntitle: Resumo\n---\nResuma o texto.\n\nResponda em Markdown.");
+        assert_eq!(review.metadata.title, "Resumo");
+        assert_eq!(review.blocks().len(), 2);
+    }
+
+    #[test]
+    fn test_assign_merge_and_remove_blocks() {
+        let mut review = review("Primeiro bloco.\n\nSegundo bloco.\n\nTerceiro bloco.");
+
+        review.assign(2, SectionKind::Guidance);
+        assert_eq!(review.blocks()[2].kind, SectionKind::Guidance);
+        assert!(!review.blocks()[2].is_uncertain());
+
+        assert!(!review.merge_with_previous(0));
+        assert!(review.merge_with_previous(1));
+        assert_eq!(review.blocks().len(), 2);
+        assert_eq!(review.blocks()[0].text, "Primeiro bloco.\n\nSegundo bloco.");
+
+        review.remove(0);
+        let data = review.into_prompt();
+        assert_eq!(
+            data.section_body(SectionKind::Guidance).as_deref(),
+            Some("Terceiro bloco.")
+        );
+        assert_eq!(data.section_body(SectionKind::MainContent), None);
+    }
+
+    #[test]
+    fn test_split_block_into_lines_or_sentences() {
+        let classifier = SectionClassifier::bundled();
+        let mut review = review(
+            "Você é um revisor. Responda em JSON com os campos nota e motivo.\n\nLinha um\nLinha dois",
+        );
+
+        assert!(review.split(0, &classifier));
+        assert_eq!(review.blocks().len(), 3);
+        assert_eq!(review.blocks()[0].text, "Você é um revisor.");
+        assert_eq!(review.blocks()[0].kind, SectionKind::Context);
+        assert_eq!(review.blocks()[1].kind, SectionKind::OutputFormat);
+
+        assert!(review.split(2, &classifier));
+        assert_eq!(review.blocks().len(), 4);
+        assert_eq!(review.blocks()[2].text, "Linha um");
+        assert_eq!(review.blocks()[3].text, "Linha dois");
+        assert!(!review.split(3, &classifier));
+    }
+}
@@ -0,0 +1,120 @@
+/// Tracks fenced code blocks (```` ``` ```` or `~~~`) while a text is read line by line.
+#[derive(Debug, Clone, Default)]
+pub struct FenceTracker {
+    /// Fence character and length of the block currently open
+    open: Option<(char, usize)>,
+}
+
+impl FenceTracker {
+    pub fn new() -> Self {
+        Self { open: None }
+    }
+
+    /// Returns true while inside a fenced block
+    pub fn is_open(&self) -> bool {
+        self.open.is_some()
+    }
+
+    /// Feeds the next line and returns true when it belongs to a fenced block,
+    /// including the opening and closing fence lines themselves
+    pub fn feed(&mut self, line: &str) -> bool {
+        let trimmed = line.trim_start();
+        match self.open {
+            Some((fence_char, fence_len)) => {
+                let run = trimmed.chars().take_while(|c| *c == fence_char).count();
+                if run >= fence_len && trimmed[run * fence_char.len_utf8()..].trim().is_empty() {
+                    self.open = None;
+                }
+                true
+            }
+            None => match fence_opening(trimmed) {
+                Some(fence) => {
+                    self.open = Some(fence);
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+
+    /// Line that closes the block currently open, if any
+    pub fn closing_fence(&self) -> Option<String> {
+        self.open
+            .map(|(fence_char, fence_len)| fence_char.to_string().repeat(fence_len))
+    }
+}
+
+// Recognises the opening line of a fenced block
+fn fence_opening(trimmed: &str) -> Option<(char, usize)> {
+    let fence_char = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let run = trimmed.chars().take_while(|c| *c == fence_char).count();
+    (run >= 3).then_some((fence_char, run))
+}
+
+/// Removes blank lines at the start and whitespace at the end of a text,
+/// keeping the indentation of its first line
+pub fn trim_blank_lines(text: &str) -> &str {
+    let text = text.trim_end();
+    let start = text
+        .find(|c: char| !c.is_whitespace())
+        .and_then(|first| text[..first].rfind('\n').map(|newline| newline + 1))
+        .unwrap_or(0);
+    &text[start..]
+}
+
+/// Returns true for Markdown ATX headings such as `## Título`
+pub fn is_heading(line: &str) -> bool {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    (1..=6).contains(&hashes) && line[hashes..].starts_with(' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fence_tracker_marks_block_lines() {
+        let text = "antes\n```rust\nfn main() {}\n```\ndepois";
+        let mut tracker = FenceTracker::new();
+        let marks: Vec<bool> = text.lines().map(|line| tracker.feed(line)).collect();
+        assert_eq!(marks, vec![false, true, true, true, false]);
+        assert!(!tracker.is_open());
+    }
+
+    #[test]
+    fn test_fence_tracker_needs_matching_fence() {
+        // Um bloco aberto com quatro crases só fecha com quatro ou mais
+        let text = "````md\n```\ninterno\n```\n````\nfora";
+        let mut tracker = FenceTracker::new();
+        let marks: Vec<bool> = text.lines().map(|line| tracker.feed(line)).collect();
+        assert_eq!(marks, vec![true, true, true, true, true, false]);
+    }
+
+    #[test]
+    fn test_fence_tracker_closing_fence() {
+        let mut tracker = FenceTracker::new();
+        tracker.feed("~~~~python");
+        assert_eq!(tracker.closing_fence(), Some("~~~~".to_string()));
+        tracker.feed("~~~~");
+        assert_eq!(tracker.closing_fence(), None);
+    }
+
+    #[test]
+    fn test_trim_blank_lines_keeps_indentation() {
+        assert_eq!(
+            trim_blank_lines("\n  \n    src/\n      main.rs\n\n"),
+            "    src/\n      main.rs"
+        );
+        assert_eq!(trim_blank_lines("texto"), "texto");
+        assert_eq!(trim_blank_lines(" \n \n"), "");
+    }
+
+    #[test]
+    fn test_is_heading() {
+        assert!(is_heading("# Título"));
+        assert!(is_heading("### Seção"));
+        assert!(!is_heading("#sem espaço"));
+        assert!(!is_heading("####### demais"));
+        assert!(!is_heading("texto # não"));
+    }
+}
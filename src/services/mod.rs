@@ -1,2 +1,29 @@
+pub mod app_dirs;
+pub mod attachment_reader;
+pub mod budget;
+pub mod chunker;
+pub mod compactor;
 pub mod file_service;
+pub mod git_ingest;
+pub mod heading_aliases;
+pub mod history;
+pub mod i18n;
+pub mod import_review;
+pub mod markdown;
 pub mod prompt_generator;
+pub mod prompt_library;
+pub mod prompt_merge;
+pub mod prompt_parser;
+pub mod prompt_series;
+pub mod recent_files;
+pub mod recovery;
+pub mod repo_map;
+pub mod section_classifier;
+pub mod section_diff;
+pub mod settings;
+pub mod source_reader;
+pub mod tabs;
+pub mod text_encoding;
+pub mod token_counter;
+pub mod undo;
+pub mod workspace;
@@ -1,8 +1,12 @@
 use crate::models::{
     auxiliary_content::AuxiliaryContent, context::Context, few_shot::FewShot, guidance::Guidance,
-    limitations::Limitations, main_content::MainContent, output_format::OutputFormat,
-    refactoring::Refactoring, tests::Tests,
+    language::Language, limitations::Limitations, main_content::MainContent,
+    metadata::DocumentMetadata, output_format::OutputFormat, refactoring::Refactoring,
+    section::SectionKind, tests::Tests,
 };
+use crate::services::attachment_reader::render_auxiliary;
+use crate::services::markdown::trim_blank_lines;
+use crate::services::source_reader::render_refactoring;
 
 /// Struct to hold all prompt sections before generating the final prompt
 #[derive(Debug, Clone)]
@@ -16,6 +20,8 @@ pub struct PromptData {
     pub guidance: Option<Guidance>,
     pub tests: Option<Tests>,
     pub output_format: Option<OutputFormat>,
+    /// Document metadata; never part of the prompt text itself
+    pub metadata: DocumentMetadata,
 }
 
 impl PromptData {
@@ -31,6 +37,88 @@ impl PromptData {
             guidance: None,
             tests: None,
             output_format: None,
+            metadata: DocumentMetadata::new(),
+        }
+    }
+
+    /// Returns the rendered body of a section, or None when it is absent or blank
+    pub fn section_body(&self, kind: SectionKind) -> Option<String> {
+        let body = match kind {
+            SectionKind::FewShot => self.few_shot.as_ref().map(|s| s.content.clone()),
+            SectionKind::Context => self.context.as_ref().map(|s| s.description.clone()),
+            SectionKind::MainContent => self.main_content.as_ref().map(|s| s.instructions.clone()),
+            SectionKind::AuxiliaryContent => {
+                self.auxiliary_content.as_ref().map(|s| s.render_body())
+            }
+            SectionKind::Limitations => self.limitations.as_ref().map(|s| s.text.clone()),
+            SectionKind::Refactoring => self.refactoring.as_ref().map(|s| s.render_body()),
+            SectionKind::Guidance => self.guidance.as_ref().map(|s| s.text.clone()),
+            SectionKind::Tests => self.tests.as_ref().map(|s| s.render_body()),
+            SectionKind::OutputFormat => self.output_format.as_ref().map(|s| s.text.clone()),
+        };
+        body.filter(|body| !body.trim().is_empty())
+    }
+
+    /// Returns the body of a section as it goes into the prompt, with the files
+    /// referenced by the section read from disk, or None when it is absent or blank
+    pub fn prompt_body(&self, kind: SectionKind) -> Option<String> {
+        let body = match kind {
+            SectionKind::AuxiliaryContent => self.auxiliary_content.as_ref().map(render_auxiliary),
+            SectionKind::Refactoring => self.refactoring.as_ref().map(render_refactoring),
+            _ => return self.section_body(kind),
+        };
+        body.filter(|body| !body.trim().is_empty())
+    }
+
+    /// Replaces a section with plain text, dropping any structured extras
+    /// (attachments, goals, test cases) that were already rendered into it
+    pub fn set_section_body(&mut self, kind: SectionKind, body: String) {
+        match kind {
+            SectionKind::FewShot => {
+                let mut section = FewShot::new();
+                section.content = body;
+                self.few_shot = Some(section);
+            }
+            SectionKind::Context => {
+                let mut section = Context::new();
+                section.description = body;
+                self.context = Some(section);
+            }
+            SectionKind::MainContent => {
+                let mut section = MainContent::new();
+                section.instructions = body;
+                self.main_content = Some(section);
+            }
+            SectionKind::AuxiliaryContent => {
+                let mut section = AuxiliaryContent::new();
+                section.data = body;
+                self.auxiliary_content = Some(section);
+            }
+            SectionKind::Limitations => {
+                let mut section = Limitations::new();
+                section.text = body;
+                self.limitations = Some(section);
+            }
+            SectionKind::Refactoring => {
+                let mut section = Refactoring::new();
+                section.text = body;
+                self.refactoring = Some(section);
+            }
+            SectionKind::Guidance => {
+                let mut section = Guidance::new();
+                section.text = body;
+                self.guidance = Some(section);
+            }
+            SectionKind::Tests => {
+                let mut section = Tests::new();
+                section.text = body;
+                self.tests = Some(section);
+            }
+            SectionKind::OutputFormat => {
+                let mut section = OutputFormat::new();
+                section.text = body;
+                self.output_format = Some(section);
+            }
         }
     }
 
@@ -66,7 +154,7 @@ impl PromptData {
         }
 
         if let Some(section) = &self.auxiliary_content {
-            let text = section.generate_text();
+            let text = AuxiliaryContent::with_markers(&render_auxiliary(section));
             sections.push(if include_section_markers {
                 text
             } else {
@@ -84,7 +172,7 @@ impl PromptData {
         }
 
         if let Some(section) = &self.refactoring {
-            let text = section.generate_text();
+            let text = Refactoring::with_markers(&render_refactoring(section));
             sections.push(if include_section_markers {
                 text
             } else {
@@ -137,81 +225,157 @@ impl PromptData {
         cleaned_lines.join("\n")
     }
 
+    /// Builds the native document: the metadata header followed by every section
+    /// under its `## ` heading, without the preview footer, so it can be parsed back
+    pub fn build_document(&self) -> String {
+        self.build_document_in(Language::Portuguese)
+    }
+
+    /// Builds the native document with the section headings in `language`
+    pub fn build_document_in(&self, language: Language) -> String {
+        let header = self.metadata.to_header();
+        let sections = SectionKind::ALL
+            .iter()
+            .filter_map(|kind| {
+                self.section_body(*kind).map(|body| {
+                    format!(
+                        "## {}\n\n{}",
+                        kind.title_in(language),
+                        trim_blank_lines(&body)
+                    )
+                })
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        match (header.is_empty(), sections.is_empty()) {
+            (true, _) => sections,
+            (false, true) => header,
+            (false, false) => format!("{}\n\n{}", header, sections),
+        }
+    }
+
     /// Builds a clean prompt for preview with section titles instead of markers
     pub fn build_preview_prompt(&self) -> String {
+        self.build_preview_prompt_in(Language::Portuguese)
+    }
+
+    /// Builds the preview with the section titles and the footer note in `language`
+    pub fn build_preview_prompt_in(&self, language: Language) -> String {
         let mut sections: Vec<String> = Vec::new();
 
         if let Some(section) = &self.few_shot {
             if !section.content.trim().is_empty() {
                 sections.push(format!(
-                    "## Few-Shot Examples\n\n{}",
-                    section.content.trim()
+                    "## {}\n\n{}",
+                    SectionKind::FewShot.title_in(language),
+                    trim_blank_lines(&section.content)
                 ));
             }
         }
 
         if let Some(section) = &self.context {
             if !section.description.trim().is_empty() {
-                sections.push(format!("## Contexto\n\n{}", section.description.trim()));
+                sections.push(format!(
+                    "## {}\n\n{}",
+                    SectionKind::Context.title_in(language),
+                    trim_blank_lines(&section.description)
+                ));
             }
         }
 
         if let Some(section) = &self.main_content {
             if !section.instructions.trim().is_empty() {
                 sections.push(format!(
-                    "## Conteúdo Principal\n\n{}",
-                    section.instructions.trim()
+                    "## {}\n\n{}",
+                    SectionKind::MainContent.title_in(language),
+                    trim_blank_lines(&section.instructions)
                 ));
             }
         }
 
         if let Some(section) = &self.auxiliary_content {
-            if !section.data.trim().is_empty() {
-                sections.push(format!("## Conteúdo Auxiliar\n\n{}", section.data.trim()));
+            if !section.is_empty() {
+                sections.push(format!(
+                    "## {}\n\n{}",
+                    SectionKind::AuxiliaryContent.title_in(language),
+                    trim_blank_lines(&render_auxiliary(section))
+                ));
             }
         }
 
         if let Some(section) = &self.limitations {
             if !section.text.trim().is_empty() {
-                sections.push(format!("## Limitações\n\n{}", section.text.trim()));
+                sections.push(format!(
+                    "## {}\n\n{}",
+                    SectionKind::Limitations.title_in(language),
+                    trim_blank_lines(&section.text)
+                ));
             }
         }
 
         if let Some(section) = &self.refactoring {
-            if !section.text.trim().is_empty() {
+            if !section.is_empty() {
                 sections.push(format!(
-                    "## Refatoração (Código)\n\n{}",
-                    section.text.trim()
+                    "## {}\n\n{}",
+                    SectionKind::Refactoring.title_in(language),
+                    trim_blank_lines(&render_refactoring(section))
                 ));
             }
         }
 
         if let Some(section) = &self.guidance {
             if !section.text.trim().is_empty() {
-                sections.push(format!("## Orientações\n\n{}", section.text.trim()));
+                sections.push(format!(
+                    "## {}\n\n{}",
+                    SectionKind::Guidance.title_in(language),
+                    trim_blank_lines(&section.text)
+                ));
             }
         }
 
         if let Some(section) = &self.tests {
-            if !section.text.trim().is_empty() {
-                sections.push(format!("## Testes\n\n{}", section.text.trim()));
+            if !section.is_empty() {
+                sections.push(format!(
+                    "## {}\n\n{}",
+                    SectionKind::Tests.title_in(language),
+                    trim_blank_lines(&section.render_body())
+                ));
             }
         }
 
         if let Some(section) = &self.output_format {
             if !section.text.trim().is_empty() {
-                sections.push(format!("## Formato de Saída\n\n{}", section.text.trim()));
+                sections.push(format!(
+                    "## {}\n\n{}",
+                    SectionKind::OutputFormat.title_in(language),
+                    trim_blank_lines(&section.text)
+                ));
             }
         }
 
         if sections.is_empty() {
-            "Nenhum campo foi preenchido ainda.".to_string()
-        } else {
-            // Add user-friendly message at the end
-            sections.push("---".to_string());
-            sections.push("📋 **Nota:** Ao copiar ou salvar, apenas o texto do prompt será incluído, sem os subtítulos ou marcações acima.".to_string());
-            sections.join("\n\n")
+            return match language {
+                Language::Portuguese => "Nenhum campo foi preenchido ainda.",
+                Language::English => "No field has been filled in yet.",
+                Language::Spanish => "Todavía no se ha rellenado ningún campo.",
+            }
+            .to_string();
         }
+
+        // Add user-friendly message at the end
+        sections.push("---".to_string());
+        sections.push(preview_note(language).to_string());
+        sections.join("\n\n")
+    }
+}
+
+/// Footer note that ends the preview in `language`
+pub fn preview_note(language: Language) -> &'static str {
+    match language {
+        Language::Portuguese => "📋 **Nota:** Ao copiar ou salvar, apenas o texto do prompt será incluído, sem os subtítulos ou marcações acima.",
+        Language::English => "📋 **Note:** Copying or saving includes only the prompt text, without the headings or markers above.",
+        Language::Spanish => "📋 **Nota:** Al copiar o guardar, solo se incluye el texto del prompt, sin los subtítulos ni las marcas de arriba.",
     }
 }
 
@@ -251,6 +415,20 @@ mod tests {
         assert!(data.output_format.is_none());
     }
 
+    #[test]
+    fn test_build_document_starts_with_metadata_header() {
+        let mut data = create_sample_prompt_data();
+        data.metadata.title = "Análise".to_string();
+        data.metadata.model = "gpt-4o".to_string();
+
+        let document = data.build_document();
+
+        assert!(document
+            .starts_with("---\ntitle: Análise\nmodel: gpt-4o\n---\n\n## Few-Shot Examples\n\n"));
+        // Os metadados nunca entram no texto do prompt
+        assert!(!data.build_prompt(false).contains("gpt-4o"));
+    }
+
     #[test]
     fn test_prompt_data_clone() {
         let original = create_sample_prompt_data();
@@ -356,6 +534,20 @@ mod tests {
         assert!(preview.contains("📋 **Nota:**"));
     }
 
+    #[test]
+    fn test_build_preview_prompt_in_english() {
+        let data = create_sample_prompt_data();
+        let preview = data.build_preview_prompt_in(Language::English);
+
+        assert!(preview.contains("## Context\n\nVocê é um especialista em Rust"));
+        assert!(preview.contains("## Main Content"));
+        assert!(preview.contains("📋 **Note:**"));
+        assert_eq!(
+            PromptData::new().build_preview_prompt_in(Language::Spanish),
+            "Todavía no se ha rellenado ningún campo."
+        );
+    }
+
     #[test]
     fn test_build_preview_prompt_skips_empty_sections() {
         let mut data = PromptData::new();
@@ -0,0 +1,509 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::models::metadata::{format_timestamp, unix_seconds};
+use crate::models::section::SectionKind;
+use crate::services::file_service::write_atomically;
+use crate::services::prompt_generator::PromptData;
+use crate::services::prompt_parser::parse_prompt;
+use crate::services::text_encoding::read_text_file;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Name of the index file kept at the root of the library folder
+pub const INDEX_FILE_NAME: &str = ".biblioteca.txt";
+
+/// Extensions of the prompt files picked up by the library and the workspace
+pub const PROMPT_EXTENSIONS: [&str; 2] = ["txt", "md"];
+
+/// One prompt of the library, with its metadata and indexed sections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryEntry {
+    /// Path relative to the library folder, always with `/` separators
+    pub file: String,
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    pub tags: Vec<String>,
+    /// Model the prompt was written for (e.g. "gpt-4o"), empty when unknown
+    pub model: String,
+    pub favorite: bool,
+    /// Creation time, in seconds since the Unix epoch
+    pub created: u64,
+    /// Last modification time, in seconds since the Unix epoch
+    pub updated: u64,
+    /// Non-empty sections parsed from the file
+    pub sections: Vec<(SectionKind, String)>,
+}
+
+impl LibraryEntry {
+    /// Returns true when the entry has the given tag, ignoring case and accents
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = fold_text(tag.trim());
+        self.tags.iter().any(|own| fold_text(own) == tag)
+    }
+
+    /// One-line description shown under the title in the result list
+    pub fn details(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.tags.is_empty() {
+            parts.push(
+                self.tags
+                    .iter()
+                    .map(|tag| format!("#{}", tag))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+        }
+        if !self.model.is_empty() {
+            parts.push(self.model.clone());
+        }
+        parts.push(format_timestamp(self.updated));
+        parts.join(" · ")
+    }
+
+    // Folded text searched by the full-text query
+    fn search_text(&self) -> String {
+        let mut text = [
+            self.title.as_str(),
+            self.description.as_str(),
+            self.author.as_str(),
+            &self.tags.join(" "),
+            self.model.as_str(),
+        ]
+        .join("\n");
+        for (_, body) in &self.sections {
+            text.push('\n');
+            text.push_str(body);
+        }
+        fold_text(&text)
+    }
+}
+
+/// Search criteria for the library.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LibraryQuery {
+    /// Words that must all appear in the title, tags, model or sections
+    pub text: String,
+    /// Tags the prompt must have
+    pub tags: Vec<String>,
+    pub favorites_only: bool,
+}
+
+/// Prompt library backed by a local folder. Prompt files are scanned and parsed
+/// into metadata and sections; the favourite flag, and the details of files
+/// without a metadata header, live in an index file next to them.
+#[derive(Debug, Clone)]
+pub struct PromptLibrary {
+    root: PathBuf,
+    entries: Vec<LibraryEntry>,
+}
+
+impl PromptLibrary {
+    /// Opens (creating when needed) the library stored in `root`
+    pub fn open(root: &Path) -> Result<Self> {
+        fs::create_dir_all(root).with_path(root)?;
+        let mut library = Self {
+            root: root.to_path_buf(),
+            entries: Vec::new(),
+        };
+        library.refresh()?;
+        Ok(library)
+    }
+
+    /// Folder holding the library
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// All indexed prompts
+    pub fn entries(&self) -> &[LibraryEntry] {
+        &self.entries
+    }
+
+    /// Finds an entry by its relative file path
+    pub fn entry(&self, file: &str) -> Option<&LibraryEntry> {
+        self.entries.iter().find(|entry| entry.file == file)
+    }
+
+    /// Absolute path of an entry's file
+    pub fn path_of(&self, file: &str) -> PathBuf {
+        self.root.join(file)
+    }
+
+    /// Rescans the folder, re-parsing every prompt and merging the stored metadata
+    pub fn refresh(&mut self) -> Result<()> {
+        let metadata = read_index(&self.root.join(INDEX_FILE_NAME))?;
+        let mut files = Vec::new();
+        scan_prompt_files(&self.root, &self.root, &mut files)?;
+
+        self.entries = files
+            .into_iter()
+            .filter_map(|(file, path)| {
+                let content = read_text_file(&path, None).ok()?.text;
+                let modified = fs::metadata(&path)
+                    .and_then(|meta| meta.modified())
+                    .map(unix_seconds)
+                    .unwrap_or_default();
+                let stored = metadata.get(&file);
+                let data = parse_prompt(&content);
+                let header = &data.metadata;
+
+                // The document header wins over the index, which covers plain files
+                let pick = |own: &str, stored: Option<&String>| {
+                    Some(own.trim().to_string())
+                        .filter(|value| !value.is_empty())
+                        .or_else(|| stored.filter(|value| !value.is_empty()).cloned())
+                };
+                Some(LibraryEntry {
+                    title: pick(&header.title, stored.map(|meta| &meta.title))
+                        .unwrap_or_else(|| default_title(&file)),
+                    description: header.description.clone(),
+                    author: header.author.clone(),
+                    tags: if header.tags.is_empty() {
+                        stored.map(|meta| meta.tags.clone()).unwrap_or_default()
+                    } else {
+                        header.tags.clone()
+                    },
+                    model: pick(&header.model, stored.map(|meta| &meta.model)).unwrap_or_default(),
+                    favorite: stored.is_some_and(|meta| meta.favorite),
+                    created: header
+                        .created
+                        .or(stored
+                            .map(|meta| meta.created)
+                            .filter(|created| *created > 0))
+                        .unwrap_or(modified),
+                    updated: header.modified.unwrap_or(modified).max(modified),
+                    sections: SectionKind::ALL
+                        .iter()
+                        .filter_map(|kind| data.section_body(*kind).map(|body| (*kind, body)))
+                        .collect(),
+                    file,
+                })
+            })
+            .collect();
+
+        self.sort_entries();
+        Ok(())
+    }
+
+    /// Returns the entries matching the query: favourites first, then the most recent
+    pub fn search(&self, query: &LibraryQuery) -> Vec<&LibraryEntry> {
+        let terms: Vec<String> = fold_text(&query.text)
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+
+        self.entries
+            .iter()
+            .filter(|entry| !query.favorites_only || entry.favorite)
+            .filter(|entry| query.tags.iter().all(|tag| entry.has_tag(tag)))
+            .filter(|entry| {
+                let text = entry.search_text();
+                terms.iter().all(|term| text.contains(term.as_str()))
+            })
+            .collect()
+    }
+
+    /// Every tag used in the library, sorted and without duplicates
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.entries.iter().flat_map(|entry| entry.tags.iter()) {
+            if !tags.iter().any(|known| fold_text(known) == fold_text(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+        tags.sort_by_key(|tag| fold_text(tag));
+        tags
+    }
+
+    /// Saves a prompt in the native format as a new library entry and indexes it.
+    /// The file name is derived from the metadata title; returns the relative file path.
+    pub fn add_prompt(&mut self, data: &PromptData) -> Result<String> {
+        let stem = data
+            .metadata
+            .file_stem()
+            .unwrap_or_else(|| "prompt".to_string());
+        let mut file = format!("{}.txt", stem);
+        let mut counter = 2;
+        while self.root.join(&file).exists() {
+            file = format!("{} ({}).txt", stem, counter);
+            counter += 1;
+        }
+
+        write_atomically(&self.root.join(&file), data.build_document().as_bytes())?;
+        self.refresh()?;
+        self.write_index()?;
+        Ok(file)
+    }
+
+    /// Marks or unmarks an entry as favourite
+    pub fn set_favorite(&mut self, file: &str, favorite: bool) -> Result<()> {
+        self.update_metadata(file, |entry| entry.favorite = favorite)
+    }
+
+    /// Replaces the title, tags and model of an entry in the index.
+    /// A metadata header in the file itself still takes precedence.
+    pub fn set_details(
+        &mut self,
+        file: &str,
+        title: &str,
+        tags: &[String],
+        model: &str,
+    ) -> Result<()> {
+        self.update_metadata(file, |entry| {
+            if !title.trim().is_empty() {
+                entry.title = title.trim().to_string();
+            }
+            entry.tags = tags.to_vec();
+            entry.model = model.trim().to_string();
+        })
+    }
+
+    // Applies a change to one entry and rewrites the index
+    fn update_metadata(
+        &mut self,
+        file: &str,
+        change: impl FnOnce(&mut LibraryEntry),
+    ) -> Result<()> {
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.file == file)
+            .ok_or_else(|| {
+                Error::validation(format!("prompt '{}' não está na biblioteca", file))
+            })?;
+        change(entry);
+        self.sort_entries();
+        self.write_index()
+    }
+
+    fn sort_entries(&mut self) {
+        self.entries.sort_by(|a, b| {
+            b.favorite
+                .cmp(&a.favorite)
+                .then(b.updated.cmp(&a.updated))
+                .then_with(|| fold_text(&a.title).cmp(&fold_text(&b.title)))
+        });
+    }
+
+    // Writes the metadata of every entry to the index file
+    fn write_index(&self) -> Result<()> {
+        let mut content = String::from("# Índice da biblioteca de prompts\n");
+        for entry in &self.entries {
+            content.push_str(&format!(
+                "\n[{}]\ntitle = {}\ntags = {}\nmodel = {}\nfavorite = {}\ncreated = {}\n",
+                entry.file,
+                single_line(&entry.title),
+                single_line(&entry.tags.join(", ")),
+                single_line(&entry.model),
+                entry.favorite,
+                entry.created
+            ));
+        }
+        write_atomically(&self.root.join(INDEX_FILE_NAME), content.as_bytes())
+    }
+}
+
+/// Splits a comma-separated tag list, dropping blanks and a leading `#`
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text.split(',') {
+        let tag = tag.trim().trim_start_matches('#').trim();
+        if !tag.is_empty() && !tags.iter().any(|known| fold_text(known) == fold_text(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+/// Lowercases a text and strips the accents used in Portuguese and Spanish,
+/// so that searches ignore both
+pub fn fold_text(text: &str) -> String {
+    text.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'í' | 'ì' | 'î' | 'ï' => 'i',
+            'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+            'ú' | 'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            'ñ' => 'n',
+            c => c,
+        })
+        .collect()
+}
+
+// Metadata stored in the index for one file
+#[derive(Debug, Default)]
+struct StoredMetadata {
+    title: String,
+    tags: Vec<String>,
+    model: String,
+    favorite: bool,
+    created: u64,
+}
+
+// Reads the index file; a missing index is an empty library
+fn read_index(path: &Path) -> Result<HashMap<String, StoredMetadata>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(e) => return Err(Error::io(e, path)),
+    };
+
+    let mut metadata = HashMap::new();
+    let mut current: Option<(String, StoredMetadata)> = None;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(file) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            if let Some((file, meta)) = current.take() {
+                metadata.insert(file, meta);
+            }
+            current = Some((file.to_string(), StoredMetadata::default()));
+            continue;
+        }
+        let (Some((_, meta)), Some((key, value))) = (current.as_mut(), line.split_once('=')) else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "title" => meta.title = value.to_string(),
+            "tags" => meta.tags = parse_tags(value),
+            "model" => meta.model = value.to_string(),
+            "favorite" => meta.favorite = value == "true",
+            "created" => meta.created = value.parse().unwrap_or_default(),
+            _ => {}
+        }
+    }
+    if let Some((file, meta)) = current {
+        metadata.insert(file, meta);
+    }
+
+    Ok(metadata)
+}
+
+// Collects the prompt files below `dir`, skipping hidden files and folders
+fn scan_prompt_files(root: &Path, dir: &Path, files: &mut Vec<(String, PathBuf)>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_path(dir)? {
+        let path = entry.with_path(dir)?.path();
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            scan_prompt_files(root, &path, files)?;
+            continue;
+        }
+        let is_prompt = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| PROMPT_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+        if is_prompt {
+            if let Ok(relative) = path.strip_prefix(root) {
+                let file = relative
+                    .components()
+                    .map(|part| part.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.push((file, path));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Title shown for files without metadata: the file name without extension
+fn default_title(file: &str) -> String {
+    Path::new(file)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| file.to_string())
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_fold_text_ignores_case_and_accents() {
+        assert_eq!(
+            fold_text("Conteúdo PRINCIPAL ção"),
+            "conteudo principal cao"
+        );
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(
+            parse_tags(" rust, #API ,, api, Revisão "),
+            vec!["rust", "API", "Revisão"]
+        );
+        assert!(parse_tags("  ").is_empty());
+    }
+
+    #[test]
+    fn test_index_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.txt"), "## Contexto\n\nolá").unwrap();
+
+        let mut library = PromptLibrary::open(temp_dir.path()).unwrap();
+        library
+            .set_details("a.txt", "Meu prompt", &parse_tags("x, y"), "gpt-4o")
+            .unwrap();
+        library.set_favorite("a.txt", true).unwrap();
+
+        let reopened = PromptLibrary::open(temp_dir.path()).unwrap();
+        let entry = reopened.entry("a.txt").unwrap();
+        assert_eq!(entry.title, "Meu prompt");
+        assert_eq!(entry.tags, vec!["x", "y"]);
+        assert_eq!(entry.model, "gpt-4o");
+        assert!(entry.favorite);
+        assert_eq!(
+            entry.sections,
+            vec![(SectionKind::Context, "olá".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_metadata_header_wins_over_index() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("b.txt"),
+            "---\ntitle: Do cabeçalho\nauthor: Ana\n---\n\n## Contexto\n\nolá",
+        )
+        .unwrap();
+
+        let mut library = PromptLibrary::open(temp_dir.path()).unwrap();
+        library
+            .set_details("b.txt", "Do índice", &parse_tags("x"), "")
+            .unwrap();
+
+        let reopened = PromptLibrary::open(temp_dir.path()).unwrap();
+        let entry = reopened.entry("b.txt").unwrap();
+        assert_eq!(entry.title, "Do cabeçalho");
+        assert_eq!(entry.author, "Ana");
+        // Tags ausentes no cabeçalho vêm do índice
+        assert_eq!(entry.tags, vec!["x"]);
+    }
+
+    #[test]
+    fn test_unknown_entry_is_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut library = PromptLibrary::open(temp_dir.path()).unwrap();
+        let error = library.set_favorite("nada.txt", true).unwrap_err();
+        assert!(matches!(error, Error::Validation { .. }));
+        assert!(error.to_string().contains("nada.txt"));
+    }
+}
@@ -0,0 +1,378 @@
+use crate::services::section_diff::{split_document, DocumentSection};
+
+/// Conflict markers, in Git's diff3 style
+const OURS_MARKER: &str = "<<<<<<< nossa";
+const BASE_MARKER: &str = "||||||| base";
+const SEPARATOR_MARKER: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>> deles";
+
+/// How one section was merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionMerge {
+    /// Same text on both sides or changed on one side only; None when the
+    /// section was deleted
+    Clean(Option<String>),
+    /// Changed in different ways on both sides; None where the section is absent
+    Conflict {
+        base: Option<String>,
+        ours: Option<String>,
+        theirs: Option<String>,
+    },
+}
+
+/// One section of a three-way merge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedSection {
+    pub heading: String,
+    /// Number of `#` marks of the heading, 0 without heading
+    pub level: usize,
+    /// The section holds the metadata header
+    pub metadata: bool,
+    pub merge: SectionMerge,
+}
+
+impl MergedSection {
+    /// Returns true while the section has an unresolved conflict
+    pub fn is_conflict(&self) -> bool {
+        matches!(self.merge, SectionMerge::Conflict { .. })
+    }
+
+    /// Title shown in the conflict editor
+    pub fn title(&self) -> &str {
+        if self.heading.is_empty() {
+            "Início do documento"
+        } else {
+            &self.heading
+        }
+    }
+
+    // Text of the section in the merged document; None when it was deleted
+    fn render(&self) -> Option<String> {
+        let body = match &self.merge {
+            SectionMerge::Clean(None) => return None,
+            SectionMerge::Clean(Some(body)) => body.clone(),
+            SectionMerge::Conflict { base, ours, theirs } => [
+                OURS_MARKER,
+                ours.as_deref().unwrap_or_default(),
+                BASE_MARKER,
+                base.as_deref().unwrap_or_default(),
+                SEPARATOR_MARKER,
+                theirs.as_deref().unwrap_or_default(),
+                THEIRS_MARKER,
+            ]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("\n"),
+        };
+
+        match (self.metadata, self.level) {
+            (true, _) => Some(format!("---\n{}\n---", body)),
+            (false, 0) => Some(body),
+            (false, level) if body.is_empty() => {
+                Some(format!("{} {}", "#".repeat(level), self.heading))
+            }
+            (false, level) => Some(format!(
+                "{} {}\n\n{}",
+                "#".repeat(level),
+                self.heading,
+                body
+            )),
+        }
+    }
+}
+
+/// Result of a three-way merge, section by section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeResult {
+    pub sections: Vec<MergedSection>,
+}
+
+impl MergeResult {
+    /// Number of sections still in conflict
+    pub fn conflict_count(&self) -> usize {
+        self.sections
+            .iter()
+            .filter(|section| section.is_conflict())
+            .count()
+    }
+
+    /// Returns true when every section merged or was resolved
+    pub fn is_clean(&self) -> bool {
+        self.conflict_count() == 0
+    }
+
+    /// Resolves the conflict of the section at `index` with the text chosen
+    /// for it; an empty text removes the section. Returns false when the
+    /// section has no conflict.
+    pub fn resolve(&mut self, index: usize, text: &str) -> bool {
+        match self.sections.get_mut(index) {
+            Some(section) if section.is_conflict() => {
+                let text = text.trim();
+                section.merge = SectionMerge::Clean((!text.is_empty()).then(|| text.to_string()));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Merged document text. Sections still in conflict are written with
+    /// conflict markers, our version first.
+    pub fn to_document(&self) -> String {
+        self.sections
+            .iter()
+            .filter_map(MergedSection::render)
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Merges two versions of a document edited from a common base. Sections
+/// changed on one side only take that side's text; sections changed on both
+/// sides become conflicts. Metadata fields are merged one by one, so editing
+/// different fields on each side does not conflict.
+pub fn merge_documents(base: &str, ours: &str, theirs: &str) -> MergeResult {
+    let base = split_document(base);
+    let ours = split_document(ours);
+    let theirs = split_document(theirs);
+    let find = |sections: &'_ [DocumentSection], key: &str| -> Option<DocumentSection> {
+        sections
+            .iter()
+            .find(|section| section.key() == key)
+            .cloned()
+    };
+
+    let mut sections = Vec::new();
+    for key in merge_order(&ours, &theirs) {
+        let (base, ours, theirs) = (find(&base, &key), find(&ours, &key), find(&theirs, &key));
+        let Some(reference) = ours.clone().or_else(|| theirs.clone()) else {
+            continue;
+        };
+        let body = |section: &Option<DocumentSection>| section.as_ref().map(|s| s.body.clone());
+        let (base, ours, theirs) = (body(&base), body(&ours), body(&theirs));
+
+        let merge = if reference.is_metadata() {
+            merge_metadata(base, ours, theirs)
+        } else {
+            merge_bodies(base, ours, theirs)
+        };
+        if merge == SectionMerge::Clean(None) {
+            continue;
+        }
+        sections.push(MergedSection {
+            metadata: reference.is_metadata(),
+            heading: reference.heading,
+            level: reference.level,
+            merge,
+        });
+    }
+
+    MergeResult { sections }
+}
+
+// Our section order, with sections only found on their side placed after the
+// section that precedes them there
+fn merge_order(ours: &[DocumentSection], theirs: &[DocumentSection]) -> Vec<String> {
+    let mut order: Vec<String> = ours
+        .iter()
+        .map(|section| section.key().to_string())
+        .collect();
+    for (i, section) in theirs.iter().enumerate() {
+        if order.iter().any(|key| key == section.key()) {
+            continue;
+        }
+        let position = theirs[..i]
+            .iter()
+            .rev()
+            .find_map(|previous| order.iter().position(|key| key == previous.key()))
+            .map_or(0, |position| position + 1);
+        order.insert(position, section.key().to_string());
+    }
+    order
+}
+
+// Three-way choice for one piece of text
+fn merge_bodies(
+    base: Option<String>,
+    ours: Option<String>,
+    theirs: Option<String>,
+) -> SectionMerge {
+    if ours == theirs || theirs == base {
+        SectionMerge::Clean(ours)
+    } else if ours == base {
+        SectionMerge::Clean(theirs)
+    } else {
+        SectionMerge::Conflict { base, ours, theirs }
+    }
+}
+
+// Merges the header field by field; a field changed on both sides makes the
+// whole header a conflict
+fn merge_metadata(
+    base: Option<String>,
+    ours: Option<String>,
+    theirs: Option<String>,
+) -> SectionMerge {
+    let (base_fields, our_fields, their_fields) = (
+        header_fields(base.as_deref()),
+        header_fields(ours.as_deref()),
+        header_fields(theirs.as_deref()),
+    );
+    let get = |fields: &[(String, String)], key: &str| {
+        fields
+            .iter()
+            .find(|(field, _)| field == key)
+            .map(|(_, lines)| lines.clone())
+    };
+
+    let mut keys: Vec<&str> = our_fields.iter().map(|(key, _)| key.as_str()).collect();
+    for (key, _) in &their_fields {
+        if !keys.contains(&key.as_str()) {
+            keys.push(key);
+        }
+    }
+
+    let mut lines = Vec::new();
+    for key in keys {
+        match merge_bodies(
+            get(&base_fields, key),
+            get(&our_fields, key),
+            get(&their_fields, key),
+        ) {
+            SectionMerge::Clean(Some(field)) => lines.push(field),
+            SectionMerge::Clean(None) => {}
+            SectionMerge::Conflict { .. } => return SectionMerge::Conflict { base, ours, theirs },
+        }
+    }
+
+    SectionMerge::Clean((!lines.is_empty()).then(|| lines.join("\n")))
+}
+
+// Groups header lines by key, keeping repeated keys such as `stop` together
+fn header_fields(header: Option<&str>) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+    for line in header.unwrap_or_default().lines() {
+        let key = line
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        match fields.iter_mut().find(|(field, _)| *field == key) {
+            Some((_, lines)) => {
+                lines.push('\n');
+                lines.push_str(line);
+            }
+            None => fields.push((key, line.to_string())),
+        }
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "## Contexto\n\nVocê é um revisor.\n\n## Conteúdo Principal\n\nRevise o código.\n\n## Limitações\n\nSem jargão.";
+
+    #[test]
+    fn test_one_sided_changes_merge_cleanly() {
+        let ours = BASE.replace("Você é um revisor.", "Você é um revisor sênior.");
+        let theirs = BASE.replace("Sem jargão.", "Sem jargão e sem emojis.");
+
+        let result = merge_documents(BASE, &ours, &theirs);
+
+        assert!(result.is_clean());
+        assert_eq!(
+            result.to_document(),
+            "## Contexto\n\nVocê é um revisor sênior.\n\n## Conteúdo Principal\n\nRevise o código.\n\n## Limitações\n\nSem jargão e sem emojis."
+        );
+    }
+
+    #[test]
+    fn test_both_sides_changing_a_section_conflict() {
+        let ours = BASE.replace("Revise o código.", "Revise o código com calma.");
+        let theirs = BASE.replace("Revise o código.", "Revise apenas os testes.");
+
+        let result = merge_documents(BASE, &ours, &theirs);
+
+        assert_eq!(result.conflict_count(), 1);
+        assert_eq!(result.sections[1].title(), "Conteúdo Principal");
+        assert!(result.to_document().contains(
+            "## Conteúdo Principal\n\n<<<<<<< nossa\nRevise o código com calma.\n||||||| base\nRevise o código.\n=======\nRevise apenas os testes.\n>>>>>>> deles"
+        ));
+    }
+
+    #[test]
+    fn test_same_change_on_both_sides_is_not_a_conflict() {
+        let changed = BASE.replace("Sem jargão.", "Seja breve.");
+        assert!(merge_documents(BASE, &changed, &changed).is_clean());
+    }
+
+    #[test]
+    fn test_added_and_deleted_sections() {
+        let ours = format!("{}\n\n## Formato de Saída\n\nMarkdown", BASE);
+        // Do outro lado, uma seção nova no início e Limitações removida
+        let theirs = BASE
+            .replace("## Contexto", "# Objetivo\n\nRevisar PRs.\n\n## Contexto")
+            .replace("\n\n## Limitações\n\nSem jargão.", "");
+
+        let result = merge_documents(BASE, &ours, &theirs);
+
+        assert!(result.is_clean());
+        let titles: Vec<&str> = result.sections.iter().map(|s| s.title()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Objetivo",
+                "Contexto",
+                "Conteúdo Principal",
+                "Formato de Saída"
+            ]
+        );
+        assert!(result
+            .to_document()
+            .starts_with("# Objetivo\n\nRevisar PRs."));
+    }
+
+    #[test]
+    fn test_deleting_a_section_changed_on_the_other_side_conflicts() {
+        let ours = BASE.replace("\n\n## Limitações\n\nSem jargão.", "");
+        let theirs = BASE.replace("Sem jargão.", "Sem gírias.");
+
+        let mut result = merge_documents(BASE, &ours, &theirs);
+
+        assert_eq!(result.conflict_count(), 1);
+        assert!(result
+            .to_document()
+            .ends_with("## Limitações\n\n<<<<<<< nossa\n||||||| base\nSem jargão.\n=======\nSem gírias.\n>>>>>>> deles"));
+
+        // Resolver com texto vazio remove a seção
+        assert!(result.resolve(2, ""));
+        assert!(result.is_clean());
+        assert!(!result.to_document().contains("Limitações"));
+        assert!(!result.resolve(0, "não é conflito"));
+    }
+
+    #[test]
+    fn test_metadata_fields_merge_independently() {
+        let base = "---\ntitle: Revisão\nmodel: gpt-4o\n---\n\n## Contexto\n\nA";
+        let ours = "---\ntitle: Revisão de PR\nmodel: gpt-4o\n---\n\n## Contexto\n\nA";
+        let theirs =
+            "---\ntitle: Revisão\nmodel: claude\nstop: FIM\nstop: ###\n---\n\n## Contexto\n\nA";
+
+        let result = merge_documents(base, ours, theirs);
+
+        assert!(result.is_clean());
+        assert_eq!(
+            result.to_document(),
+            "---\ntitle: Revisão de PR\nmodel: claude\nstop: FIM\nstop: ###\n---\n\n## Contexto\n\nA"
+        );
+
+        let conflicting = "---\ntitle: Outro título\nmodel: gpt-4o\n---\n\n## Contexto\n\nA";
+        let result = merge_documents(base, ours, conflicting);
+        assert!(result.sections[0].metadata);
+        assert!(result.sections[0].is_conflict());
+    }
+}
@@ -0,0 +1,535 @@
+use crate::models::{
+    auxiliary_content::AuxiliaryContent, language::Language, metadata::DocumentMetadata,
+    refactoring::Refactoring, section::SectionKind, tests::Tests,
+};
+use crate::services::heading_aliases::HeadingAliases;
+use crate::services::markdown::{trim_blank_lines, FenceTracker};
+use crate::services::prompt_generator::{preview_note, PromptData};
+use crate::services::section_classifier::{
+    assign_paragraphs, classify_paragraphs, ClassifiedParagraph, SectionClassifier,
+};
+
+/// Returns the section introduced by a `## ` heading line, if it names one.
+/// Headings are recognized in every supported language, with or without the
+/// parenthetical part, as in `## Refatoração`.
+pub fn section_from_heading(line: &str) -> Option<SectionKind> {
+    let heading = line.trim().strip_prefix("## ")?.trim_start();
+    // Older files wrote "Few-shot" or just "Few-Shot"
+    if heading.starts_with("Few-shot") || heading.starts_with("Few-Shot") {
+        return Some(SectionKind::FewShot);
+    }
+    Language::ALL
+        .iter()
+        .flat_map(|language| SectionKind::ALL.map(|kind| (kind.title_in(*language), kind)))
+        .find(|(title, _)| {
+            let prefix = title.split(" (").next().unwrap_or(title);
+            heading.starts_with(prefix)
+        })
+        .map(|(_, kind)| kind)
+}
+
+/// Parses a saved prompt back into its metadata and sections, recognizing
+/// the built-in heading aliases and classifying plain paragraphs with the
+/// bundled classifier.
+pub fn parse_prompt(content: &str) -> PromptData {
+    parse_prompt_with(
+        content,
+        &HeadingAliases::new(),
+        &SectionClassifier::bundled(),
+    )
+}
+
+/// Parses a saved prompt, recognizing section headings through `aliases`.
+/// Native documents and previews keep every section body as written. Other
+/// files with Markdown headings (`#` to `######`) are read section by section
+/// until the footer; without them, `**Bold:**` and `Label:` lines introduce
+/// the sections. The paragraphs of plain files go to the sections proposed
+/// by `classifier`.
+pub fn parse_prompt_with(
+    content: &str,
+    aliases: &HeadingAliases,
+    classifier: &SectionClassifier,
+) -> PromptData {
+    let (metadata, body) = DocumentMetadata::split_header(content);
+    let mut data = PromptData::new();
+    data.metadata = metadata;
+
+    if is_native(body) {
+        parse_native(body, &mut data);
+        return data;
+    }
+
+    match heading_style(body, aliases) {
+        Some(style) => parse_structured(body, style, aliases, &mut data),
+        None => assign_paragraphs(&classify_paragraphs(body, classifier), &mut data),
+    }
+
+    data
+}
+
+/// Whether a prompt has section headings that `aliases` recognizes
+pub fn has_headings(content: &str, aliases: &HeadingAliases) -> bool {
+    let (_, body) = DocumentMetadata::split_header(content);
+    heading_style(body, aliases).is_some()
+}
+
+/// Paragraphs of a prompt without section headings and the sections proposed
+/// for them; None when the prompt has headings
+pub fn unstructured_paragraphs(
+    content: &str,
+    aliases: &HeadingAliases,
+    classifier: &SectionClassifier,
+) -> Option<Vec<ClassifiedParagraph>> {
+    let (_, body) = DocumentMetadata::split_header(content);
+    match heading_style(body, aliases) {
+        Some(_) => None,
+        None => Some(classify_paragraphs(body, classifier)),
+    }
+}
+
+// How the headings of a structured file are written
+#[derive(Clone, Copy)]
+enum HeadingStyle {
+    /// `# Role`, `### Persona`
+    Markdown,
+    /// `**Constraints:**` or `Output:` alone on a line
+    Label,
+}
+
+// Heading style of a file, Markdown first; None for plain files
+fn heading_style(body: &str, aliases: &HeadingAliases) -> Option<HeadingStyle> {
+    [HeadingStyle::Markdown, HeadingStyle::Label]
+        .into_iter()
+        .find(|style| {
+            body.lines()
+                .any(|line| heading(line.trim(), *style, aliases).is_some())
+        })
+}
+
+// Section introduced by a heading line, with the text that follows the
+// heading on the same line, as in `**Constraints:** be brief`
+fn heading<'a>(
+    line: &'a str,
+    style: HeadingStyle,
+    aliases: &HeadingAliases,
+) -> Option<(SectionKind, &'a str)> {
+    match style {
+        HeadingStyle::Markdown => {
+            let text = line.trim_start_matches('#');
+            let level = line.len() - text.len();
+            if !(1..=6).contains(&level) || !text.starts_with(' ') {
+                return None;
+            }
+            aliases
+                .section_for(text.trim().trim_end_matches('#'))
+                .map(|kind| (kind, ""))
+        }
+        HeadingStyle::Label => {
+            for marker in ["**", "__"] {
+                if let Some((label, rest)) = line
+                    .strip_prefix(marker)
+                    .and_then(|inner| inner.split_once(marker))
+                {
+                    let rest = rest.trim_start_matches(':').trim();
+                    return aliases.section_for(label).map(|kind| (kind, rest));
+                }
+            }
+            let label = line.strip_suffix(':')?;
+            if label.chars().count() > 60 || label.starts_with(['-', '*', '>']) {
+                return None;
+            }
+            aliases.section_for(label).map(|kind| (kind, ""))
+        }
+    }
+}
+
+// Section named by a heading exactly as the native document writes it
+fn native_heading(line: &str) -> Option<SectionKind> {
+    let title = line.strip_prefix("## ")?.trim_end();
+    Language::ALL.iter().find_map(|language| {
+        SectionKind::ALL
+            .into_iter()
+            .find(|kind| kind.title_in(*language) == title)
+    })
+}
+
+// Whether a body is a native document or a preview: its first line is a
+// section heading written by `build_document_in`
+fn is_native(body: &str) -> bool {
+    body.lines()
+        .find(|line| !line.trim().is_empty())
+        .is_some_and(|line| native_heading(line).is_some())
+}
+
+// Reads a native document or a preview. Section bodies are kept verbatim,
+// headings inside fenced blocks are ignored and only the preview footer
+// ends the document.
+fn parse_native(body: &str, data: &mut PromptData) {
+    let mut fences = FenceTracker::new();
+    let mut current_section: Option<SectionKind> = None;
+    let mut lines: Vec<&str> = Vec::new();
+
+    for line in body.lines() {
+        if !fences.feed(line) {
+            if let Some(kind) = native_heading(line) {
+                if let Some(section) = current_section {
+                    store_section(section, &lines.join("\n"), data);
+                }
+                current_section = Some(kind);
+                lines.clear();
+                continue;
+            }
+            if Language::ALL
+                .iter()
+                .any(|language| line.trim() == preview_note(*language))
+            {
+                // Drop the separator written before the footer
+                while lines.last().is_some_and(|line| line.trim().is_empty()) {
+                    lines.pop();
+                }
+                if lines.last().is_some_and(|line| line.trim() == "---") {
+                    lines.pop();
+                }
+                break;
+            }
+        }
+        lines.push(line);
+    }
+
+    if let Some(section) = current_section {
+        store_section(section, &lines.join("\n"), data);
+    }
+}
+
+// Reads the structured format, one heading per section. Text before the
+// first heading belongs to the main content.
+fn parse_structured(
+    content: &str,
+    style: HeadingStyle,
+    aliases: &HeadingAliases,
+    data: &mut PromptData,
+) {
+    let mut current_section = SectionKind::MainContent;
+    let mut section_content = String::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if let Some((kind, rest)) = heading(line, style, aliases) {
+            store_section(current_section, &section_content, data);
+            current_section = kind;
+            section_content = rest.to_string();
+        } else if line.starts_with("---") || line.starts_with("*Prompt gerado") {
+            // Separators and footer end the prompt
+            break;
+        } else if !line.is_empty() {
+            if !section_content.is_empty() {
+                section_content.push('\n');
+            }
+            section_content.push_str(line);
+        }
+    }
+
+    store_section(current_section, &section_content, data);
+}
+
+// Stores the collected text of a section, after the text of an earlier
+// heading of the same section, parsing the structured auxiliary content,
+// refactoring and tests sections
+fn store_section(kind: SectionKind, content: &str, data: &mut PromptData) {
+    let trimmed_content = trim_blank_lines(content);
+    if trimmed_content.is_empty() {
+        return;
+    }
+    let content = match data.section_body(kind) {
+        Some(current) => format!("{}\n{}", current, trimmed_content),
+        None => trimmed_content.to_string(),
+    };
+
+    match kind {
+        SectionKind::AuxiliaryContent => {
+            data.auxiliary_content = Some(AuxiliaryContent::parse(&content))
+        }
+        SectionKind::Refactoring => data.refactoring = Some(Refactoring::parse(&content)),
+        SectionKind::Tests => data.tests = Some(Tests::parse(&content)),
+        _ => data.set_section_body(kind, content),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::attachment::{Attachment, AttachmentKind};
+    use crate::models::refactoring::{CodeReference, RefactoringGoal};
+
+    #[test]
+    fn test_section_from_heading() {
+        assert_eq!(
+            section_from_heading("## Few-Shot Examples"),
+            Some(SectionKind::FewShot)
+        );
+        assert_eq!(
+            section_from_heading("  ## Refatoração (Código)"),
+            Some(SectionKind::Refactoring)
+        );
+        assert_eq!(section_from_heading("## Outra coisa"), None);
+        assert_eq!(section_from_heading("Contexto"), None);
+    }
+
+    #[test]
+    fn test_parse_structured_prompt() {
+        let content = format!(
+            "## Contexto\n\nVocê é um revisor.\n\n## Conteúdo Principal\n\nRevise o código.\n\n---\n\nSeja direto.\n\n---\n\n{}\n\n## Limitações\n\nignorado",
+            preview_note(Language::Portuguese)
+        );
+
+        let data = parse_prompt(&content);
+
+        assert_eq!(
+            data.section_body(SectionKind::Context).as_deref(),
+            Some("Você é um revisor.")
+        );
+        assert_eq!(
+            data.section_body(SectionKind::MainContent).as_deref(),
+            Some("Revise o código.\n\n---\n\nSeja direto.")
+        );
+        // Um separador faz parte da seção; tudo depois da nota da pré-visualização é ignorado
+        assert!(data.limitations.is_none());
+    }
+
+    #[test]
+    fn test_parse_preview_round_trip() {
+        let mut original = PromptData::new();
+        original.set_section_body(SectionKind::FewShot, "Exemplo 1".to_string());
+        original.set_section_body(SectionKind::Guidance, "Use Rust.".to_string());
+
+        let data = parse_prompt(&original.build_preview_prompt());
+
+        assert_eq!(
+            data.section_body(SectionKind::FewShot).as_deref(),
+            Some("Exemplo 1")
+        );
+        assert_eq!(
+            data.section_body(SectionKind::Guidance).as_deref(),
+            Some("Use Rust.")
+        );
+    }
+
+    #[test]
+    fn test_parse_document_with_metadata_header() {
+        let mut original = PromptData::new();
+        original.metadata.title = "Revisão".to_string();
+        original.metadata.max_tokens = Some(512);
+        original.set_section_body(SectionKind::Context, "Você é um revisor.".to_string());
+
+        let data = parse_prompt(&original.build_document());
+
+        assert_eq!(data.metadata, original.metadata);
+        assert_eq!(
+            data.section_body(SectionKind::Context).as_deref(),
+            Some("Você é um revisor.")
+        );
+    }
+
+    #[test]
+    fn test_parse_document_keeps_code_references() {
+        let mut refactoring = Refactoring::new();
+        refactoring.text = "Simplifique o parser".to_string();
+        refactoring.goals = vec![RefactoringGoal::Readability];
+        refactoring.files = vec![CodeReference::with_lines("src/parser.rs", 10, 42)];
+        let mut original = PromptData::new();
+        original.refactoring = Some(refactoring);
+
+        // Reabrir o documento restaura as referências, não o código lido
+        let data = parse_prompt(&original.build_document());
+        let parsed = data.refactoring.unwrap();
+        assert_eq!(parsed.text, "Simplifique o parser");
+        assert_eq!(parsed.goals, vec![RefactoringGoal::Readability]);
+        assert_eq!(
+            parsed.files,
+            vec![CodeReference::with_lines("src/parser.rs", 10, 42)]
+        );
+    }
+
+    #[test]
+    fn test_parse_document_keeps_attachments() {
+        let mut directory = Attachment::new("src", AttachmentKind::Directory);
+        directory.exclude = vec!["target/**".to_string()];
+        let mut auxiliary = AuxiliaryContent::new();
+        auxiliary.data = "Código do projeto".to_string();
+        auxiliary.attachments = vec![directory];
+        let mut original = PromptData::new();
+        original.auxiliary_content = Some(auxiliary);
+
+        // Reabrir o documento restaura a lista de anexos, não o conteúdo lido
+        let data = parse_prompt(&original.build_document());
+        let parsed = data.auxiliary_content.unwrap();
+        assert_eq!(parsed.data, "Código do projeto");
+        assert_eq!(
+            parsed.attachments,
+            original.auxiliary_content.unwrap().attachments
+        );
+    }
+
+    #[test]
+    fn test_parse_document_keeps_bodies_verbatim() {
+        let diff = "Revise o diff:\n\n```diff\ndiff --git a/x b/x\n--- a/x\n+++ b/x\n@@ -1 +1 @@\n-antigo\n+novo\n```";
+        let tree = "  src/\n    main.rs\n    services/\n      mod.rs\n\n  Cargo.toml";
+        let snippet = "Exemplo:\n\n```markdown\n## Contexto\n\ntexto de exemplo\n```";
+        let mut original = PromptData::new();
+        original.set_section_body(SectionKind::Context, diff.to_string());
+        original.set_section_body(SectionKind::MainContent, tree.to_string());
+        original.set_section_body(SectionKind::Guidance, snippet.to_string());
+        original.set_section_body(SectionKind::OutputFormat, "Markdown".to_string());
+
+        // Linhas em branco, indentação, `---` e títulos dentro de blocos são preservados
+        for document in [original.build_document(), original.build_preview_prompt()] {
+            let data = parse_prompt(&document);
+            for kind in SectionKind::ALL {
+                assert_eq!(
+                    data.section_body(kind),
+                    original.section_body(kind),
+                    "{:?}",
+                    kind
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_document_with_separator_lines_in_bodies() {
+        let mut original = PromptData::new();
+        original.metadata.title = "Front matter".to_string();
+        original.metadata.description = "---\nrascunho\n---".to_string();
+        original.set_section_body(
+            SectionKind::AuxiliaryContent,
+            "---\ntitle: Guia\ntags: [rust]\n---\n\nConteúdo do guia".to_string(),
+        );
+        original.set_section_body(
+            SectionKind::Limitations,
+            "Seja breve.\n\n---\n\nSem jargão.".to_string(),
+        );
+        original.set_section_body(SectionKind::OutputFormat, "Markdown".to_string());
+
+        // Linhas `---` não encerram o documento nem o cabeçalho
+        let data = parse_prompt(&original.build_document());
+        assert_eq!(data.metadata, original.metadata);
+        for kind in SectionKind::ALL {
+            assert_eq!(
+                data.section_body(kind),
+                original.section_body(kind),
+                "{:?}",
+                kind
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_headings_in_every_language() {
+        let mut original = PromptData::new();
+        for kind in SectionKind::ALL {
+            original.set_section_body(kind, format!("Texto de {}", kind.id()));
+        }
+
+        // Os títulos em inglês e espanhol são reconhecidos como os em português
+        for language in Language::ALL {
+            let data = parse_prompt(&original.build_document_in(language));
+            for kind in SectionKind::ALL {
+                assert_eq!(
+                    data.section_body(kind),
+                    original.section_body(kind),
+                    "{:?} em {:?}",
+                    kind,
+                    language
+                );
+            }
+        }
+        assert_eq!(
+            section_from_heading("## Refactorización"),
+            Some(SectionKind::Refactoring)
+        );
+    }
+
+    #[test]
+    fn test_parse_markdown_prompt_with_heading_aliases() {
+        let content = "# Prompt de revisão\n\nLeia com atenção.\n\n# Role\nYou are a reviewer.\n\n### INSTRUÇÕES\nReview the diff.\n\n## Constraints\nBe brief.\n\n#### Output\nMarkdown list.\n\n## Rules\nNo jargon.";
+
+        let data = parse_prompt(content);
+
+        assert_eq!(
+            data.section_body(SectionKind::Context).as_deref(),
+            Some("You are a reviewer.")
+        );
+        // O texto antes do primeiro título conhecido fica no conteúdo principal
+        assert_eq!(
+            data.section_body(SectionKind::MainContent).as_deref(),
+            Some("# Prompt de revisão\nLeia com atenção.\nReview the diff.")
+        );
+        // Dois títulos da mesma seção são juntados
+        assert_eq!(
+            data.section_body(SectionKind::Limitations).as_deref(),
+            Some("Be brief.\nNo jargon.")
+        );
+        assert_eq!(
+            data.section_body(SectionKind::OutputFormat).as_deref(),
+            Some("Markdown list.")
+        );
+    }
+
+    #[test]
+    fn test_parse_bold_and_label_headings() {
+        let content = "**Persona:** Você é um tradutor.\n\n__Tarefa__\nTraduza o texto.\n\nRestrições:\n- Mantenha o tom.\n\nFormato: texto simples";
+        let mut aliases = HeadingAliases::new();
+        aliases.add("Tarefa", SectionKind::Guidance);
+
+        let data = parse_prompt_with(content, &aliases, &SectionClassifier::bundled());
+
+        assert_eq!(
+            data.section_body(SectionKind::Context).as_deref(),
+            Some("Você é um tradutor.")
+        );
+        assert_eq!(
+            data.section_body(SectionKind::Guidance).as_deref(),
+            Some("Traduza o texto.")
+        );
+        // "Formato: texto simples" não é um título porque continua na linha
+        assert_eq!(
+            data.section_body(SectionKind::Limitations).as_deref(),
+            Some("- Mantenha o tom.\nFormato: texto simples")
+        );
+    }
+
+    #[test]
+    fn test_parse_simple_format_distribution() {
+        let content = "Faça um resumo.\n\nmaterial um\n\nsempre cite fontes\n\nmaterial dois\n\nmaterial três";
+
+        let data = parse_prompt(content);
+
+        assert_eq!(
+            data.section_body(SectionKind::MainContent).as_deref(),
+            Some("Faça um resumo.")
+        );
+        assert_eq!(
+            data.section_body(SectionKind::Guidance).as_deref(),
+            Some("sempre cite fontes")
+        );
+        assert_eq!(
+            data.section_body(SectionKind::AuxiliaryContent).as_deref(),
+            Some("material um\n\nmaterial dois\n\nmaterial três")
+        );
+    }
+
+    #[test]
+    fn test_parse_single_paragraph_and_empty_content() {
+        let data = parse_prompt("  apenas uma instrução  ");
+        assert_eq!(
+            data.section_body(SectionKind::MainContent).as_deref(),
+            Some("apenas uma instrução")
+        );
+
+        let empty = parse_prompt("   \n\n  ");
+        assert!(SectionKind::ALL
+            .iter()
+            .all(|kind| empty.section_body(*kind).is_none()));
+    }
+}
@@ -0,0 +1,422 @@
+use crate::error::{Error, Result};
+use crate::models::{metadata::DocumentMetadata, section::SectionKind};
+use crate::services::chunker::split_semantic;
+use crate::services::file_service::{save_prompt_to_file, save_prompt_to_specific_path};
+use crate::services::prompt_generator::PromptData;
+use std::path::{Path, PathBuf};
+
+/// Sections repeated in every part so that each one can be answered on its own
+const SHARED_SECTIONS: [SectionKind; 3] = [
+    SectionKind::Context,
+    SectionKind::MainContent,
+    SectionKind::Limitations,
+];
+
+/// Options for splitting a prompt into a series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeriesOptions {
+    /// Maximum tokens of auxiliary material carried by each part
+    pub chunk_tokens: usize,
+    /// Whether a final prompt asking to combine the partial answers is added
+    pub combine_prompt: bool,
+}
+
+impl Default for SeriesOptions {
+    fn default() -> Self {
+        Self {
+            chunk_tokens: 8_000,
+            combine_prompt: true,
+        }
+    }
+}
+
+/// Role of a prompt within a series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesPromptKind {
+    /// Carries one chunk of the auxiliary material
+    Part,
+    /// Asks to combine the partial answers
+    Combine,
+}
+
+impl SeriesPromptKind {
+    /// Identifier used in the JSONL export
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SeriesPromptKind::Part => "part",
+            SeriesPromptKind::Combine => "combine",
+        }
+    }
+}
+
+/// One prompt of a series.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesPrompt {
+    pub kind: SeriesPromptKind,
+    pub text: String,
+}
+
+/// Prompts produced by splitting oversized auxiliary content.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PromptSeries {
+    pub prompts: Vec<SeriesPrompt>,
+    /// Metadata of the source document; its title and generation
+    /// parameters are repeated on every JSONL line
+    pub metadata: DocumentMetadata,
+}
+
+impl PromptSeries {
+    /// Number of parts carrying auxiliary material
+    pub fn part_count(&self) -> usize {
+        self.prompts
+            .iter()
+            .filter(|prompt| prompt.kind == SeriesPromptKind::Part)
+            .count()
+    }
+
+    /// Serializes the series as JSON Lines, one object per prompt
+    pub fn to_jsonl(&self) -> String {
+        let total = self.prompts.len();
+        let parameters = self.metadata_fields();
+        self.prompts
+            .iter()
+            .enumerate()
+            .map(|(index, prompt)| {
+                format!(
+                    "{{\"index\":{},\"total\":{},\"kind\":\"{}\",\"prompt\":\"{}\"{}}}\n",
+                    index + 1,
+                    total,
+                    prompt.kind.as_str(),
+                    json_escape(&prompt.text),
+                    parameters
+                )
+            })
+            .collect()
+    }
+
+    // JSON fields for the title and generation parameters that are set
+    fn metadata_fields(&self) -> String {
+        let metadata = &self.metadata;
+        let mut fields = String::new();
+        if !metadata.title.is_empty() {
+            fields.push_str(&format!(",\"title\":\"{}\"", json_escape(&metadata.title)));
+        }
+        if !metadata.model.is_empty() {
+            fields.push_str(&format!(",\"model\":\"{}\"", json_escape(&metadata.model)));
+        }
+        if let Some(temperature) = metadata.temperature {
+            fields.push_str(&format!(",\"temperature\":{}", temperature));
+        }
+        if let Some(max_tokens) = metadata.max_tokens {
+            fields.push_str(&format!(",\"max_tokens\":{}", max_tokens));
+        }
+        if !metadata.stop_sequences.is_empty() {
+            let stop: Vec<String> = metadata
+                .stop_sequences
+                .iter()
+                .map(|stop| format!("\"{}\"", json_escape(stop)))
+                .collect();
+            fields.push_str(&format!(",\"stop\":[{}]", stop.join(",")));
+        }
+        fields
+    }
+
+    /// Writes every prompt to `<stem>_parte_NN.txt` (and `<stem>_combinar.txt`) in a directory
+    pub fn export_numbered(&self, directory: &Path, stem: &str) -> Result<Vec<PathBuf>> {
+        let width = self.part_count().to_string().len().max(2);
+        let mut paths = Vec::new();
+        let mut part = 0;
+
+        for prompt in &self.prompts {
+            let title = match prompt.kind {
+                SeriesPromptKind::Part => {
+                    part += 1;
+                    format!("{}_parte_{:0width$}", stem, part, width = width)
+                }
+                SeriesPromptKind::Combine => format!("{}_combinar", stem),
+            };
+            save_prompt_to_file(&prompt.text, &directory.to_string_lossy(), &title)
+                .map_err(|e| Error::export(e, directory))?;
+            paths.push(directory.join(format!("{}.txt", title)));
+        }
+
+        Ok(paths)
+    }
+
+    /// Writes the whole series to a single JSONL file
+    pub fn export_jsonl(&self, path: &Path) -> Result<()> {
+        save_prompt_to_specific_path(&self.to_jsonl(), &path.to_string_lossy())
+            .map_err(|e| Error::export(e, path))
+    }
+}
+
+/// Splits the auxiliary content into chunks and builds one prompt per chunk.
+/// Every part repeats Context, Main Content and Limitations and is framed as
+/// "parte i de N"; the optional final prompt carries the remaining sections and
+/// asks for the partial answers to be combined. When the auxiliary content fits
+/// in a single chunk the series holds just the regular prompt.
+pub fn build_series(data: &PromptData, options: &SeriesOptions) -> PromptSeries {
+    let auxiliary = data
+        .prompt_body(SectionKind::AuxiliaryContent)
+        .unwrap_or_default();
+    let chunks = split_semantic(&auxiliary, options.chunk_tokens);
+
+    if chunks.len() <= 1 {
+        return PromptSeries {
+            prompts: vec![SeriesPrompt {
+                kind: SeriesPromptKind::Part,
+                text: data.build_prompt(false),
+            }],
+            metadata: data.metadata.clone(),
+        };
+    }
+
+    let total = chunks.len();
+    let mut prompts: Vec<SeriesPrompt> = chunks
+        .iter()
+        .enumerate()
+        .map(|(index, chunk)| {
+            let mut part = PromptData::new();
+            for kind in SHARED_SECTIONS {
+                if let Some(body) = data.section_body(kind) {
+                    part.set_section_body(kind, body);
+                }
+            }
+            part.set_section_body(
+                SectionKind::AuxiliaryContent,
+                frame_part(chunk, index + 1, total, options.combine_prompt),
+            );
+            SeriesPrompt {
+                kind: SeriesPromptKind::Part,
+                text: part.build_prompt(false),
+            }
+        })
+        .collect();
+
+    if options.combine_prompt {
+        let mut combine = data.clone();
+        combine.set_section_body(
+            SectionKind::AuxiliaryContent,
+            format!(
+                "As respostas parciais para as partes 1 a {} do material auxiliar foram fornecidas anteriormente. \
+                 Combine-as em uma única resposta final, eliminando repetições e resolvendo contradições.",
+                total
+            ),
+        );
+        prompts.push(SeriesPrompt {
+            kind: SeriesPromptKind::Combine,
+            text: combine.build_prompt(false),
+        });
+    }
+
+    PromptSeries {
+        prompts,
+        metadata: data.metadata.clone(),
+    }
+}
+
+// Wraps a chunk with the "parte i de N" framing
+fn frame_part(chunk: &str, part: usize, total: usize, combine_prompt: bool) -> String {
+    let closing = if part < total || combine_prompt {
+        "Responda considerando apenas esta parte; as respostas parciais serão combinadas ao final."
+            .to_string()
+    } else {
+        format!(
+            "Esta é a última parte: responda considerando todo o material enviado nas {} partes.",
+            total
+        )
+    };
+    format!(
+        "Parte {} de {} do material auxiliar.\n\n{}\n\n{}",
+        part, total, chunk, closing
+    )
+}
+
+// Escapes a string for use inside a JSON string literal
+fn json_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn sample_data() -> PromptData {
+        let files: Vec<String> = (1..=3)
+            .map(|i| {
+                let lines: Vec<String> = (1..=30)
+                    .map(|n| format!("linha {} do arquivo {}", n, i))
+                    .collect();
+                format!(
+                    "Arquivo: `arquivo_{}.txt`\n```\n{}\n```",
+                    i,
+                    lines.join("\n")
+                )
+            })
+            .collect();
+
+        let mut data = PromptData::new();
+        data.set_section_body(SectionKind::Context, "Você é um revisor.".to_string());
+        data.set_section_body(SectionKind::MainContent, "Resuma os arquivos.".to_string());
+        data.set_section_body(SectionKind::Limitations, "Seja breve.".to_string());
+        data.set_section_body(SectionKind::OutputFormat, "Markdown".to_string());
+        data.set_section_body(SectionKind::AuxiliaryContent, files.join("\n\n"));
+        data
+    }
+
+    #[test]
+    fn test_small_auxiliary_content_is_a_single_prompt() {
+        let mut data = PromptData::new();
+        data.set_section_body(SectionKind::AuxiliaryContent, "pouco material".to_string());
+
+        let series = build_series(&data, &SeriesOptions::default());
+
+        assert_eq!(series.prompts.len(), 1);
+        assert_eq!(series.prompts[0].text, data.build_prompt(false));
+    }
+
+    #[test]
+    fn test_series_parts_share_sections_and_framing() {
+        let options = SeriesOptions {
+            chunk_tokens: 250,
+            combine_prompt: true,
+        };
+        let series = build_series(&sample_data(), &options);
+
+        assert_eq!(series.part_count(), 3);
+        assert_eq!(series.prompts.len(), 4);
+        for (index, prompt) in series.prompts[..3].iter().enumerate() {
+            assert!(prompt.text.contains("Você é um revisor."));
+            assert!(prompt.text.contains("Resuma os arquivos."));
+            assert!(prompt.text.contains("Seja breve."));
+            assert!(!prompt.text.contains("Markdown"));
+            assert!(prompt
+                .text
+                .contains(&format!("Parte {} de 3 do material auxiliar.", index + 1)));
+            assert!(prompt
+                .text
+                .contains(&format!("Arquivo: `arquivo_{}.txt`", index + 1)));
+        }
+
+        let combine = &series.prompts[3];
+        assert_eq!(combine.kind, SeriesPromptKind::Combine);
+        assert!(combine.text.contains("partes 1 a 3"));
+        assert!(combine.text.contains("Markdown"));
+        assert!(!combine.text.contains("linha 1 do arquivo 1"));
+    }
+
+    #[test]
+    fn test_series_without_combine_prompt() {
+        let options = SeriesOptions {
+            chunk_tokens: 250,
+            combine_prompt: false,
+        };
+        let series = build_series(&sample_data(), &options);
+
+        assert_eq!(series.prompts.len(), 3);
+        assert!(series.prompts[2].text.contains("Esta é a última parte"));
+    }
+
+    #[test]
+    fn test_to_jsonl_escapes_text() {
+        let series = PromptSeries {
+            prompts: vec![
+                SeriesPrompt {
+                    kind: SeriesPromptKind::Part,
+                    text: "linha \"um\"\n\tdois \\ três".to_string(),
+                },
+                SeriesPrompt {
+                    kind: SeriesPromptKind::Combine,
+                    text: "combine".to_string(),
+                },
+            ],
+            ..PromptSeries::default()
+        };
+
+        assert_eq!(
+            series.to_jsonl(),
+            "{\"index\":1,\"total\":2,\"kind\":\"part\",\"prompt\":\"linha \\\"um\\\"\\n\\tdois \\\\ três\"}\n\
+             {\"index\":2,\"total\":2,\"kind\":\"combine\",\"prompt\":\"combine\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_to_jsonl_includes_generation_parameters() {
+        let mut data = PromptData::new();
+        data.set_section_body(SectionKind::MainContent, "Resuma.".to_string());
+        data.metadata.title = "Resumo".to_string();
+        data.metadata.model = "gpt-4o".to_string();
+        data.metadata.temperature = Some(0.5);
+        data.metadata.max_tokens = Some(1024);
+        data.metadata.stop_sequences = vec!["###".to_string()];
+
+        let jsonl = build_series(&data, &SeriesOptions::default()).to_jsonl();
+
+        assert!(jsonl.ends_with(
+            ",\"title\":\"Resumo\",\"model\":\"gpt-4o\",\"temperature\":0.5,\"max_tokens\":1024,\"stop\":[\"###\"]}\n"
+        ));
+    }
+
+    #[test]
+    fn test_export_numbered_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let options = SeriesOptions {
+            chunk_tokens: 250,
+            combine_prompt: true,
+        };
+        let series = build_series(&sample_data(), &options);
+
+        let paths = series.export_numbered(temp_dir.path(), "prompt").unwrap();
+
+        let names: Vec<String> = paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "prompt_parte_01.txt",
+                "prompt_parte_02.txt",
+                "prompt_parte_03.txt",
+                "prompt_combinar.txt"
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(&paths[0]).unwrap(),
+            series.prompts[0].text
+        );
+    }
+
+    #[test]
+    fn test_export_jsonl_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("serie.jsonl");
+        let series = build_series(
+            &sample_data(),
+            &SeriesOptions {
+                chunk_tokens: 250,
+                combine_prompt: true,
+            },
+        );
+
+        series.export_jsonl(&path).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 4);
+        assert!(content.lines().all(|line| line.starts_with("{\"index\":")));
+    }
+}
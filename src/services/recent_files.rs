@@ -0,0 +1,229 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::services::app_dirs;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Number of unpinned files remembered; pinned files are always kept
+pub const RECENT_FILES_LIMIT: usize = 15;
+
+/// A file opened or saved recently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentFile {
+    pub path: PathBuf,
+    /// Pinned files stay at the top of the list and are never dropped
+    pub pinned: bool,
+}
+
+impl RecentFile {
+    /// File name shown in the menu
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.display().to_string())
+    }
+}
+
+/// Most-recently-used list of prompt files, kept in a text file with one
+/// path per line; pinned files are marked with a leading `* `.
+#[derive(Debug, Clone)]
+pub struct RecentFiles {
+    file: Option<PathBuf>,
+    /// Pinned files first, then the others from the most recent
+    entries: Vec<RecentFile>,
+}
+
+impl Default for RecentFiles {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecentFiles {
+    /// List kept in `file`, starting with its saved entries; a missing file
+    /// starts an empty list
+    pub fn load(file: &Path) -> Result<Self> {
+        let mut recent = Self {
+            file: Some(file.to_path_buf()),
+            entries: Vec::new(),
+        };
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(recent),
+            Err(e) => return Err(Error::io(e, file)),
+        };
+
+        for line in content.lines() {
+            let (pinned, path) = match line.strip_prefix("* ") {
+                Some(path) => (true, path),
+                None => (false, line),
+            };
+            if !path.is_empty() {
+                recent.entries.push(RecentFile {
+                    path: PathBuf::from(path),
+                    pinned,
+                });
+            }
+        }
+        recent.arrange();
+        Ok(recent)
+    }
+
+    /// Empty list kept only in memory
+    pub fn new() -> Self {
+        Self {
+            file: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// List kept in `recentes.txt` in the user data directory; without a
+    /// data directory the list lives only in memory
+    pub fn in_user_data_dir() -> Result<Self> {
+        match app_dirs::data_dir() {
+            Some(dir) => Self::load(&dir.join("recentes.txt")),
+            None => Ok(Self::new()),
+        }
+    }
+
+    /// Pinned files first, then the others from the most recent
+    pub fn entries(&self) -> &[RecentFile] {
+        &self.entries
+    }
+
+    /// Moves `path` to the top of the unpinned files, adding it when new
+    pub fn add(&mut self, path: &Path) {
+        let pinned = match self.entries.iter().position(|entry| entry.path == path) {
+            Some(index) => self.entries.remove(index).pinned,
+            None => false,
+        };
+        let first_unpinned = self.entries.iter().filter(|entry| entry.pinned).count();
+        let index = if pinned { 0 } else { first_unpinned };
+        self.entries.insert(
+            index,
+            RecentFile {
+                path: path.to_path_buf(),
+                pinned,
+            },
+        );
+        self.arrange();
+    }
+
+    /// Pins or unpins a listed file. Returns false when it is not listed.
+    pub fn set_pinned(&mut self, path: &Path, pinned: bool) -> bool {
+        let Some(entry) = self.entries.iter_mut().find(|entry| entry.path == path) else {
+            return false;
+        };
+        entry.pinned = pinned;
+        self.arrange();
+        true
+    }
+
+    /// Removes a file from the list, as when it no longer exists
+    pub fn remove(&mut self, path: &Path) {
+        self.entries.retain(|entry| entry.path != path);
+    }
+
+    /// Forgets the unpinned files
+    pub fn clear_unpinned(&mut self) {
+        self.entries.retain(|entry| entry.pinned);
+    }
+
+    /// Writes the list back to its file
+    pub fn save(&self) -> Result<()> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir).with_path(dir)?;
+        }
+        let content: String = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let marker = if entry.pinned { "* " } else { "" };
+                format!("{}{}\n", marker, entry.path.display())
+            })
+            .collect();
+        fs::write(file, content).with_path(file)
+    }
+
+    // Puts pinned files first, keeping the order within each group, and drops
+    // the oldest unpinned files past the limit
+    fn arrange(&mut self) {
+        self.entries.sort_by_key(|entry| !entry.pinned);
+        let mut unpinned = 0;
+        self.entries.retain(|entry| {
+            if entry.pinned {
+                return true;
+            }
+            unpinned += 1;
+            unpinned <= RECENT_FILES_LIMIT
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn paths(recent: &RecentFiles) -> Vec<&str> {
+        recent
+            .entries()
+            .iter()
+            .map(|entry| entry.path.to_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_most_recent_first_and_pinned_on_top() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut recent = RecentFiles::load(&temp_dir.path().join("recentes.txt")).unwrap();
+        recent.add(Path::new("/p/a.txt"));
+        recent.add(Path::new("/p/b.txt"));
+        recent.add(Path::new("/p/c.txt"));
+        assert_eq!(paths(&recent), vec!["/p/c.txt", "/p/b.txt", "/p/a.txt"]);
+
+        // Arquivos fixados ficam no topo mesmo quando abertos há mais tempo
+        assert!(recent.set_pinned(Path::new("/p/a.txt"), true));
+        recent.add(Path::new("/p/b.txt"));
+        assert_eq!(paths(&recent), vec!["/p/a.txt", "/p/b.txt", "/p/c.txt"]);
+        assert!(!recent.set_pinned(Path::new("/p/x.txt"), true));
+
+        recent.clear_unpinned();
+        assert_eq!(paths(&recent), vec!["/p/a.txt"]);
+    }
+
+    #[test]
+    fn test_unpinned_files_are_limited() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut recent = RecentFiles::load(&temp_dir.path().join("recentes.txt")).unwrap();
+        recent.add(Path::new("/fixado.txt"));
+        recent.set_pinned(Path::new("/fixado.txt"), true);
+        for index in 0..RECENT_FILES_LIMIT + 3 {
+            recent.add(&PathBuf::from(format!("/p/{}.txt", index)));
+        }
+
+        assert_eq!(recent.entries().len(), RECENT_FILES_LIMIT + 1);
+        assert_eq!(recent.entries()[0].path, PathBuf::from("/fixado.txt"));
+        assert!(!paths(&recent).contains(&"/p/0.txt"));
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("dados").join("recentes.txt");
+        let mut recent = RecentFiles::load(&file).unwrap();
+        recent.add(Path::new("/p/revisão.txt"));
+        recent.add(Path::new("/p/b.txt"));
+        recent.set_pinned(Path::new("/p/revisão.txt"), true);
+        recent.save().unwrap();
+
+        let loaded = RecentFiles::load(&file).unwrap();
+        assert_eq!(loaded.entries(), recent.entries());
+        assert!(loaded.entries()[0].pinned);
+        assert_eq!(loaded.entries()[0].name(), "revisão.txt");
+    }
+}
@@ -0,0 +1,253 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::services::app_dirs;
+use crate::services::file_service::write_atomically;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Seconds between two autosaves of unsaved work
+pub const AUTOSAVE_INTERVAL_SECONDS: u64 = 30;
+
+/// Name of the recovery file inside the recovery folder
+const RECOVERY_FILE_NAME: &str = "rascunho.txt";
+
+/// Unsaved editor state written for crash recovery.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Editor contents, in the document format
+    pub document: String,
+    /// File the editor contents came from, if they were ever saved or opened
+    pub source: Option<PathBuf>,
+    /// Time of the autosave, in seconds since the Unix epoch
+    pub saved_at: u64,
+}
+
+/// Folder holding the last autosave of unsaved work.
+#[derive(Debug, Clone)]
+pub struct RecoveryStore {
+    dir: PathBuf,
+}
+
+impl RecoveryStore {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// Store in the `recuperacao` folder of the user data directory
+    pub fn in_user_data_dir() -> Option<Self> {
+        app_dirs::data_dir().map(|dir| Self::new(&dir.join("recuperacao")))
+    }
+
+    /// Folder holding the recovery file
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Writes the snapshot, replacing the previous one. The file is written
+    /// under a temporary name first, so a crash while saving keeps the last
+    /// complete snapshot.
+    pub fn save(&self, snapshot: &Snapshot) -> Result<()> {
+        fs::create_dir_all(&self.dir).with_path(&self.dir)?;
+        let source = snapshot
+            .source
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        let content = format!(
+            "salvo: {}\norigem: {}\n\n{}",
+            snapshot.saved_at, source, snapshot.document
+        );
+
+        write_atomically(&self.dir.join(RECOVERY_FILE_NAME), content.as_bytes())
+    }
+
+    /// Reads the last snapshot; None when there is no unsaved work to recover
+    pub fn load(&self) -> Result<Option<Snapshot>> {
+        let file = self.dir.join(RECOVERY_FILE_NAME);
+        let content = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(Error::io(e, &file)),
+        };
+
+        let Some((header, document)) = content.split_once("\n\n") else {
+            return Ok(None);
+        };
+        let mut snapshot = Snapshot {
+            document: document.to_string(),
+            source: None,
+            saved_at: 0,
+        };
+        for line in header.lines() {
+            match line.split_once(": ") {
+                Some(("salvo", value)) => snapshot.saved_at = value.parse().unwrap_or(0),
+                Some(("origem", value)) if !value.is_empty() => {
+                    snapshot.source = Some(PathBuf::from(value))
+                }
+                _ => {}
+            }
+        }
+        Ok(Some(snapshot))
+    }
+
+    /// Removes the snapshot once the work was saved or discarded
+    pub fn clear(&self) -> Result<()> {
+        let file = self.dir.join(RECOVERY_FILE_NAME);
+        match fs::remove_file(&file) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(Error::io(e, &file)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Tracks whether the editor has unsaved changes and writes them to the
+/// recovery store when they change.
+#[derive(Debug, Clone)]
+pub struct Autosave {
+    store: Option<RecoveryStore>,
+    /// Editor contents at the last save, open or clear
+    clean: String,
+    /// Editor contents of the last autosave
+    autosaved: Option<String>,
+}
+
+impl Autosave {
+    /// Starts with `clean` as the saved contents; without a store, changes are
+    /// tracked but never written
+    pub fn new(store: Option<RecoveryStore>, clean: String) -> Self {
+        Self {
+            store,
+            clean,
+            autosaved: None,
+        }
+    }
+
+    /// Store receiving the autosaves
+    pub fn store(&self) -> Option<&RecoveryStore> {
+        self.store.as_ref()
+    }
+
+    /// Returns true when `current` differs from the last saved contents
+    pub fn is_dirty(&self, current: &str) -> bool {
+        self.clean != current
+    }
+
+    /// Records `current` as saved and drops the recovery snapshot
+    pub fn mark_clean(&mut self, current: String) -> Result<()> {
+        self.clean = current;
+        self.autosaved = None;
+        match &self.store {
+            Some(store) => store.clear(),
+            None => Ok(()),
+        }
+    }
+
+    /// Contents at the last save, open or clear
+    pub fn clean(&self) -> &str {
+        &self.clean
+    }
+
+    /// Tracks another document, saved as `clean`, keeping the recovery
+    /// snapshot; used when the editor switches documents
+    pub fn switch_to(&mut self, clean: String) {
+        self.clean = clean;
+        self.autosaved = None;
+    }
+
+    /// Writes `current` to the recovery store when it has unsaved changes that
+    /// were not written yet. Returns true when a snapshot was written.
+    pub fn autosave(&mut self, current: &str, source: Option<&Path>, now: u64) -> Result<bool> {
+        let Some(store) = &self.store else {
+            return Ok(false);
+        };
+        if !self.is_dirty(current) || self.autosaved.as_deref() == Some(current) {
+            return Ok(false);
+        }
+
+        store.save(&Snapshot {
+            document: current.to_string(),
+            source: source.map(Path::to_path_buf),
+            saved_at: now,
+        })?;
+        self.autosaved = Some(current.to_string());
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_load_and_clear_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = RecoveryStore::new(&temp_dir.path().join("recuperacao"));
+        assert_eq!(store.load().unwrap(), None);
+
+        let snapshot = Snapshot {
+            document: "## Contexto\n\nVocê é um revisor.\n\n## Conteúdo Principal\n\nRevise."
+                .to_string(),
+            source: Some(PathBuf::from("/prompts/revisão.txt")),
+            saved_at: 1_700_000_000,
+        };
+        store.save(&snapshot).unwrap();
+        assert_eq!(store.load().unwrap(), Some(snapshot));
+
+        store.clear().unwrap();
+        assert_eq!(store.load().unwrap(), None);
+        // Limpar sem rascunho não é erro
+        store.clear().unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_without_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = RecoveryStore::new(temp_dir.path());
+
+        let snapshot = Snapshot {
+            document: "texto solto".to_string(),
+            source: None,
+            saved_at: 5,
+        };
+        store.save(&snapshot).unwrap();
+
+        assert_eq!(store.load().unwrap(), Some(snapshot));
+        assert!(!temp_dir.path().join("rascunho.txt.tmp").exists());
+    }
+
+    #[test]
+    fn test_autosave_only_writes_new_unsaved_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = RecoveryStore::new(temp_dir.path());
+        let mut autosave = Autosave::new(Some(store.clone()), "salvo".to_string());
+
+        assert!(!autosave.is_dirty("salvo"));
+        assert!(!autosave.autosave("salvo", None, 1).unwrap());
+
+        assert!(autosave.is_dirty("editado"));
+        assert!(autosave.autosave("editado", None, 2).unwrap());
+        // O mesmo conteúdo não é gravado duas vezes
+        assert!(!autosave.autosave("editado", None, 3).unwrap());
+        assert!(autosave
+            .autosave("editado de novo", Some(Path::new("/p/a.txt")), 4)
+            .unwrap());
+        let snapshot = store.load().unwrap().unwrap();
+        assert_eq!(snapshot.document, "editado de novo");
+        assert_eq!(snapshot.saved_at, 4);
+
+        // Salvar o documento descarta o rascunho
+        autosave.mark_clean("editado de novo".to_string()).unwrap();
+        assert!(!autosave.is_dirty("editado de novo"));
+        assert_eq!(store.load().unwrap(), None);
+    }
+
+    #[test]
+    fn test_autosave_without_store_only_tracks_changes() {
+        let mut autosave = Autosave::new(None, String::new());
+        assert!(autosave.is_dirty("texto"));
+        assert!(!autosave.autosave("texto", None, 1).unwrap());
+    }
+}
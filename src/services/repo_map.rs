@@ -0,0 +1,495 @@
+use crate::error::{Error, Result};
+use crate::models::attachment::{Attachment, AttachmentKind, AttachmentLimits};
+use crate::services::attachment_reader::{collect_files, read_text_file, SkipReason};
+use crate::services::source_reader::detect_language;
+use crate::services::token_counter::estimate_tokens;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Files larger than this are not read; their token count is estimated from the size
+const MAX_SCANNED_BYTES: u64 = 1024 * 1024;
+
+/// Options for generating a repository map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoMapOptions {
+    /// Maximum number of tokens of the generated map
+    pub token_budget: usize,
+    /// Whether top-level symbols of Rust, Python and TypeScript files are listed
+    pub include_symbols: bool,
+    /// Whether `.gitignore` files found while walking are honoured
+    pub respect_gitignore: bool,
+    /// Glob patterns of files left out of the map
+    pub exclude: Vec<String>,
+}
+
+impl Default for RepoMapOptions {
+    fn default() -> Self {
+        Self {
+            token_budget: 2_000,
+            include_symbols: true,
+            respect_gitignore: true,
+            exclude: Vec::new(),
+        }
+    }
+}
+
+// A line of the map: a directory or a file with its (optional) symbol line
+struct MapLine {
+    text: String,
+    symbols: Option<String>,
+}
+
+/// Walks `root` and renders a compact tree with the size and token count of every file.
+/// Symbols are added file by file while they fit in the token budget; if even the bare
+/// tree does not fit, it is cut and the number of omitted entries is reported.
+pub fn build_repo_map(root: &Path, options: &RepoMapOptions) -> Result<String> {
+    if !root.is_dir() {
+        return Err(Error::io(
+            io::Error::new(io::ErrorKind::NotFound, "diretório não encontrado"),
+            root,
+        ));
+    }
+
+    let mut attachment = Attachment::new(root, AttachmentKind::Directory);
+    attachment.exclude = options.exclude.clone();
+    attachment.respect_gitignore = options.respect_gitignore;
+
+    let limits = AttachmentLimits {
+        max_file_bytes: MAX_SCANNED_BYTES,
+        ..AttachmentLimits::default()
+    };
+
+    let mut lines: Vec<MapLine> = Vec::new();
+    let mut open_dirs: Vec<String> = Vec::new();
+    let mut file_count = 0;
+    let mut total_tokens = 0;
+
+    for (path, label) in collect_files(&attachment) {
+        let mut components: Vec<&str> = label.split('/').collect();
+        let file_name = components.pop().unwrap_or_default();
+
+        // Open the directories this file lives in that were not listed yet
+        let shared = open_dirs
+            .iter()
+            .zip(&components)
+            .take_while(|(open, current)| open == current)
+            .count();
+        open_dirs.truncate(shared);
+        for dir in &components[shared..] {
+            lines.push(MapLine {
+                text: format!("{}{}/", "  ".repeat(open_dirs.len()), dir),
+                symbols: None,
+            });
+            open_dirs.push(dir.to_string());
+        }
+
+        let indent = "  ".repeat(open_dirs.len());
+        let size = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+        let (details, symbols) = match read_text_file(&path, &limits) {
+            Ok(content) => {
+                let tokens = estimate_tokens(&content);
+                total_tokens += tokens;
+                let symbols = if options.include_symbols {
+                    extract_symbols(detect_language(&path), &content)
+                } else {
+                    Vec::new()
+                };
+                (
+                    format!("{}, ~{} tokens", format_size(size), tokens),
+                    symbols,
+                )
+            }
+            Err(SkipReason::TooLarge(bytes)) => {
+                let tokens = (bytes as usize).div_ceil(4);
+                total_tokens += tokens;
+                (
+                    format!("{}, ~{} tokens", format_size(size), tokens),
+                    Vec::new(),
+                )
+            }
+            Err(_) => (format!("{}, binário", format_size(size)), Vec::new()),
+        };
+
+        file_count += 1;
+        lines.push(MapLine {
+            text: format!("{}{} ({})", indent, file_name, details),
+            symbols: (!symbols.is_empty()).then(|| format!("{}  {}", indent, symbols.join(", "))),
+        });
+    }
+
+    let header = format!(
+        "Mapa do repositório: {} arquivos, ~{} tokens",
+        file_count, total_tokens
+    );
+    Ok(fit_to_budget(header, lines, options.token_budget))
+}
+
+// Chooses which lines of the map are emitted so that the result fits the token budget
+fn fit_to_budget(header: String, lines: Vec<MapLine>, budget: usize) -> String {
+    // Line lengths in characters, counting the newline that joins them
+    let cost = |text: &str| text.chars().count() + 1;
+    let budget_chars = budget * 4;
+
+    let tree_chars: usize =
+        cost(&header) + lines.iter().map(|line| cost(&line.text)).sum::<usize>();
+
+    if tree_chars <= budget_chars {
+        let mut used = tree_chars;
+        let mut output = vec![header];
+        for line in lines {
+            output.push(line.text);
+            if let Some(symbols) = line.symbols {
+                if used + cost(&symbols) <= budget_chars {
+                    used += cost(&symbols);
+                    output.push(symbols);
+                }
+            }
+        }
+        return output.join("\n");
+    }
+
+    // The bare tree does not fit: keep the first lines and report what was cut
+    let total = lines.len();
+    let note =
+        |omitted: usize| format!("… ({} entradas omitidas pelo orçamento de tokens)", omitted);
+    let mut used = cost(&header) + cost(&note(total));
+    let mut output = vec![header];
+    for line in lines {
+        if used + cost(&line.text) > budget_chars {
+            break;
+        }
+        used += cost(&line.text);
+        output.push(line.text);
+    }
+    let omitted = total - (output.len() - 1);
+    output.push(note(omitted));
+    output.join("\n")
+}
+
+/// Formats a file size in bytes using B, KB or MB
+pub fn format_size(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    let value = bytes as f64;
+    if value < KB {
+        format!("{} B", bytes)
+    } else if value < KB * KB {
+        format!("{:.1} KB", value / KB)
+    } else {
+        format!("{:.1} MB", value / (KB * KB))
+    }
+}
+
+/// Lists the top-level functions, types and classes declared in a source file.
+/// Supports Rust, Python and TypeScript/JavaScript; other languages yield no symbols.
+/// Only unindented declarations are considered, so nested items are left out.
+pub fn extract_symbols(language: &str, content: &str) -> Vec<String> {
+    let extract: fn(&str) -> Option<String> = match language {
+        "rust" => rust_symbol,
+        "python" => python_symbol,
+        "typescript" | "tsx" | "javascript" | "jsx" => typescript_symbol,
+        _ => return Vec::new(),
+    };
+
+    content
+        .lines()
+        .filter(|line| !line.starts_with(char::is_whitespace))
+        .filter_map(extract)
+        .collect()
+}
+
+// Strips any of the given prefixes from the start of a declaration, repeatedly
+fn strip_modifiers<'a>(mut line: &'a str, modifiers: &[&str]) -> &'a str {
+    while let Some(rest) = modifiers
+        .iter()
+        .find_map(|modifier| line.strip_prefix(modifier))
+    {
+        line = rest.trim_start();
+    }
+    line
+}
+
+// Reads the identifier at the start of a string
+fn identifier(text: &str) -> Option<&str> {
+    let end = text
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .unwrap_or(text.len());
+    (end > 0).then(|| &text[..end])
+}
+
+/// Modifiers that may precede a Rust item declaration
+const RUST_MODIFIERS: [&str; 6] = [
+    "pub(crate) ",
+    "pub(super) ",
+    "pub ",
+    "default ",
+    "async ",
+    "unsafe ",
+];
+
+fn rust_symbol(line: &str) -> Option<String> {
+    let mut line = strip_modifiers(line, &RUST_MODIFIERS);
+    // `const` is a modifier only in `const fn`; otherwise it declares a constant
+    if let Some(rest) = line.strip_prefix("const ") {
+        let rest = strip_modifiers(rest.trim_start(), &RUST_MODIFIERS);
+        if rest.starts_with("fn ") {
+            line = rest;
+        }
+    }
+    if let Some(rest) = line.strip_prefix("extern ") {
+        line = rest
+            .trim_start()
+            .strip_prefix('"')
+            .and_then(|abi| abi.split_once('"'))
+            .map(|(_, rest)| rest.trim_start())
+            .unwrap_or(rest);
+    }
+
+    if let Some(rest) = line.strip_prefix("impl") {
+        if rest.starts_with([' ', '<']) {
+            let signature = rest
+                .split(['{', ';'])
+                .next()
+                .unwrap_or_default()
+                .split(" where")
+                .next()
+                .unwrap_or_default()
+                .trim_end();
+            return Some(format!("impl{}", signature));
+        }
+    }
+    if let Some(rest) = line.strip_prefix("macro_rules! ") {
+        return identifier(rest).map(|name| format!("macro {}!", name));
+    }
+
+    ["fn", "struct", "enum", "trait", "type", "mod", "union"]
+        .iter()
+        .find_map(|keyword| {
+            let rest = line.strip_prefix(keyword)?.strip_prefix(' ')?;
+            identifier(rest.trim_start()).map(|name| format!("{} {}", keyword, name))
+        })
+}
+
+fn python_symbol(line: &str) -> Option<String> {
+    let line = strip_modifiers(line, &["async "]);
+    ["def", "class"].iter().find_map(|keyword| {
+        let rest = line.strip_prefix(keyword)?.strip_prefix(' ')?;
+        identifier(rest.trim_start()).map(|name| format!("{} {}", keyword, name))
+    })
+}
+
+fn typescript_symbol(line: &str) -> Option<String> {
+    let line = strip_modifiers(
+        line,
+        &["export ", "default ", "declare ", "abstract ", "async "],
+    );
+
+    if let Some(rest) = line.strip_prefix("function") {
+        let rest = rest.trim_start_matches('*').trim_start();
+        return identifier(rest).map(|name| format!("function {}", name));
+    }
+    for keyword in ["class", "interface", "type", "enum"] {
+        if let Some(rest) = line
+            .strip_prefix(keyword)
+            .and_then(|rest| rest.strip_prefix(' '))
+        {
+            return identifier(rest.trim_start()).map(|name| format!("{} {}", keyword, name));
+        }
+    }
+
+    // Arrow functions and function expressions bound to a top-level constant
+    let rest = line
+        .strip_prefix("const ")
+        .or_else(|| line.strip_prefix("let "))?;
+    let name = identifier(rest)?;
+    let value = rest[name.len()..].split_once('=')?.1.trim_start();
+    let is_function = value.starts_with("function")
+        || value.starts_with("async")
+        || (value.starts_with('(') && value.contains("=>"));
+    is_function.then(|| format!("function {}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, relative: &str, content: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_rust_symbols() {
+        let code = "use std::fmt;\n\npub struct Parser {\n    pos: usize,\n}\n\nimpl<'a> fmt::Display for Parser where Self: Sized {\n    fn fmt(&self) {}\n}\n\npub(crate) async fn run() {}\nconst fn limite() -> usize { 1 }\nenum Modo { A }\nmacro_rules! regra { () => {} }\n";
+        assert_eq!(
+            extract_symbols("rust", code),
+            vec![
+                "struct Parser",
+                "impl<'a> fmt::Display for Parser",
+                "fn run",
+                "fn limite",
+                "enum Modo",
+                "macro regra!",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_python_symbols() {
+        let code = "import os\n\nclass Leitor:\n    def ler(self):\n        pass\n\nasync def baixar(url):\n    pass\n";
+        assert_eq!(
+            extract_symbols("python", code),
+            vec!["class Leitor", "def baixar"]
+        );
+    }
+
+    #[test]
+    fn test_typescript_symbols() {
+        let code = "import x from 'y';\nexport default class App {}\nexport interface Props {}\nexport type Id = string;\nexport async function carregar() {}\nconst soma = (a: number, b: number) => a + b;\nconst LIMITE = 10;\n";
+        assert_eq!(
+            extract_symbols("typescript", code),
+            vec![
+                "class App",
+                "interface Props",
+                "type Id",
+                "function carregar",
+                "function soma",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_language_has_no_symbols() {
+        assert!(extract_symbols("go", "func main() {}").is_empty());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(2048), "2.0 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+    }
+
+    #[test]
+    fn test_build_repo_map_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("projeto");
+        write(&project, "Cargo.toml", "[package]\n");
+        write(&project, "src/main.rs", "fn main() {}\n");
+        write(&project, "src/util/texto.rs", "pub struct Texto;\n");
+
+        let map = build_repo_map(&project, &RepoMapOptions::default()).unwrap();
+
+        assert_eq!(
+            map,
+            "Mapa do repositório: 3 arquivos, ~12 tokens\n\
+             projeto/\n\
+             \x20 Cargo.toml (10 B, ~3 tokens)\n\
+             \x20 src/\n\
+             \x20   main.rs (13 B, ~4 tokens)\n\
+             \x20     fn main\n\
+             \x20   util/\n\
+             \x20     texto.rs (18 B, ~5 tokens)\n\
+             \x20       struct Texto"
+        );
+    }
+
+    #[test]
+    fn test_build_repo_map_without_symbols() {
+        let temp_dir = TempDir::new().unwrap();
+        write(temp_dir.path(), "main.rs", "fn main() {}\n");
+
+        let options = RepoMapOptions {
+            include_symbols: false,
+            ..RepoMapOptions::default()
+        };
+        let map = build_repo_map(temp_dir.path(), &options).unwrap();
+
+        assert!(map.contains("main.rs (13 B, ~4 tokens)"));
+        assert!(!map.contains("fn main"));
+    }
+
+    #[test]
+    fn test_build_repo_map_marks_binary_files() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("logo.png"), [0u8, 1, 2, 3]).unwrap();
+
+        let map = build_repo_map(temp_dir.path(), &RepoMapOptions::default()).unwrap();
+
+        assert!(map.contains("logo.png (4 B, binário)"));
+    }
+
+    #[test]
+    fn test_build_repo_map_drops_symbols_over_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        let functions: String = (0..50)
+            .map(|i| format!("fn funcao_{}() {{}}\n", i))
+            .collect();
+        write(temp_dir.path(), "lib.rs", &functions);
+
+        let options = RepoMapOptions {
+            token_budget: 30,
+            ..RepoMapOptions::default()
+        };
+        let map = build_repo_map(temp_dir.path(), &options).unwrap();
+
+        assert!(map.contains("lib.rs ("));
+        assert!(!map.contains("funcao_"));
+        assert!(estimate_tokens(&map) <= 30);
+    }
+
+    #[test]
+    fn test_build_repo_map_truncates_tree_over_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..40 {
+            write(temp_dir.path(), &format!("arquivo_{:02}.txt", i), "texto");
+        }
+
+        let options = RepoMapOptions {
+            token_budget: 60,
+            ..RepoMapOptions::default()
+        };
+        let map = build_repo_map(temp_dir.path(), &options).unwrap();
+
+        assert!(estimate_tokens(&map) <= 60);
+        assert!(map.contains("arquivo_00.txt"));
+        assert!(!map.contains("arquivo_39.txt"));
+        assert!(map.ends_with("entradas omitidas pelo orçamento de tokens)"));
+    }
+
+    #[test]
+    fn test_build_repo_map_respects_exclude_and_gitignore() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("projeto");
+        write(&project, ".gitignore", "target/\n");
+        write(&project, "target/debug/app", "binário");
+        write(&project, "src/main.rs", "fn main() {}\n");
+        write(&project, "Cargo.lock", "# lock\n");
+
+        let options = RepoMapOptions {
+            exclude: vec!["*.lock".to_string()],
+            ..RepoMapOptions::default()
+        };
+        let map = build_repo_map(&project, &options).unwrap();
+
+        assert!(map.contains("main.rs"));
+        assert!(!map.contains("target"));
+        assert!(!map.contains("Cargo.lock"));
+    }
+
+    #[test]
+    fn test_build_repo_map_missing_directory() {
+        let result = build_repo_map(
+            Path::new("/caminho/que/nao/existe"),
+            &RepoMapOptions::default(),
+        );
+        let error = result.unwrap_err();
+        assert!(error.is_not_found());
+        assert_eq!(
+            error.to_string(),
+            "/caminho/que/nao/existe: diretório não encontrado"
+        );
+    }
+}
@@ -0,0 +1,447 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::models::{section::SectionKind, tests::Tests};
+use crate::services::app_dirs;
+use crate::services::file_service::write_atomically;
+use crate::services::heading_aliases::HeadingAliases;
+use crate::services::prompt_generator::PromptData;
+use crate::services::prompt_library::{fold_text, PromptLibrary};
+use crate::services::prompt_parser::{has_headings, parse_prompt_with};
+use crate::services::text_encoding::read_text_file;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Name of the trained model file inside the user data directory
+pub const MODEL_FILE_NAME: &str = "classificador.txt";
+
+/// Paragraphs placed with less confidence than this are offered for manual
+/// assignment
+pub const LOW_CONFIDENCE: f64 = 0.6;
+
+// Version of the model file format
+const MODEL_VERSION: u32 = 1;
+
+// Example paragraphs of every section, shipped with the application
+const TRAINING_CORPUS: &str = include_str!("section_corpus.txt");
+
+/// Section most likely to hold a paragraph, with its probability.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Classification {
+    pub kind: SectionKind,
+    /// Posterior probability of `kind`, between 0 and 1
+    pub confidence: f64,
+}
+
+/// A paragraph of a file without headings and the section proposed for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassifiedParagraph {
+    pub text: String,
+    pub kind: SectionKind,
+    pub confidence: f64,
+}
+
+impl ClassifiedParagraph {
+    /// Whether the paragraph should be checked by the user
+    pub fn is_uncertain(&self) -> bool {
+        self.confidence < LOW_CONFIDENCE
+    }
+}
+
+// Training counts of one section
+#[derive(Debug, Clone, Default)]
+struct SectionCounts {
+    paragraphs: u32,
+    features: HashMap<String, u32>,
+    // Sum of the feature counts
+    total: u32,
+}
+
+/// Naive Bayes classifier over the words and word pairs of a paragraph,
+/// telling which section a paragraph of a file without headings belongs to.
+/// Words are compared ignoring case and accents.
+#[derive(Debug, Clone)]
+pub struct SectionClassifier {
+    /// One entry per section, in the order of `SectionKind::ALL`
+    sections: Vec<SectionCounts>,
+    vocabulary: HashSet<String>,
+}
+
+impl Default for SectionClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SectionClassifier {
+    /// Classifier without training, which places nothing
+    pub fn new() -> Self {
+        Self {
+            sections: vec![SectionCounts::default(); SectionKind::ALL.len()],
+            vocabulary: HashSet::new(),
+        }
+    }
+
+    /// Classifier trained on the corpus shipped with the application
+    pub fn bundled() -> Self {
+        let mut classifier = Self::new();
+        let mut kind = None;
+        let mut paragraph = String::new();
+        for line in TRAINING_CORPUS.lines().chain([""]) {
+            let marker = line
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+                .and_then(SectionKind::from_id);
+            if line.trim().is_empty() || marker.is_some() {
+                if let Some(kind) = kind {
+                    classifier.train(kind, &paragraph);
+                }
+                paragraph.clear();
+                kind = marker.or(kind);
+            } else {
+                paragraph.push_str(line);
+                paragraph.push('\n');
+            }
+        }
+        classifier
+    }
+
+    /// Counts the words of a paragraph known to belong to `kind`
+    pub fn train(&mut self, kind: SectionKind, paragraph: &str) {
+        let features = features(paragraph);
+        if features.is_empty() {
+            return;
+        }
+        let counts = &mut self.sections[kind.index()];
+        counts.paragraphs += 1;
+        for feature in features {
+            counts.total += 1;
+            *counts.features.entry(feature.clone()).or_default() += 1;
+            self.vocabulary.insert(feature);
+        }
+    }
+
+    /// Trains on every paragraph of the sections of a prompt
+    pub fn train_prompt(&mut self, data: &PromptData) {
+        for kind in SectionKind::ALL {
+            if let Some(body) = data.section_body(kind) {
+                for paragraph in split_paragraphs(&body) {
+                    self.train(kind, &paragraph);
+                }
+            }
+        }
+    }
+
+    /// Trains on the prompts of a library whose sections have headings, so
+    /// the classifier learns how the user writes each section. Returns the
+    /// number of prompts used.
+    pub fn train_library(&mut self, library: &PromptLibrary, aliases: &HeadingAliases) -> usize {
+        let mut used = 0;
+        for entry in library.entries() {
+            let Ok(decoded) = read_text_file(&library.path_of(&entry.file), None) else {
+                continue;
+            };
+            let content = decoded.text;
+            if has_headings(&content, aliases) {
+                self.train_prompt(&parse_prompt_with(&content, aliases, self));
+                used += 1;
+            }
+        }
+        used
+    }
+
+    /// Number of paragraphs the classifier was trained on
+    pub fn training_size(&self) -> u32 {
+        self.sections.iter().map(|counts| counts.paragraphs).sum()
+    }
+
+    /// Most likely section of a paragraph; None before any training or for
+    /// text without words
+    pub fn classify(&self, paragraph: &str) -> Option<Classification> {
+        let trained = self.training_size();
+        let features = features(paragraph);
+        if trained == 0 || features.is_empty() {
+            return None;
+        }
+
+        // Log-probabilities with add-one smoothing; words never seen in
+        // training say nothing about the section and are skipped
+        let vocabulary = self.vocabulary.len() as f64;
+        let sections = self.sections.len() as f64;
+        let scores: Vec<f64> = self
+            .sections
+            .iter()
+            .map(|counts| {
+                let prior = ((counts.paragraphs as f64 + 1.0) / (trained as f64 + sections)).ln();
+                let denominator = counts.total as f64 + vocabulary;
+                features
+                    .iter()
+                    .filter(|feature| self.vocabulary.contains(*feature))
+                    .map(|feature| {
+                        let count = counts.features.get(feature).copied().unwrap_or(0);
+                        ((count as f64 + 1.0) / denominator).ln()
+                    })
+                    .sum::<f64>()
+                    + prior
+            })
+            .collect();
+
+        let best = scores
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)?;
+        let total: f64 = scores
+            .iter()
+            .map(|score| (score - scores[best]).exp())
+            .sum();
+        Some(Classification {
+            kind: SectionKind::ALL[best],
+            confidence: 1.0 / total,
+        })
+    }
+
+    /// Model as text: a version line, the paragraph count of each section and
+    /// one tab-separated `section, feature, count` line per feature
+    pub fn to_text(&self) -> String {
+        let mut text = format!("versao: {}\n", MODEL_VERSION);
+        for (kind, counts) in SectionKind::ALL.iter().zip(&self.sections) {
+            text.push_str(&format!("secao: {} {}\n", kind.id(), counts.paragraphs));
+        }
+        for (kind, counts) in SectionKind::ALL.iter().zip(&self.sections) {
+            let mut features: Vec<_> = counts.features.iter().collect();
+            features.sort();
+            for (feature, count) in features {
+                text.push_str(&format!("{}\t{}\t{}\n", kind.id(), feature, count));
+            }
+        }
+        text
+    }
+
+    /// Reads a model written by `to_text`, skipping unknown lines
+    pub fn parse(text: &str) -> Self {
+        let mut classifier = Self::new();
+        for line in text.lines() {
+            if let Some(section) = line.strip_prefix("secao: ") {
+                let Some((id, paragraphs)) = section.split_once(' ') else {
+                    continue;
+                };
+                if let (Some(kind), Ok(paragraphs)) =
+                    (SectionKind::from_id(id), paragraphs.trim().parse())
+                {
+                    classifier.sections[kind.index()].paragraphs = paragraphs;
+                }
+                continue;
+            }
+
+            let mut fields = line.split('\t');
+            let (Some(id), Some(feature), Some(count), None) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let (Some(kind), Ok(count)) = (SectionKind::from_id(id), count.parse::<u32>()) else {
+                continue;
+            };
+            let counts = &mut classifier.sections[kind.index()];
+            counts.total += count;
+            *counts.features.entry(feature.to_string()).or_default() += count;
+            classifier.vocabulary.insert(feature.to_string());
+        }
+        classifier
+    }
+}
+
+/// Keeps a trained classifier in a text file.
+pub struct ClassifierStore {
+    file: PathBuf,
+}
+
+impl ClassifierStore {
+    pub fn new(file: &Path) -> Self {
+        Self {
+            file: file.to_path_buf(),
+        }
+    }
+
+    /// Store in `classificador.txt` of the user data directory
+    pub fn in_user_data_dir() -> Option<Self> {
+        app_dirs::data_dir().map(|dir| Self::new(&dir.join(MODEL_FILE_NAME)))
+    }
+
+    /// Reads the trained model; the bundled one when nothing was trained yet
+    pub fn load(&self) -> Result<SectionClassifier> {
+        match fs::read_to_string(&self.file) {
+            Ok(text) => Ok(SectionClassifier::parse(&text)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(SectionClassifier::bundled()),
+            Err(e) => Err(Error::io(e, &self.file)),
+        }
+    }
+
+    /// Writes the model under a temporary name first, then replaces the file
+    pub fn save(&self, classifier: &SectionClassifier) -> Result<()> {
+        if let Some(dir) = self.file.parent() {
+            fs::create_dir_all(dir).with_path(dir)?;
+        }
+        write_atomically(&self.file, classifier.to_text().as_bytes())
+    }
+}
+
+/// Paragraphs of a text, separated by blank lines
+pub fn split_paragraphs(text: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for line in text.lines().chain([""]) {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(current.join("\n").trim().to_string());
+                current.clear();
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    paragraphs
+}
+
+/// Proposes a section for every paragraph of a file without headings. A
+/// lone paragraph is the main content, as is anything the classifier cannot
+/// place.
+pub fn classify_paragraphs(text: &str, classifier: &SectionClassifier) -> Vec<ClassifiedParagraph> {
+    let paragraphs = split_paragraphs(text);
+    let alone = paragraphs.len() == 1;
+    paragraphs
+        .into_iter()
+        .map(|text| {
+            let classification = classifier.classify(&text).filter(|_| !alone);
+            ClassifiedParagraph {
+                kind: classification.map_or(SectionKind::MainContent, |c| c.kind),
+                confidence: classification.map_or(1.0, |c| c.confidence),
+                text,
+            }
+        })
+        .collect()
+}
+
+/// Stores the paragraphs in their sections, in order, separated by blank lines
+pub fn assign_paragraphs(paragraphs: &[ClassifiedParagraph], data: &mut PromptData) {
+    for kind in SectionKind::ALL {
+        let texts: Vec<&str> = paragraphs
+            .iter()
+            .filter(|paragraph| paragraph.kind == kind)
+            .map(|paragraph| paragraph.text.as_str())
+            .collect();
+        if texts.is_empty() {
+            continue;
+        }
+        let body = texts.join("\n\n");
+        if kind == SectionKind::Tests {
+            data.tests = Some(Tests::parse(&body));
+        } else {
+            data.set_section_body(kind, body);
+        }
+    }
+}
+
+// Words and pairs of neighbouring words, lowercase and without accents
+fn features(text: &str) -> Vec<String> {
+    let folded = fold_text(text);
+    let words: Vec<&str> = folded
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let pairs = words.windows(2).map(|pair| pair.join(" "));
+    words
+        .iter()
+        .map(|word| word.to_string())
+        .chain(pairs)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_classifier_places_common_paragraphs() {
+        let classifier = SectionClassifier::bundled();
+        let kind = |text: &str| classifier.classify(text).map(|c| c.kind);
+
+        assert_eq!(
+            kind("Você é um especialista em segurança de aplicações web."),
+            Some(SectionKind::Context)
+        );
+        assert_eq!(
+            kind("Do not use more than 100 words and never invent data."),
+            Some(SectionKind::Limitations)
+        );
+        assert_eq!(
+            kind("Return the answer as JSON with the fields name and score."),
+            Some(SectionKind::OutputFormat)
+        );
+        assert_eq!(
+            kind("Escribe pruebas unitarias para la función de login."),
+            Some(SectionKind::Tests)
+        );
+        assert_eq!(SectionClassifier::new().classify("qualquer texto"), None);
+    }
+
+    #[test]
+    fn test_training_changes_the_proposed_section() {
+        let mut classifier = SectionClassifier::bundled();
+        let paragraph = "Glossário interno: PBX, URA e SLA";
+        assert!(classifier.classify(paragraph).unwrap().confidence < LOW_CONFIDENCE);
+
+        for _ in 0..3 {
+            classifier.train(
+                SectionKind::AuxiliaryContent,
+                "Glossário interno: PBX, URA e SLA",
+            );
+        }
+        let classification = classifier.classify(paragraph).unwrap();
+        assert_eq!(classification.kind, SectionKind::AuxiliaryContent);
+        assert!(classification.confidence >= LOW_CONFIDENCE);
+    }
+
+    #[test]
+    fn test_model_text_round_trip() {
+        let classifier = SectionClassifier::bundled();
+
+        let restored = SectionClassifier::parse(&classifier.to_text());
+
+        assert_eq!(restored.training_size(), classifier.training_size());
+        assert_eq!(restored.to_text(), classifier.to_text());
+        let paragraph = "Sempre explique o raciocínio.";
+        assert_eq!(restored.classify(paragraph), classifier.classify(paragraph));
+    }
+
+    #[test]
+    fn test_classify_paragraphs_and_assign() {
+        let classifier = SectionClassifier::bundled();
+        let paragraphs = classify_paragraphs(
+            "Você é um revisor de textos.\r\n\r\nRevise o texto abaixo.\n  \nResponda em Markdown.",
+            &classifier,
+        );
+        let kinds: Vec<SectionKind> = paragraphs.iter().map(|p| p.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                SectionKind::Context,
+                SectionKind::MainContent,
+                SectionKind::OutputFormat
+            ]
+        );
+
+        let mut data = PromptData::new();
+        assign_paragraphs(&paragraphs, &mut data);
+        assert_eq!(
+            data.section_body(SectionKind::Context).as_deref(),
+            Some("Você é um revisor de textos.")
+        );
+
+        // Um parágrafo sozinho é sempre o conteúdo principal
+        let alone = classify_paragraphs("Você é um revisor.", &classifier);
+        assert_eq!(alone[0].kind, SectionKind::MainContent);
+        assert!(!alone[0].is_uncertain());
+    }
+}
@@ -0,0 +1,708 @@
+use crate::models::metadata::DocumentMetadata;
+use crate::services::markdown::{is_heading, FenceTracker};
+use crate::services::prompt_library::fold_text;
+use crate::services::prompt_parser::section_from_heading;
+
+/// Largest token table compared with a full LCS; bigger changes are shown as
+/// a block removal followed by a block addition
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Heading given to the metadata header when documents are compared
+const METADATA_HEADING: &str = "Metadados";
+
+/// Key of the metadata section; no heading can produce it
+const METADATA_KEY: &str = "---";
+
+/// One piece of a diff: a line, a word or a run of text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffPiece {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// How a section changed between two documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionChange {
+    Added,
+    Removed,
+    Modified,
+    Unchanged,
+}
+
+impl SectionChange {
+    /// Label shown next to the section title
+    pub fn label(&self) -> &'static str {
+        match self {
+            SectionChange::Added => "adicionada",
+            SectionChange::Removed => "removida",
+            SectionChange::Modified => "modificada",
+            SectionChange::Unchanged => "sem alterações",
+        }
+    }
+}
+
+/// Side of a comparison shown in one pane of the split view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffSide {
+    Old,
+    New,
+}
+
+/// A section of a document split at its headings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentSection {
+    /// Heading text without the `#` marks; empty for text before the first heading
+    pub heading: String,
+    /// Number of `#` marks of the heading; 0 for the text before the first
+    /// heading and for the metadata header
+    pub level: usize,
+    pub body: String,
+    // Identity used to align the section with the other document
+    key: String,
+}
+
+impl DocumentSection {
+    /// Identity used to align the section with the same section of another
+    /// version of the document
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns true for the section holding the metadata header
+    pub fn is_metadata(&self) -> bool {
+        self.key == METADATA_KEY
+    }
+}
+
+/// Result of comparing one section of two documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionComparison {
+    pub heading: String,
+    pub change: SectionChange,
+    /// The section exists in both documents but at a different position
+    pub moved: bool,
+    /// Word-level differences of the section body
+    pub words: Vec<DiffPiece>,
+}
+
+impl SectionComparison {
+    /// Title of the section compared
+    pub fn title(&self) -> &str {
+        if self.heading.is_empty() {
+            "Início do documento"
+        } else {
+            &self.heading
+        }
+    }
+
+    /// Change label, including whether the section moved
+    pub fn status(&self) -> String {
+        match (self.change, self.moved) {
+            (SectionChange::Unchanged, true) => "movida".to_string(),
+            (change, true) => format!("{}, movida", change.label()),
+            (change, false) => change.label().to_string(),
+        }
+    }
+
+    /// Returns true when the section differs in content or position
+    pub fn has_differences(&self) -> bool {
+        self.change != SectionChange::Unchanged || self.moved
+    }
+
+    /// Text of one side of the section, with the words only found on that side
+    /// marked as `[-removido-]` or `{+adicionado+}`
+    pub fn side_text(&self, side: DiffSide) -> String {
+        let mut text = String::new();
+        for piece in &self.words {
+            match (piece, side) {
+                (DiffPiece::Same(words), _) => text.push_str(words),
+                (DiffPiece::Removed(words), DiffSide::Old) => {
+                    text.push_str(&format!("[-{}-]", words))
+                }
+                (DiffPiece::Added(words), DiffSide::New) => {
+                    text.push_str(&format!("{{+{}+}}", words))
+                }
+                _ => {}
+            }
+        }
+        text
+    }
+}
+
+/// Splits a document at its level 1 and 2 headings, ignoring headings inside
+/// fenced code. The metadata header, when present, becomes a first section.
+pub fn split_document(content: &str) -> Vec<DocumentSection> {
+    let (metadata, body) = DocumentMetadata::split_header(content);
+    let mut sections = Vec::new();
+
+    if !metadata.is_empty() {
+        sections.push(DocumentSection {
+            heading: METADATA_HEADING.to_string(),
+            level: 0,
+            body: metadata_lines(&metadata),
+            key: METADATA_KEY.to_string(),
+        });
+    }
+
+    let mut fences = FenceTracker::new();
+    let mut heading = String::new();
+    let mut level = 0;
+    let mut lines: Vec<&str> = Vec::new();
+    for line in body.lines() {
+        let in_fence = fences.feed(line);
+        let hashes = line.chars().take_while(|c| *c == '#').count();
+        if !in_fence && hashes <= 2 && is_heading(line) {
+            push_section(&mut sections, heading, level, &lines);
+            heading = line[hashes..].trim().to_string();
+            level = hashes;
+            lines.clear();
+        } else {
+            lines.push(line);
+        }
+    }
+    push_section(&mut sections, heading, level, &lines);
+
+    sections
+}
+
+/// Compares two documents section by section. Sections are aligned by heading,
+/// so a section found at another position is reported as moved rather than as
+/// removed and added. Sections are listed in the order of the new document,
+/// with removed ones at their former position.
+pub fn compare_documents(old: &str, new: &str) -> Vec<SectionComparison> {
+    let old_sections = split_document(old);
+    let new_sections = split_document(new);
+    let old_keys: Vec<&str> = old_sections.iter().map(|s| s.key.as_str()).collect();
+    let new_keys: Vec<&str> = new_sections.iter().map(|s| s.key.as_str()).collect();
+    let find =
+        |sections: &[DocumentSection], key: &str| sections.iter().find(|s| s.key == key).cloned();
+
+    let mut comparisons = Vec::new();
+    for piece in diff_sequence(&old_keys, &new_keys) {
+        match piece {
+            DiffPiece::Same(key) => {
+                let (old, new) = (find(&old_sections, &key), find(&new_sections, &key));
+                if let (Some(old), Some(new)) = (old, new) {
+                    comparisons.push(compare_section(Some(old), Some(new), false));
+                }
+            }
+            DiffPiece::Added(key) => {
+                let old = find(&old_sections, &key);
+                let moved = old.is_some();
+                comparisons.push(compare_section(old, find(&new_sections, &key), moved));
+            }
+            // A moved section is reported at its new position
+            DiffPiece::Removed(key) if !new_keys.contains(&key.as_str()) => {
+                comparisons.push(compare_section(find(&old_sections, &key), None, false));
+            }
+            DiffPiece::Removed(_) => {}
+        }
+    }
+
+    comparisons
+}
+
+/// Returns true when any section differs in content or position
+pub fn has_differences(comparisons: &[SectionComparison]) -> bool {
+    comparisons.iter().any(SectionComparison::has_differences)
+}
+
+/// Renders a comparison as plain text: a `## Título (estado)` line per section
+/// and the changed words marked as `[-removido-]` and `{+adicionado+}`.
+/// Sections without changes in content are only named.
+pub fn render_unified(comparisons: &[SectionComparison]) -> String {
+    render(comparisons, &UNIFIED_MARKS)
+}
+
+/// Renders a comparison for a terminal, with ANSI colours instead of markers
+pub fn render_colored(comparisons: &[SectionComparison]) -> String {
+    render(comparisons, &COLORED_MARKS)
+}
+
+/// Line diff based on the longest common subsequence
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffPiece> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    diff_sequence(&old, &new)
+}
+
+/// Word diff: the lines that changed are compared word by word. Consecutive
+/// pieces of the same kind are merged, so unchanged text comes in runs.
+pub fn diff_words(old: &str, new: &str) -> Vec<DiffPiece> {
+    let mut pieces = Vec::new();
+    let mut removed = String::new();
+    let mut added = String::new();
+
+    for line in diff_lines(old, new) {
+        match line {
+            DiffPiece::Same(line) => {
+                push_word_block(&mut pieces, &removed, &added);
+                removed.clear();
+                added.clear();
+                push_piece(&mut pieces, DiffPiece::Same(line + "\n"));
+            }
+            DiffPiece::Removed(line) => {
+                removed.push_str(&line);
+                removed.push('\n');
+            }
+            DiffPiece::Added(line) => {
+                added.push_str(&line);
+                added.push('\n');
+            }
+        }
+    }
+    push_word_block(&mut pieces, &removed, &added);
+
+    // Lines were joined with a newline each; the last one is not part of the text
+    if let Some(DiffPiece::Same(text) | DiffPiece::Added(text) | DiffPiece::Removed(text)) =
+        pieces.last_mut()
+    {
+        text.pop();
+        if text.is_empty() {
+            pieces.pop();
+        }
+    }
+    pieces
+}
+
+// Markers written around headings, removed and added text
+struct Marks {
+    heading: (&'static str, &'static str),
+    removed: (&'static str, &'static str),
+    added: (&'static str, &'static str),
+}
+
+const UNIFIED_MARKS: Marks = Marks {
+    heading: ("", ""),
+    removed: ("[-", "-]"),
+    added: ("{+", "+}"),
+};
+
+const COLORED_MARKS: Marks = Marks {
+    heading: ("\x1b[1m", "\x1b[0m"),
+    removed: ("\x1b[31m", "\x1b[0m"),
+    added: ("\x1b[32m", "\x1b[0m"),
+};
+
+fn render(comparisons: &[SectionComparison], marks: &Marks) -> String {
+    if !has_differences(comparisons) {
+        return "Nenhuma diferença entre os documentos.".to_string();
+    }
+
+    comparisons
+        .iter()
+        .map(|comparison| {
+            let mut text = format!(
+                "{}## {} ({}){}",
+                marks.heading.0,
+                comparison.title(),
+                comparison.status(),
+                marks.heading.1
+            );
+            if comparison.change != SectionChange::Unchanged {
+                text.push('\n');
+                for piece in &comparison.words {
+                    let (open, words, close) = match piece {
+                        DiffPiece::Same(words) => ("", words, ""),
+                        DiffPiece::Removed(words) => (marks.removed.0, words, marks.removed.1),
+                        DiffPiece::Added(words) => (marks.added.0, words, marks.added.1),
+                    };
+                    text.push_str(open);
+                    text.push_str(words);
+                    text.push_str(close);
+                }
+            }
+            text
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn compare_section(
+    old: Option<DocumentSection>,
+    new: Option<DocumentSection>,
+    moved: bool,
+) -> SectionComparison {
+    let heading = new
+        .as_ref()
+        .or(old.as_ref())
+        .map(|section| section.heading.clone())
+        .unwrap_or_default();
+    let (change, words) = match (old, new) {
+        (Some(old), Some(new)) if old.body == new.body => {
+            (SectionChange::Unchanged, vec![DiffPiece::Same(new.body)])
+        }
+        (Some(old), Some(new)) => (SectionChange::Modified, diff_words(&old.body, &new.body)),
+        (None, Some(new)) => (SectionChange::Added, vec![DiffPiece::Added(new.body)]),
+        (Some(old), None) => (SectionChange::Removed, vec![DiffPiece::Removed(old.body)]),
+        (None, None) => (SectionChange::Unchanged, Vec::new()),
+    };
+    SectionComparison {
+        heading,
+        change,
+        moved,
+        words,
+    }
+}
+
+// Stores the lines collected under a heading; blank text before the first
+// heading is not a section
+fn push_section(
+    sections: &mut Vec<DocumentSection>,
+    heading: String,
+    level: usize,
+    lines: &[&str],
+) {
+    let body = lines.join("\n").trim().to_string();
+    if heading.is_empty() && body.is_empty() {
+        return;
+    }
+
+    // Known prompt sections match whatever the heading wording; repeated
+    // headings are told apart by their position
+    let base = match section_from_heading(&format!("## {}", heading)) {
+        Some(kind) => kind.id().to_string(),
+        None => fold_text(&heading)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+    let repeats = sections
+        .iter()
+        .filter(|section| section.key == base || section.key.starts_with(&format!("{}#", base)))
+        .count();
+    let key = if repeats == 0 {
+        base
+    } else {
+        format!("{}#{}", base, repeats + 1)
+    };
+
+    sections.push(DocumentSection {
+        heading,
+        level,
+        body,
+        key,
+    });
+}
+
+// Header lines compared for the metadata; the modification time changes on
+// every save and is left out
+fn metadata_lines(metadata: &DocumentMetadata) -> String {
+    metadata
+        .to_header()
+        .lines()
+        .filter(|line| *line != "---" && !line.starts_with("modified: "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Word-compares a block of removed lines with the block of added lines that
+// replaced it
+fn push_word_block(pieces: &mut Vec<DiffPiece>, removed: &str, added: &str) {
+    let old = word_tokens(removed);
+    let new = word_tokens(added);
+    for piece in diff_sequence(&old, &new) {
+        push_piece(pieces, piece);
+    }
+}
+
+// Appends a piece, merging it with the previous one when both are of the same kind
+fn push_piece(pieces: &mut Vec<DiffPiece>, piece: DiffPiece) {
+    match (pieces.last_mut(), piece) {
+        (Some(DiffPiece::Same(last)), DiffPiece::Same(text))
+        | (Some(DiffPiece::Added(last)), DiffPiece::Added(text))
+        | (Some(DiffPiece::Removed(last)), DiffPiece::Removed(text)) => last.push_str(&text),
+        (_, piece) => pieces.push(piece),
+    }
+}
+
+// Splits a text into words, runs of whitespace and single punctuation marks
+fn word_tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let same_class = |next: char| {
+            (c.is_alphanumeric() || c == '_') && (next.is_alphanumeric() || next == '_')
+                || c.is_whitespace() && next.is_whitespace()
+        };
+        if !chars.peek().is_some_and(|(_, next)| same_class(*next)) {
+            let end = i + c.len_utf8();
+            tokens.push(&text[start..end]);
+            start = end;
+        }
+    }
+    tokens
+}
+
+// Diff of two token sequences based on the longest common subsequence
+fn diff_sequence(old: &[&str], new: &[&str]) -> Vec<DiffPiece> {
+    // Common prefix and suffix are kept out of the table
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut pieces: Vec<DiffPiece> = old[..prefix]
+        .iter()
+        .map(|token| DiffPiece::Same(token.to_string()))
+        .collect();
+    pieces.extend(diff_middle(old_middle, new_middle));
+    pieces.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|token| DiffPiece::Same(token.to_string())),
+    );
+    pieces
+}
+
+fn diff_middle(old: &[&str], new: &[&str]) -> Vec<DiffPiece> {
+    let removed = || {
+        old.iter()
+            .map(|token| DiffPiece::Removed(token.to_string()))
+    };
+    let added = || new.iter().map(|token| DiffPiece::Added(token.to_string()));
+    if old.is_empty() || new.is_empty() || old.len() * new.len() > MAX_LCS_CELLS {
+        return removed().chain(added()).collect();
+    }
+
+    // lengths[i][j] = LCS length of old[i..] and new[j..]
+    let width = new.len() + 1;
+    let mut lengths = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * width + j] = if old[i] == new[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut pieces = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            pieces.push(DiffPiece::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            pieces.push(DiffPiece::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            pieces.push(DiffPiece::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    pieces.extend(
+        old[i..]
+            .iter()
+            .map(|token| DiffPiece::Removed(token.to_string())),
+    );
+    pieces.extend(
+        new[j..]
+            .iter()
+            .map(|token| DiffPiece::Added(token.to_string())),
+    );
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::section::SectionKind;
+    use crate::services::prompt_generator::PromptData;
+
+    fn same(text: &str) -> DiffPiece {
+        DiffPiece::Same(text.to_string())
+    }
+
+    fn added(text: &str) -> DiffPiece {
+        DiffPiece::Added(text.to_string())
+    }
+
+    fn removed(text: &str) -> DiffPiece {
+        DiffPiece::Removed(text.to_string())
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        assert_eq!(
+            diff_lines("a\nb\nc\nd", "a\nc\nx\nd"),
+            vec![same("a"), removed("b"), same("c"), added("x"), same("d")]
+        );
+        assert_eq!(diff_lines("", "novo"), vec![added("novo")]);
+        assert_eq!(diff_lines("igual", "igual"), vec![same("igual")]);
+    }
+
+    #[test]
+    fn test_diff_words_within_changed_lines() {
+        assert_eq!(
+            diff_words(
+                "Revise.\nSeja breve e direto.",
+                "Revise.\nSeja detalhado e direto."
+            ),
+            vec![
+                same("Revise.\nSeja "),
+                removed("breve"),
+                added("detalhado"),
+                same(" e direto.")
+            ]
+        );
+        assert_eq!(diff_words("igual", "igual"), vec![same("igual")]);
+        assert_eq!(
+            diff_words("a\nb", "a\nb\nc"),
+            vec![same("a\nb\n"), added("c")]
+        );
+    }
+
+    #[test]
+    fn test_split_document_at_headings() {
+        let content =
+            "Introdução\n\n# Tarefa\n\nFaça.\n\n```md\n## Não é seção\n```\n\n### Detalhe\n\nmais";
+        let sections = split_document(content);
+
+        let headings: Vec<&str> = sections.iter().map(|s| s.heading.as_str()).collect();
+        assert_eq!(headings, vec!["", "Tarefa"]);
+        assert_eq!(sections[0].body, "Introdução");
+        // Títulos em blocos de código e de nível 3 ficam no corpo da seção
+        assert!(sections[1].body.contains("## Não é seção"));
+        assert!(sections[1].body.ends_with("### Detalhe\n\nmais"));
+    }
+
+    #[test]
+    fn test_compare_documents_by_section() {
+        let mut old = PromptData::new();
+        old.set_section_body(SectionKind::Context, "Você é um revisor.".to_string());
+        old.set_section_body(SectionKind::MainContent, "Revise.\nSeja breve.".to_string());
+        old.set_section_body(SectionKind::Limitations, "Sem jargão.".to_string());
+
+        let mut new = old.clone();
+        new.set_section_body(
+            SectionKind::MainContent,
+            "Revise.\nSeja detalhado.".to_string(),
+        );
+        new.limitations = None;
+        new.set_section_body(SectionKind::OutputFormat, "Markdown".to_string());
+
+        let comparisons = compare_documents(&old.build_document(), &new.build_document());
+
+        let changes: Vec<(&str, SectionChange)> = comparisons
+            .iter()
+            .map(|comparison| (comparison.title(), comparison.change))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("Contexto", SectionChange::Unchanged),
+                ("Conteúdo Principal", SectionChange::Modified),
+                ("Limitações", SectionChange::Removed),
+                ("Formato de Saída", SectionChange::Added),
+            ]
+        );
+        assert_eq!(
+            comparisons[1].words,
+            vec![
+                same("Revise.\nSeja "),
+                removed("breve"),
+                added("detalhado"),
+                same(".")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_moved_sections_are_not_removed_and_added() {
+        let old = "## Contexto\n\nA\n\n## Orientações\n\nB\n\n## Limitações\n\nC";
+        let new = "## Limitações\n\nC\n\n## Contexto\n\nA\n\n## Orientações\n\nB mais";
+
+        let comparisons = compare_documents(old, new);
+
+        let statuses: Vec<(&str, String)> = comparisons
+            .iter()
+            .map(|comparison| (comparison.title(), comparison.status()))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("Limitações", "movida".to_string()),
+                ("Contexto", "sem alterações".to_string()),
+                ("Orientações", "modificada".to_string()),
+            ]
+        );
+        assert!(has_differences(&comparisons));
+    }
+
+    #[test]
+    fn test_headings_align_by_section_and_repetition() {
+        // Variações de grafia dos títulos conhecidos são a mesma seção
+        let comparisons = compare_documents("## Few-shot\n\nx", "## Few-Shot Examples\n\nx");
+        assert_eq!(comparisons.len(), 1);
+        assert_eq!(comparisons[0].change, SectionChange::Unchanged);
+
+        let comparisons = compare_documents("# Nota\n\num\n\n# Nota\n\ndois", "# Nota\n\num");
+        assert_eq!(comparisons[1].change, SectionChange::Removed);
+        assert_eq!(comparisons[1].words, vec![removed("dois")]);
+    }
+
+    #[test]
+    fn test_metadata_comparison_ignores_modification_time() {
+        let mut old = PromptData::new();
+        old.metadata.title = "Revisão".to_string();
+        old.metadata.modified = Some(100);
+        let mut new = old.clone();
+        new.metadata.modified = Some(200);
+
+        let comparisons = compare_documents(&old.build_document(), &new.build_document());
+        assert!(!has_differences(&comparisons));
+
+        new.metadata.model = "gpt-4o".to_string();
+        let comparisons = compare_documents(&old.build_document(), &new.build_document());
+        assert_eq!(comparisons[0].title(), "Metadados");
+        assert!(comparisons[0].words.iter().any(
+            |piece| matches!(piece, DiffPiece::Added(text) if text.contains("model: gpt-4o"))
+        ));
+    }
+
+    #[test]
+    fn test_render_unified_and_colored() {
+        let old = "## Contexto\n\nigual\n\n## Orientações\n\nuse Rust";
+        let new = "## Contexto\n\nigual\n\n## Orientações\n\nuse Go";
+        let comparisons = compare_documents(old, new);
+
+        assert_eq!(
+            render_unified(&comparisons),
+            "## Contexto (sem alterações)\n\n## Orientações (modificada)\nuse [-Rust-]{+Go+}"
+        );
+        assert!(render_colored(&comparisons).contains("use \x1b[31mRust\x1b[0m\x1b[32mGo\x1b[0m"));
+        assert_eq!(
+            render_unified(&compare_documents(old, old)),
+            "Nenhuma diferença entre os documentos."
+        );
+    }
+
+    #[test]
+    fn test_side_text_marks_one_side() {
+        let comparisons = compare_documents("## Contexto\n\num dois", "## Contexto\n\num três");
+        assert_eq!(comparisons[0].side_text(DiffSide::Old), "um [-dois-]");
+        assert_eq!(comparisons[0].side_text(DiffSide::New), "um {+três+}");
+    }
+
+    #[test]
+    fn test_large_change_falls_back_to_blocks() {
+        let old: String = (0..3_000).map(|i| format!("a{}\n", i)).collect();
+        let new: String = (0..3_000).map(|i| format!("b{}\n", i)).collect();
+
+        let lines = diff_lines(&old, &new);
+
+        assert_eq!(lines.len(), 6_000);
+        assert_eq!(lines[0], removed("a0"));
+        assert_eq!(lines[3_000], added("b0"));
+    }
+}
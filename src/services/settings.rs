@@ -0,0 +1,535 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::models::language::Language;
+use crate::services::app_dirs;
+use crate::services::file_service::{write_atomically, DEFAULT_BACKUP_COUNT};
+use crate::services::prompt_generator::PromptData;
+use crate::services::recovery::AUTOSAVE_INTERVAL_SECONDS;
+use crate::services::text_encoding::{LineEnding, TextEncoding};
+use crate::services::token_counter::Tokenizer;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Schema version written to the settings file
+pub const SETTINGS_VERSION: u32 = 3;
+
+/// File name suggested when saving a prompt without a title
+pub const DEFAULT_FILE_NAME: &str = "generated_prompt.txt";
+
+/// Library folder used until another one is chosen
+pub const DEFAULT_LIBRARY_DIR: &str = "prompts salvos";
+
+/// Smallest and largest font sizes offered, in pixels
+pub const FONT_SIZE_RANGE: (u32, u32) = (10, 24);
+
+/// Shortest and longest autosave intervals accepted, in seconds
+pub const AUTOSAVE_RANGE: (u64, u64) = (5, 3600);
+
+/// Most backups kept of each saved file
+pub const MAX_BACKUP_COUNT: usize = 20;
+
+/// Name of the settings file inside the configuration directory
+const SETTINGS_FILE_NAME: &str = "configuracoes.txt";
+
+// Value of "codificacao" when the encoding of opened files is detected
+const AUTOMATIC_ENCODING: &str = "automatica";
+
+/// How the prompt is written when copied or saved to a file other than a prompt file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Renderer {
+    /// Plain text without section markers
+    Plain,
+    /// Text with the `<START_...>` and `<END_...>` section markers
+    Markers,
+    /// Editable document with the metadata header and `## ` headings
+    Document,
+}
+
+impl Renderer {
+    /// All renderers, in the order shown in the UI
+    pub const ALL: [Renderer; 3] = [Renderer::Plain, Renderer::Markers, Renderer::Document];
+
+    /// Label shown in the UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            Renderer::Plain => "Texto simples",
+            Renderer::Markers => "Texto com marcadores de seção",
+            Renderer::Document => "Documento editável (com metadados)",
+        }
+    }
+
+    /// Value stored in the settings file
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Renderer::Plain => "texto",
+            Renderer::Markers => "marcadores",
+            Renderer::Document => "documento",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|renderer| renderer.as_str() == value)
+    }
+
+    /// Writes the prompt in this format; documents get their section
+    /// headings in `language`
+    pub fn render(&self, data: &PromptData, language: Language) -> String {
+        match self {
+            Renderer::Plain => data.build_prompt(false),
+            Renderer::Markers => data.build_prompt(true),
+            Renderer::Document => data.build_document_in(language),
+        }
+    }
+}
+
+/// Color scheme of the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    /// Follows the operating system
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    /// All themes, in the order shown in the UI
+    pub const ALL: [Theme; 3] = [Theme::System, Theme::Light, Theme::Dark];
+
+    /// Label shown in the UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            Theme::System => "Seguir o sistema",
+            Theme::Light => "Claro",
+            Theme::Dark => "Escuro",
+        }
+    }
+
+    /// Value stored in the settings file
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Theme::System => "sistema",
+            Theme::Light => "claro",
+            Theme::Dark => "escuro",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|theme| theme.as_str() == value)
+    }
+}
+
+/// Line breaks of saved files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEndingPolicy {
+    /// Those of the file when it was opened; new files use the system's
+    Preserve,
+    Lf,
+    CrLf,
+}
+
+impl LineEndingPolicy {
+    /// All policies, in the order shown in the UI
+    pub const ALL: [LineEndingPolicy; 3] = [
+        LineEndingPolicy::Preserve,
+        LineEndingPolicy::Lf,
+        LineEndingPolicy::CrLf,
+    ];
+
+    /// Label shown in the UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            LineEndingPolicy::Preserve => "Manter as do arquivo",
+            LineEndingPolicy::Lf => "LF (Linux e macOS)",
+            LineEndingPolicy::CrLf => "CRLF (Windows)",
+        }
+    }
+
+    /// Value stored in the settings file
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEndingPolicy::Preserve => "manter",
+            LineEndingPolicy::Lf => "lf",
+            LineEndingPolicy::CrLf => "crlf",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|policy| policy.as_str() == value)
+    }
+
+    /// Line break to save a file that was read with `detected`
+    pub fn resolve(&self, detected: LineEnding) -> LineEnding {
+        match self {
+            LineEndingPolicy::Preserve => detected,
+            LineEndingPolicy::Lf => LineEnding::Lf,
+            LineEndingPolicy::CrLf => LineEnding::CrLf,
+        }
+    }
+}
+
+/// User preferences kept between sessions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    /// Folder where the save dialog starts; None lets the dialog decide
+    pub save_dir: Option<PathBuf>,
+    /// File name suggested for prompts without a title
+    pub file_name: String,
+    pub library_dir: PathBuf,
+    /// Folder shown in the workspace sidebar on launch
+    pub workspace_dir: Option<PathBuf>,
+    pub renderer: Renderer,
+    pub autosave_seconds: u64,
+    /// Tokenizer of the token count shown with the preview
+    pub tokenizer: Tokenizer,
+    /// Font size of the text fields and buttons, in pixels
+    pub font_size: u32,
+    pub theme: Theme,
+    /// Language of the labels of the window
+    pub ui_language: Language,
+    /// Language of the section headings of the preview and of documents
+    pub output_language: Language,
+    /// Encoding used to open files; None detects it from the contents
+    pub encoding: Option<TextEncoding>,
+    pub line_endings: LineEndingPolicy,
+    /// Previous versions kept as backups when a file is saved over
+    pub backup_count: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            save_dir: None,
+            file_name: DEFAULT_FILE_NAME.to_string(),
+            library_dir: PathBuf::from(DEFAULT_LIBRARY_DIR),
+            workspace_dir: None,
+            renderer: Renderer::Plain,
+            autosave_seconds: AUTOSAVE_INTERVAL_SECONDS,
+            tokenizer: Tokenizer::Characters,
+            font_size: 14,
+            theme: Theme::System,
+            ui_language: Language::Portuguese,
+            output_language: Language::Portuguese,
+            encoding: None,
+            line_endings: LineEndingPolicy::Preserve,
+            backup_count: DEFAULT_BACKUP_COUNT,
+        }
+    }
+}
+
+impl Settings {
+    /// Reads a settings file, migrating it from older schema versions.
+    /// Missing, unknown or invalid values keep their defaults.
+    pub fn parse(text: &str) -> Self {
+        let (version, mut fields) = read_fields(text);
+        migrate(&mut fields, version);
+
+        let mut settings = Self::default();
+        for (key, value) in &fields {
+            let value = value.as_str();
+            match key.as_str() {
+                "pasta_salvar" => settings.save_dir = optional_path(value),
+                "nome_arquivo" if !value.is_empty() => settings.file_name = value.to_string(),
+                "pasta_biblioteca" if !value.is_empty() => {
+                    settings.library_dir = PathBuf::from(value)
+                }
+                "pasta_trabalho" => settings.workspace_dir = optional_path(value),
+                "renderizador" => {
+                    settings.renderer = Renderer::parse(value).unwrap_or(settings.renderer)
+                }
+                "autosalvamento" => {
+                    if let Ok(seconds) = value.parse::<u64>() {
+                        settings.autosave_seconds =
+                            seconds.clamp(AUTOSAVE_RANGE.0, AUTOSAVE_RANGE.1);
+                    }
+                }
+                "tokenizador" => {
+                    settings.tokenizer = Tokenizer::parse(value).unwrap_or(settings.tokenizer)
+                }
+                "tamanho_fonte" => {
+                    if let Ok(size) = value.parse::<u32>() {
+                        settings.font_size = size.clamp(FONT_SIZE_RANGE.0, FONT_SIZE_RANGE.1);
+                    }
+                }
+                "tema" => settings.theme = Theme::parse(value).unwrap_or(settings.theme),
+                "idioma_interface" => {
+                    settings.ui_language = Language::parse(value).unwrap_or(settings.ui_language)
+                }
+                "idioma_prompt" => {
+                    settings.output_language =
+                        Language::parse(value).unwrap_or(settings.output_language)
+                }
+                "codificacao" => settings.encoding = TextEncoding::parse(value),
+                "finais_de_linha" => {
+                    settings.line_endings =
+                        LineEndingPolicy::parse(value).unwrap_or(settings.line_endings)
+                }
+                "copias_de_seguranca" => {
+                    if let Ok(count) = value.parse::<usize>() {
+                        settings.backup_count = count.min(MAX_BACKUP_COUNT);
+                    }
+                }
+                _ => {}
+            }
+        }
+        settings
+    }
+
+    /// Writes the settings in the current schema version
+    pub fn to_text(&self) -> String {
+        let path = |path: &Option<PathBuf>| {
+            path.as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default()
+        };
+        format!(
+            "versao: {}\npasta_salvar: {}\nnome_arquivo: {}\npasta_biblioteca: {}\npasta_trabalho: {}\nrenderizador: {}\nautosalvamento: {}\ntokenizador: {}\ntamanho_fonte: {}\ntema: {}\nidioma_interface: {}\nidioma_prompt: {}\ncodificacao: {}\nfinais_de_linha: {}\ncopias_de_seguranca: {}\n",
+            SETTINGS_VERSION,
+            path(&self.save_dir),
+            self.file_name,
+            self.library_dir.display(),
+            path(&self.workspace_dir),
+            self.renderer.as_str(),
+            self.autosave_seconds,
+            self.tokenizer.as_str(),
+            self.font_size,
+            self.theme.as_str(),
+            self.ui_language.as_str(),
+            self.output_language.as_str(),
+            self.encoding
+                .map_or(AUTOMATIC_ENCODING, |encoding| encoding.as_str()),
+            self.line_endings.as_str(),
+            self.backup_count,
+        )
+    }
+}
+
+/// Settings file in the user configuration directory.
+#[derive(Debug, Clone)]
+pub struct SettingsStore {
+    file: PathBuf,
+}
+
+impl SettingsStore {
+    pub fn new(file: &Path) -> Self {
+        Self {
+            file: file.to_path_buf(),
+        }
+    }
+
+    /// Store in `configuracoes.txt` of the user configuration directory
+    pub fn in_user_config_dir() -> Option<Self> {
+        app_dirs::config_dir().map(|dir| Self::new(&dir.join(SETTINGS_FILE_NAME)))
+    }
+
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    /// Reads the settings; defaults when the file does not exist yet. Files
+    /// of older schema versions are written back in the current version.
+    pub fn load(&self) -> Result<Settings> {
+        let text = match fs::read_to_string(&self.file) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Settings::default()),
+            Err(e) => return Err(Error::io(e, &self.file)),
+        };
+
+        let settings = Settings::parse(&text);
+        if read_fields(&text).0 < SETTINGS_VERSION {
+            self.save(&settings)?;
+        }
+        Ok(settings)
+    }
+
+    /// Writes the settings under a temporary name first, then replaces the file
+    pub fn save(&self, settings: &Settings) -> Result<()> {
+        if let Some(dir) = self.file.parent() {
+            fs::create_dir_all(dir).with_path(dir)?;
+        }
+        write_atomically(&self.file, settings.to_text().as_bytes())
+    }
+}
+
+// Schema version and `key: value` fields of a settings file. Files without a
+// version line predate the versioned schema and count as version 0.
+fn read_fields(text: &str) -> (u32, Vec<(String, String)>) {
+    let mut version = 0;
+    let mut fields = Vec::new();
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        if key == "versao" {
+            version = value.parse().unwrap_or(0);
+        } else {
+            fields.push((key.to_string(), value.to_string()));
+        }
+    }
+    (version, fields)
+}
+
+// Renames or rewrites the `key: value` fields of a settings file
+type Migration = fn(&mut [(String, String)]);
+
+// Migration steps, one per schema version: the step at index `n` turns the
+// fields of version `n` into those of version `n + 1`
+const MIGRATIONS: [Migration; SETTINGS_VERSION as usize] =
+    [migrate_unversioned, migrate_languages, migrate_text_format];
+
+// Brings the fields of a file written in `version` to the current schema.
+// Files of newer versions are read as far as their keys are known.
+fn migrate(fields: &mut [(String, String)], version: u32) {
+    for step in MIGRATIONS.iter().skip(version as usize) {
+        step(fields);
+    }
+}
+
+// Version 0 to 1: unversioned files used "autosave" for the interval
+fn migrate_unversioned(fields: &mut [(String, String)]) {
+    for (key, _) in fields.iter_mut() {
+        if key == "autosave" {
+            *key = "autosalvamento".to_string();
+        }
+    }
+}
+
+// Version 1 to 2: adds the interface and prompt languages. Version 1 had only
+// Portuguese, which is also the default, so no field changes.
+fn migrate_languages(_fields: &mut [(String, String)]) {}
+
+// Version 2 to 3: adds the encoding and line breaks of files. Version 2 read
+// only UTF-8 and saved only LF, which detection and preserving cover.
+fn migrate_text_format(_fields: &mut [(String, String)]) {}
+
+// Empty values mean no folder was chosen
+fn optional_path(value: &str) -> Option<PathBuf> {
+    (!value.is_empty()).then(|| PathBuf::from(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_settings_round_trip() {
+        let settings = Settings {
+            save_dir: Some(PathBuf::from("/home/ana/prompts")),
+            file_name: "prompt.txt".to_string(),
+            library_dir: PathBuf::from("/home/ana/biblioteca"),
+            workspace_dir: None,
+            renderer: Renderer::Document,
+            autosave_seconds: 60,
+            tokenizer: Tokenizer::Words,
+            font_size: 16,
+            theme: Theme::Dark,
+            ui_language: Language::English,
+            output_language: Language::Spanish,
+            encoding: Some(TextEncoding::Windows1252),
+            line_endings: LineEndingPolicy::CrLf,
+            backup_count: 5,
+        };
+
+        assert_eq!(Settings::parse(&settings.to_text()), settings);
+        assert_eq!(Settings::parse(""), Settings::default());
+    }
+
+    #[test]
+    fn test_invalid_values_keep_defaults() {
+        let settings = Settings::parse(
+            "versao: 1\nrenderizador: html\ntamanho_fonte: 99\nautosalvamento: nunca\ntema: \nchave_nova: x\ncopias_de_seguranca: 99\n",
+        );
+
+        assert_eq!(settings.renderer, Renderer::Plain);
+        assert_eq!(settings.font_size, FONT_SIZE_RANGE.1);
+        assert_eq!(settings.autosave_seconds, AUTOSAVE_INTERVAL_SECONDS);
+        assert_eq!(settings.theme, Theme::System);
+        assert_eq!(settings.backup_count, MAX_BACKUP_COUNT);
+    }
+
+    #[test]
+    fn test_unversioned_file_is_migrated_and_written_back() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("config").join("configuracoes.txt");
+        let store = SettingsStore::new(&file);
+        assert_eq!(store.load().unwrap(), Settings::default());
+
+        // Arquivos sem versão usavam a chave "autosave"
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, "autosave: 120\ntema: escuro\n").unwrap();
+        let settings = store.load().unwrap();
+        assert_eq!(settings.autosave_seconds, 120);
+        assert_eq!(settings.theme, Theme::Dark);
+
+        let written = fs::read_to_string(&file).unwrap();
+        assert!(written.starts_with(&format!("versao: {}\n", SETTINGS_VERSION)));
+        assert!(written.contains("autosalvamento: 120"));
+        assert_eq!(store.load().unwrap(), settings);
+    }
+
+    #[test]
+    fn test_version_1_file_gets_the_default_languages() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("configuracoes.txt");
+        fs::write(&file, "versao: 1\ntema: claro\n").unwrap();
+
+        let settings = SettingsStore::new(&file).load().unwrap();
+        assert_eq!(settings.theme, Theme::Light);
+        assert_eq!(settings.ui_language, Language::Portuguese);
+        assert_eq!(settings.output_language, Language::Portuguese);
+        let written = fs::read_to_string(&file).unwrap();
+        assert!(written.starts_with(&format!("versao: {}\n", SETTINGS_VERSION)));
+        assert!(written.contains("idioma_interface: pt\n"));
+    }
+
+    #[test]
+    fn test_version_2_file_detects_encoding_and_keeps_line_breaks() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("configuracoes.txt");
+        fs::write(&file, "versao: 2\nidioma_prompt: en\n").unwrap();
+
+        let settings = SettingsStore::new(&file).load().unwrap();
+        assert_eq!(settings.output_language, Language::English);
+        assert_eq!(settings.encoding, None);
+        assert_eq!(settings.line_endings, LineEndingPolicy::Preserve);
+        let written = fs::read_to_string(&file).unwrap();
+        assert!(written.contains("codificacao: automatica\nfinais_de_linha: manter\n"));
+
+        assert_eq!(
+            LineEndingPolicy::Preserve.resolve(LineEnding::CrLf),
+            LineEnding::CrLf
+        );
+        assert_eq!(
+            LineEndingPolicy::Lf.resolve(LineEnding::CrLf),
+            LineEnding::Lf
+        );
+    }
+
+    #[test]
+    fn test_renderers() {
+        let mut data = PromptData::new();
+        data.metadata.title = "Revisão".to_string();
+        data.set_section_body(
+            crate::models::section::SectionKind::Context,
+            "Você é um revisor.".to_string(),
+        );
+
+        assert!(!Renderer::Plain
+            .render(&data, Language::English)
+            .contains("<START_"));
+        assert!(Renderer::Markers
+            .render(&data, Language::English)
+            .contains("<START_"));
+        assert!(Renderer::Document
+            .render(&data, Language::Portuguese)
+            .contains("## Contexto"));
+        assert!(Renderer::Document
+            .render(&data, Language::English)
+            .contains("## Context\n"));
+    }
+}
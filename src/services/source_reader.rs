@@ -0,0 +1,283 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::models::refactoring::{CodeReference, Refactoring};
+use crate::models::section::SectionKind;
+use std::fs;
+use std::path::Path;
+
+/// Detects the fenced code block language for a file based on its name or extension.
+/// Returns an empty string when the language is unknown.
+pub fn detect_language(path: &Path) -> &'static str {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match file_name.as_str() {
+        "dockerfile" => return "dockerfile",
+        "makefile" => return "makefile",
+        "cargo.lock" => return "toml",
+        _ => {}
+    }
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "rs" => "rust",
+        "py" => "python",
+        "ts" => "typescript",
+        "tsx" => "tsx",
+        "js" | "mjs" | "cjs" => "javascript",
+        "jsx" => "jsx",
+        "go" => "go",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "c" | "h" => "c",
+        "cpp" | "cc" | "cxx" | "hpp" | "hh" => "cpp",
+        "cs" => "csharp",
+        "rb" => "ruby",
+        "php" => "php",
+        "swift" => "swift",
+        "scala" => "scala",
+        "dart" => "dart",
+        "lua" => "lua",
+        "r" => "r",
+        "sh" | "bash" | "zsh" => "bash",
+        "ps1" => "powershell",
+        "bat" | "cmd" => "batch",
+        "sql" => "sql",
+        "html" | "htm" => "html",
+        "css" => "css",
+        "scss" => "scss",
+        "vue" => "vue",
+        "json" => "json",
+        "toml" => "toml",
+        "yaml" | "yml" => "yaml",
+        "xml" => "xml",
+        "md" | "markdown" => "markdown",
+        "slint" => "slint",
+        _ => "",
+    }
+}
+
+/// Reads a source file, optionally restricted to a 1-based inclusive line range.
+/// The end of the range is clamped to the length of the file.
+pub fn read_source(path: &Path, line_range: Option<(usize, usize)>) -> Result<String> {
+    let content = fs::read_to_string(path).with_path(path)?;
+
+    let Some((start, end)) = line_range else {
+        return Ok(content);
+    };
+
+    let lines: Vec<&str> = content.lines().collect();
+    if start == 0 || start > end || start > lines.len() {
+        return Err(Error::validation(format!(
+            "intervalo de linhas {}-{} inválido para arquivo com {} linhas",
+            start,
+            end,
+            lines.len()
+        ))
+        .with_path(path)
+        .with_line(start));
+    }
+
+    let end = end.min(lines.len());
+    Ok(lines[start - 1..end].join("\n"))
+}
+
+/// Wraps code in a fenced block, using a fence longer than any backtick run inside the code
+pub fn fenced_block(language: &str, code: &str) -> String {
+    let mut longest_run = 0;
+    let mut current_run = 0;
+    for c in code.chars() {
+        if c == '`' {
+            current_run += 1;
+            longest_run = longest_run.max(current_run);
+        } else {
+            current_run = 0;
+        }
+    }
+
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!(
+        "{}{}\n{}\n{}",
+        fence,
+        language,
+        code.trim_end_matches('\n'),
+        fence
+    )
+}
+
+/// Reads the code of a reference, failing with a template error of the
+/// refactoring section when the file or range cannot be read
+pub fn read_code(reference: &CodeReference) -> Result<String> {
+    read_source(&reference.path, reference.line_range).map_err(|e| {
+        Error::template(format!("não foi possível ler o código anexado: {}", e))
+            .with_section(SectionKind::Refactoring)
+    })
+}
+
+/// Renders the referenced code as a fenced block with a header naming the file
+pub fn render_code_reference(reference: &CodeReference) -> String {
+    let header = match reference.line_range {
+        Some((start, end)) => format!(
+            "Arquivo: `{}` (linhas {}-{})",
+            reference.path.display(),
+            start,
+            end
+        ),
+        None => format!("Arquivo: `{}`", reference.path.display()),
+    };
+
+    match read_source(&reference.path, reference.line_range) {
+        Ok(code) => format!(
+            "{}\n{}",
+            header,
+            fenced_block(detect_language(&reference.path), &code)
+        ),
+        Err(e) => format!("{}\n(não foi possível ler o arquivo: {})", header, e),
+    }
+}
+
+/// Renders the refactoring section as it goes into the prompt, reading the
+/// referenced files from disk
+pub fn render_refactoring(refactoring: &Refactoring) -> String {
+    let files: Vec<String> = refactoring
+        .files
+        .iter()
+        .map(render_code_reference)
+        .collect();
+    refactoring.render_with_files(&files)
+}
+
+/// Checks that every file referenced by the refactoring section can be read, so
+/// a broken reference is reported instead of ending up as a note inside the prompt
+pub fn check_code_references(refactoring: &Refactoring) -> Result<()> {
+    for reference in &refactoring.files {
+        read_code(reference)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_language_by_extension() {
+        assert_eq!(detect_language(Path::new("src/main.rs")), "rust");
+        assert_eq!(detect_language(Path::new("app.py")), "python");
+        assert_eq!(detect_language(Path::new("index.TS")), "typescript");
+        assert_eq!(detect_language(Path::new("ui/app-window.slint")), "slint");
+    }
+
+    #[test]
+    fn test_detect_language_by_file_name() {
+        assert_eq!(detect_language(Path::new("Dockerfile")), "dockerfile");
+        assert_eq!(detect_language(Path::new("build/Makefile")), "makefile");
+    }
+
+    #[test]
+    fn test_detect_language_unknown() {
+        assert_eq!(detect_language(Path::new("notas.xyz")), "");
+        assert_eq!(detect_language(Path::new("LICENSE")), "");
+    }
+
+    #[test]
+    fn test_read_source_full_file() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("lib.rs");
+        fs::write(&path, "linha 1\nlinha 2\nlinha 3\n").unwrap();
+
+        let content = read_source(&path, None).unwrap();
+        assert_eq!(content, "linha 1\nlinha 2\nlinha 3\n");
+    }
+
+    #[test]
+    fn test_read_source_line_range() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("lib.rs");
+        fs::write(&path, "linha 1\nlinha 2\nlinha 3\nlinha 4\n").unwrap();
+
+        assert_eq!(
+            read_source(&path, Some((2, 3))).unwrap(),
+            "linha 2\nlinha 3"
+        );
+        // Fim do intervalo é limitado ao tamanho do arquivo
+        assert_eq!(read_source(&path, Some((4, 99))).unwrap(), "linha 4");
+    }
+
+    #[test]
+    fn test_read_source_invalid_range() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("lib.rs");
+        fs::write(&path, "linha 1\nlinha 2\n").unwrap();
+
+        assert!(read_source(&path, Some((0, 1))).is_err());
+        assert!(read_source(&path, Some((3, 1))).is_err());
+        assert!(read_source(&path, Some((5, 6))).is_err());
+    }
+
+    #[test]
+    fn test_read_source_missing_file() {
+        let path = PathBuf::from("/caminho/inexistente/arquivo.rs");
+        assert!(read_source(&path, None).is_err());
+    }
+
+    #[test]
+    fn test_fenced_block() {
+        let block = fenced_block("rust", "fn main() {}\n");
+        assert_eq!(block, "```rust\nfn main() {}\n```");
+    }
+
+    #[test]
+    fn test_fenced_block_with_backticks_inside() {
+        let block = fenced_block("markdown", "Exemplo:\n```\ncódigo\n```");
+        assert!(block.starts_with("````markdown\n"));
+        assert!(block.ends_with("\n````"));
+    }
+
+    #[test]
+    fn test_render_refactoring_reads_files_at_render_time() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("lib.rs");
+        fs::write(&path, "fn a() {}\nfn b() {}\nfn c() {}\n").unwrap();
+
+        let mut refactoring = Refactoring::new();
+        refactoring
+            .files
+            .push(CodeReference::with_lines(&path, 2, 3));
+
+        // Alterações no arquivo após anexar aparecem no render
+        fs::write(&path, "fn a() {}\nfn b2() {}\nfn c() {}\n").unwrap();
+
+        let rendered = render_refactoring(&refactoring);
+        assert!(rendered.contains("(linhas 2-3)"));
+        assert!(rendered.contains("```rust\nfn b2() {}\nfn c() {}\n```"));
+        assert!(!rendered.contains("fn a()"));
+
+        // O corpo do documento continua guardando só a referência
+        assert!(!refactoring.render_body().contains("fn b2()"));
+    }
+
+    #[test]
+    fn test_render_refactoring_unreadable_file() {
+        let mut refactoring = Refactoring::new();
+        refactoring
+            .files
+            .push(CodeReference::new("/caminho/inexistente/main.rs"));
+
+        let rendered = render_refactoring(&refactoring);
+        assert!(rendered.contains("Arquivo: `/caminho/inexistente/main.rs`"));
+        assert!(rendered.contains("não foi possível ler o arquivo"));
+
+        let error = check_code_references(&refactoring).unwrap_err();
+        assert!(matches!(error, Error::Template { .. }));
+        assert_eq!(error.context().section, Some(SectionKind::Refactoring));
+        assert!(error.to_string().contains("/caminho/inexistente/main.rs"));
+    }
+}
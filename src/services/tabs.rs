@@ -0,0 +1,462 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::services::app_dirs;
+use crate::services::file_service::write_atomically;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Title of a tab whose document was never saved
+pub const UNTITLED_TAB: &str = "Sem título";
+
+/// Name of the file listing the tabs of a session
+const SESSION_FILE_NAME: &str = "sessao.txt";
+
+/// An open document of the workspace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tab<S> {
+    /// File the document was opened from or saved to
+    pub path: Option<PathBuf>,
+    /// The document has changes that were not saved yet
+    pub dirty: bool,
+    /// Editor contents kept while the tab is in the background; None for
+    /// the tab being shown, whose contents live in the editor
+    stash: Option<S>,
+}
+
+impl<S> Tab<S> {
+    /// Name shown in the tab bar: the file name, or "Sem título"
+    pub fn title(&self) -> String {
+        self.path
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| UNTITLED_TAB.to_string())
+    }
+
+    /// Editor contents of a background tab
+    pub fn stash(&self) -> Option<&S> {
+        self.stash.as_ref()
+    }
+}
+
+/// Outcome of closing a tab.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Closed<S> {
+    /// A background tab was closed; the editor keeps its contents
+    Background,
+    /// The shown tab was closed and its neighbour is shown now, with these
+    /// contents
+    Shown(Option<S>),
+}
+
+/// Open documents of the main window. One tab is shown in the editor at a
+/// time; switching tabs swaps the editor contents with the ones kept in the
+/// selected tab.
+#[derive(Debug, Clone)]
+pub struct Tabs<S> {
+    tabs: Vec<Tab<S>>,
+    active: usize,
+}
+
+impl<S> Tabs<S> {
+    /// Starts with one tab, shown in the editor
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            tabs: vec![Tab {
+                path,
+                dirty: false,
+                stash: None,
+            }],
+            active: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tabs.is_empty()
+    }
+
+    /// Index of the tab shown in the editor
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    pub fn tabs(&self) -> &[Tab<S>] {
+        &self.tabs
+    }
+
+    pub fn active_tab(&self) -> &Tab<S> {
+        &self.tabs[self.active]
+    }
+
+    pub fn active_tab_mut(&mut self) -> &mut Tab<S> {
+        &mut self.tabs[self.active]
+    }
+
+    /// Index of the tab holding the document at `path`
+    pub fn position_of(&self, path: &Path) -> Option<usize> {
+        self.tabs
+            .iter()
+            .position(|tab| tab.path.as_deref() == Some(path))
+    }
+
+    /// Opens a new tab right after the shown one and shows it. `shown` holds
+    /// the editor contents of the tab being left; the editor is then expected
+    /// to show the new document.
+    pub fn open(&mut self, path: Option<PathBuf>, shown: S) -> usize {
+        self.tabs[self.active].stash = Some(shown);
+        self.active += 1;
+        self.tabs.insert(
+            self.active,
+            Tab {
+                path,
+                dirty: false,
+                stash: None,
+            },
+        );
+        self.active
+    }
+
+    /// Adds a background tab after the others, as when restoring a session
+    pub fn push_background(&mut self, path: Option<PathBuf>, dirty: bool, contents: S) {
+        self.tabs.push(Tab {
+            path,
+            dirty,
+            stash: Some(contents),
+        });
+    }
+
+    /// Shows the tab at `index`. `shown` is called for the editor contents of
+    /// the tab being left, which are kept in it, and the contents of the
+    /// selected tab are returned. None, without calling `shown`, when the tab
+    /// is already shown or does not exist.
+    pub fn select(&mut self, index: usize, shown: impl FnOnce() -> S) -> Option<S> {
+        if index == self.active || index >= self.tabs.len() {
+            return None;
+        }
+        self.tabs[self.active].stash = Some(shown());
+        self.active = index;
+        self.tabs[index].stash.take()
+    }
+
+    /// Index of the tab after the shown one, wrapping around
+    pub fn next_index(&self) -> usize {
+        (self.active + 1) % self.tabs.len()
+    }
+
+    /// Index of the tab before the shown one, wrapping around
+    pub fn previous_index(&self) -> usize {
+        (self.active + self.tabs.len() - 1) % self.tabs.len()
+    }
+
+    /// Closes the tab at `index`. None when it does not exist or is the only
+    /// tab left.
+    pub fn close(&mut self, index: usize) -> Option<Closed<S>> {
+        if index >= self.tabs.len() || self.tabs.len() == 1 {
+            return None;
+        }
+
+        self.tabs.remove(index);
+        if index > self.active {
+            return Some(Closed::Background);
+        }
+        if index < self.active {
+            self.active -= 1;
+            return Some(Closed::Background);
+        }
+        self.active = index.min(self.tabs.len() - 1);
+        Some(Closed::Shown(self.tabs[self.active].stash.take()))
+    }
+
+    /// Closes every tab except the one at `index`. The contents of the kept
+    /// tab are returned when it was not the one shown.
+    pub fn close_others(&mut self, index: usize) -> Option<S> {
+        if index >= self.tabs.len() {
+            return None;
+        }
+        let mut kept = self.tabs.swap_remove(index);
+        self.tabs.clear();
+        let shown_next = kept.stash.take();
+        self.tabs.push(kept);
+        self.active = 0;
+        shown_next
+    }
+}
+
+/// A tab saved in a session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionTab {
+    pub path: Option<PathBuf>,
+    /// Editor contents, in the document format
+    pub document: String,
+    /// The contents differ from the file they came from
+    pub dirty: bool,
+}
+
+/// Tabs left open when the application closed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub tabs: Vec<SessionTab>,
+    /// Index of the tab shown when the session was saved
+    pub active: usize,
+}
+
+/// Folder holding the tabs of the last session, restored on the next launch.
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    dir: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// Store in the `sessao` folder of the user data directory
+    pub fn in_user_data_dir() -> Option<Self> {
+        app_dirs::data_dir().map(|dir| Self::new(&dir.join("sessao")))
+    }
+
+    /// Writes the session, replacing the previous one. Each tab goes to its
+    /// own file (`aba-1.txt`, ...) and the list is written last, so a crash
+    /// while saving keeps a readable session.
+    pub fn save(&self, session: &Session) -> Result<()> {
+        fs::create_dir_all(&self.dir).with_path(&self.dir)?;
+        for (index, tab) in session.tabs.iter().enumerate() {
+            let path = tab
+                .path
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default();
+            let content = format!(
+                "origem: {}\nalterada: {}\n\n{}",
+                path,
+                if tab.dirty { "sim" } else { "não" },
+                tab.document
+            );
+            write_atomically(&self.dir.join(tab_file_name(index)), content.as_bytes())?;
+        }
+        let list = format!("abas: {}\nativa: {}\n", session.tabs.len(), session.active);
+        write_atomically(&self.dir.join(SESSION_FILE_NAME), list.as_bytes())?;
+
+        // Tab files left by a larger session
+        let mut index = session.tabs.len();
+        while self.dir.join(tab_file_name(index)).exists() {
+            let file = self.dir.join(tab_file_name(index));
+            fs::remove_file(&file).with_path(&file)?;
+            index += 1;
+        }
+        Ok(())
+    }
+
+    /// Reads the last session; None when there is no session with tabs
+    pub fn load(&self) -> Result<Option<Session>> {
+        let list_file = self.dir.join(SESSION_FILE_NAME);
+        let list = match fs::read_to_string(&list_file) {
+            Ok(list) => list,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(Error::io(e, &list_file)),
+        };
+
+        let mut count = 0;
+        let mut active = 0;
+        for line in list.lines() {
+            match line.split_once(": ") {
+                Some(("abas", value)) => count = value.parse().unwrap_or(0),
+                Some(("ativa", value)) => active = value.parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+
+        let mut tabs = Vec::new();
+        for index in 0..count {
+            let file = self.dir.join(tab_file_name(index));
+            let content = match fs::read_to_string(&file) {
+                Ok(content) => content,
+                // A missing tab file only loses that tab
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(Error::io(e, &file)),
+            };
+            let Some((header, document)) = content.split_once("\n\n") else {
+                continue;
+            };
+            let mut tab = SessionTab {
+                path: None,
+                document: document.to_string(),
+                dirty: false,
+            };
+            for line in header.lines() {
+                match line.split_once(": ") {
+                    Some(("origem", value)) if !value.is_empty() => {
+                        tab.path = Some(PathBuf::from(value))
+                    }
+                    Some(("alterada", value)) => tab.dirty = value == "sim",
+                    _ => {}
+                }
+            }
+            tabs.push(tab);
+        }
+
+        if tabs.is_empty() {
+            return Ok(None);
+        }
+        let active = active.min(tabs.len() - 1);
+        Ok(Some(Session { tabs, active }))
+    }
+}
+
+// Name of the file holding the tab at `index`, counted from 1
+fn tab_file_name(index: usize) -> String {
+    format!("aba-{}.txt", index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::attachment::{Attachment, AttachmentKind};
+    use crate::models::auxiliary_content::AuxiliaryContent;
+    use crate::models::refactoring::{CodeReference, Refactoring, RefactoringGoal};
+    use crate::models::tests::{TestCase, TestKind, Tests};
+    use crate::services::prompt_generator::PromptData;
+    use crate::services::prompt_parser::parse_prompt;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_switching_tabs_swaps_contents() {
+        let mut tabs = Tabs::new(Some(PathBuf::from("/p/a.txt")));
+        assert_eq!(tabs.open(None, "a"), 1);
+        assert_eq!(tabs.active_tab().title(), UNTITLED_TAB);
+
+        // A aba deixada guarda o conteúdo do editor
+        assert_eq!(tabs.select(0, || "novo"), Some("a"));
+        assert_eq!(tabs.tabs()[1].stash(), Some(&"novo"));
+        assert_eq!(tabs.tabs()[0].title(), "a.txt");
+        assert_eq!(tabs.select(0, || unreachable!()), None);
+
+        assert_eq!(tabs.next_index(), 1);
+        assert_eq!(tabs.previous_index(), 1);
+        assert_eq!(tabs.position_of(Path::new("/p/a.txt")), Some(0));
+    }
+
+    #[test]
+    fn test_close_tabs() {
+        let mut tabs = Tabs::new(None);
+        tabs.open(Some(PathBuf::from("b.txt")), "a");
+        tabs.open(Some(PathBuf::from("c.txt")), "b");
+
+        // Fechar uma aba anterior mantém a aba mostrada
+        assert_eq!(tabs.close(0), Some(Closed::Background));
+        assert_eq!(tabs.active_tab().title(), "c.txt");
+
+        // Fechar a aba mostrada mostra a vizinha
+        assert_eq!(tabs.close(1), Some(Closed::Shown(Some("b"))));
+        assert_eq!(tabs.active(), 0);
+
+        // A última aba não é fechada
+        assert_eq!(tabs.close(0), None);
+        assert_eq!(tabs.len(), 1);
+    }
+
+    #[test]
+    fn test_close_others_keeps_one_tab() {
+        let mut tabs = Tabs::new(None);
+        tabs.open(Some(PathBuf::from("b.txt")), "a");
+        tabs.open(Some(PathBuf::from("c.txt")), "b");
+
+        assert_eq!(tabs.close_others(1), Some("b"));
+        assert_eq!(tabs.len(), 1);
+        assert_eq!(tabs.active_tab().title(), "b.txt");
+        assert_eq!(tabs.close_others(0), None);
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SessionStore::new(temp_dir.path());
+        assert_eq!(store.load().unwrap(), None);
+
+        let session = Session {
+            tabs: vec![
+                SessionTab {
+                    path: Some(PathBuf::from("/prompts/revisão.txt")),
+                    document: "## Contexto\n\nVocê é um revisor.".to_string(),
+                    dirty: false,
+                },
+                SessionTab {
+                    path: None,
+                    document: "rascunho\n\ncom parágrafos".to_string(),
+                    dirty: true,
+                },
+            ],
+            active: 1,
+        };
+        store.save(&session).unwrap();
+        assert_eq!(store.load().unwrap(), Some(session.clone()));
+
+        // Uma sessão menor remove os arquivos das abas que sobraram
+        let smaller = Session {
+            tabs: session.tabs[..1].to_vec(),
+            active: 5,
+        };
+        store.save(&smaller).unwrap();
+        assert!(!temp_dir.path().join("aba-2.txt").exists());
+        let loaded = store.load().unwrap().unwrap();
+        assert_eq!(loaded.tabs, smaller.tabs);
+        assert_eq!(loaded.active, 0);
+    }
+
+    #[test]
+    fn test_session_keeps_structured_sections_of_a_tab() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SessionStore::new(temp_dir.path());
+
+        let mut auxiliary = AuxiliaryContent::new();
+        auxiliary.data = "Código do projeto".to_string();
+        auxiliary.attachments = vec![Attachment::new("src", AttachmentKind::Directory)];
+        let mut refactoring = Refactoring::new();
+        refactoring.goals = vec![RefactoringGoal::Performance];
+        refactoring.files = vec![CodeReference::with_lines("src/lib.rs", 1, 20)];
+        let mut tests = Tests::new();
+        tests.framework = "cargo test".to_string();
+        tests.kinds = vec![TestKind::Unit];
+        tests.cases = vec![TestCase {
+            name: "Arquivo vazio".to_string(),
+            given: "um arquivo sem linhas".to_string(),
+            when: "ele é lido".to_string(),
+            then: "nenhuma seção é criada".to_string(),
+        }];
+        let mut data = PromptData::new();
+        data.auxiliary_content = Some(auxiliary.clone());
+        data.refactoring = Some(refactoring.clone());
+        data.tests = Some(tests.clone());
+
+        let session = Session {
+            tabs: vec![SessionTab {
+                path: None,
+                document: data.build_document(),
+                dirty: true,
+            }],
+            active: 0,
+        };
+        store.save(&session).unwrap();
+
+        // Anexos, objetivos e casos de teste voltam com a aba restaurada
+        let loaded = store.load().unwrap().unwrap();
+        let restored = parse_prompt(&loaded.tabs[0].document);
+        let restored_auxiliary = restored.auxiliary_content.unwrap();
+        assert_eq!(restored_auxiliary.data, auxiliary.data);
+        assert_eq!(restored_auxiliary.attachments, auxiliary.attachments);
+        let restored_refactoring = restored.refactoring.unwrap();
+        assert_eq!(restored_refactoring.goals, refactoring.goals);
+        assert_eq!(restored_refactoring.files, refactoring.files);
+        let restored_tests = restored.tests.unwrap();
+        assert_eq!(restored_tests.framework, tests.framework);
+        assert_eq!(restored_tests.kinds, tests.kinds);
+        assert_eq!(restored_tests.cases, tests.cases);
+    }
+}
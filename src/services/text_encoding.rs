@@ -0,0 +1,452 @@
+use crate::error::{Error, Result};
+use std::fs;
+use std::path::Path;
+
+// Byte order marks recognized at the start of a file
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+// Characters of the Windows-1252 bytes 0x80 to 0x9F; the five unassigned
+// bytes keep the control character of the same value, as in Latin-1
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+/// Character encoding of a text file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    /// UTF-8 starting with a byte order mark, as written by Notepad
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    /// Windows-1252, the Latin-1 superset used by Windows in the West
+    Windows1252,
+}
+
+impl TextEncoding {
+    /// All encodings, in the order shown in the UI
+    pub const ALL: [TextEncoding; 5] = [
+        TextEncoding::Utf8,
+        TextEncoding::Utf8Bom,
+        TextEncoding::Utf16Le,
+        TextEncoding::Utf16Be,
+        TextEncoding::Windows1252,
+    ];
+
+    /// Label shown in the UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf8Bom => "UTF-8 com BOM",
+            TextEncoding::Utf16Le => "UTF-16 LE",
+            TextEncoding::Utf16Be => "UTF-16 BE",
+            TextEncoding::Windows1252 => "Windows-1252 (Latin-1)",
+        }
+    }
+
+    /// Value stored in the settings file
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "utf-8",
+            TextEncoding::Utf8Bom => "utf-8-bom",
+            TextEncoding::Utf16Le => "utf-16le",
+            TextEncoding::Utf16Be => "utf-16be",
+            TextEncoding::Windows1252 => "windows-1252",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|encoding| encoding.as_str() == value)
+    }
+
+    /// Guesses the encoding of a file: a byte order mark first, then UTF-16
+    /// without one (every other byte zero), valid UTF-8 and finally
+    /// Windows-1252, which accepts any byte
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(UTF8_BOM) {
+            TextEncoding::Utf8Bom
+        } else if bytes.starts_with(UTF16_LE_BOM) {
+            TextEncoding::Utf16Le
+        } else if bytes.starts_with(UTF16_BE_BOM) {
+            TextEncoding::Utf16Be
+        } else if let Some(encoding) = detect_utf16_without_bom(bytes) {
+            encoding
+        } else if std::str::from_utf8(bytes).is_ok() {
+            TextEncoding::Utf8
+        } else {
+            TextEncoding::Windows1252
+        }
+    }
+
+    /// Checks that the bytes are valid in this encoding; the error names the
+    /// line of the first invalid sequence
+    pub fn check_decodable(&self, bytes: &[u8]) -> Result<()> {
+        let invalid_at = match self {
+            TextEncoding::Utf8 | TextEncoding::Utf8Bom => {
+                let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+                std::str::from_utf8(bytes)
+                    .err()
+                    .map(|e| line_of(&String::from_utf8_lossy(&bytes[..e.valid_up_to()])))
+            }
+            TextEncoding::Utf16Le => first_invalid_utf16_line(
+                bytes.strip_prefix(UTF16_LE_BOM).unwrap_or(bytes),
+                u16::from_le_bytes,
+            ),
+            TextEncoding::Utf16Be => first_invalid_utf16_line(
+                bytes.strip_prefix(UTF16_BE_BOM).unwrap_or(bytes),
+                u16::from_be_bytes,
+            ),
+            TextEncoding::Windows1252 => None,
+        };
+        match invalid_at {
+            Some(line) => {
+                Err(Error::encoding(*self, "sequência de bytes inválida").with_line(line))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Text of the bytes in this encoding, without the byte order mark.
+    /// Invalid sequences become the replacement character.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        match self {
+            TextEncoding::Utf8 | TextEncoding::Utf8Bom => {
+                String::from_utf8_lossy(bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes)).into_owned()
+            }
+            TextEncoding::Utf16Le => decode_utf16(
+                bytes.strip_prefix(UTF16_LE_BOM).unwrap_or(bytes),
+                u16::from_le_bytes,
+            ),
+            TextEncoding::Utf16Be => decode_utf16(
+                bytes.strip_prefix(UTF16_BE_BOM).unwrap_or(bytes),
+                u16::from_be_bytes,
+            ),
+            TextEncoding::Windows1252 => bytes
+                .iter()
+                .map(|&byte| match byte {
+                    0x80..=0x9F => WINDOWS_1252_HIGH[usize::from(byte - 0x80)],
+                    _ => char::from(byte),
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether every character of the text exists in this encoding
+    pub fn can_encode(&self, text: &str) -> bool {
+        self.check_encodable(text).is_ok()
+    }
+
+    /// Checks that every character of the text exists in this encoding; the
+    /// error names the first missing one and its line
+    pub fn check_encodable(&self, text: &str) -> Result<()> {
+        if *self != TextEncoding::Windows1252 {
+            return Ok(());
+        }
+        match text
+            .char_indices()
+            .find(|(_, c)| windows_1252_byte(*c).is_none())
+        {
+            Some((index, c)) => Err(Error::encoding(
+                *self,
+                format!("o caractere '{}' não existe nesta codificação", c),
+            )
+            .with_line(line_of(&text[..index]))),
+            None => Ok(()),
+        }
+    }
+
+    /// Bytes of the text in this encoding, with the byte order mark of the
+    /// encodings that have one. Characters missing from Windows-1252 are
+    /// written as `?`.
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        match self {
+            TextEncoding::Utf8 => text.as_bytes().to_vec(),
+            TextEncoding::Utf8Bom => [UTF8_BOM, text.as_bytes()].concat(),
+            TextEncoding::Utf16Le => UTF16_LE_BOM
+                .iter()
+                .copied()
+                .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+                .collect(),
+            TextEncoding::Utf16Be => UTF16_BE_BOM
+                .iter()
+                .copied()
+                .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+                .collect(),
+            TextEncoding::Windows1252 => text
+                .chars()
+                .map(|c| windows_1252_byte(c).unwrap_or(b'?'))
+                .collect(),
+        }
+    }
+}
+
+/// Line break of a text file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`, used by Linux and macOS
+    Lf,
+    /// `\r\n`, used by Windows
+    CrLf,
+}
+
+impl LineEnding {
+    /// Line break of the current operating system
+    pub fn native() -> Self {
+        if cfg!(windows) {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    /// Line break used by most lines of the text; Lf when it has no breaks
+    pub fn detect(text: &str) -> Self {
+        let breaks = text.matches('\n').count();
+        let crlf = text.matches("\r\n").count();
+        if crlf * 2 > breaks {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    /// Text with every `\n` written as this line break
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            LineEnding::Lf => text.to_string(),
+            LineEnding::CrLf => text.replace("\r\n", "\n").replace('\n', "\r\n"),
+        }
+    }
+
+    /// Short name shown in the status bar
+    pub fn label(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "LF",
+            LineEnding::CrLf => "CRLF",
+        }
+    }
+}
+
+/// Encoding and line break of a file, kept to write it back the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextFormat {
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+}
+
+impl Default for TextFormat {
+    /// UTF-8 with the line break of the current operating system
+    fn default() -> Self {
+        Self {
+            encoding: TextEncoding::Utf8,
+            line_ending: LineEnding::native(),
+        }
+    }
+}
+
+impl TextFormat {
+    /// Bytes of a text with `\n` line breaks, written in this format
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        self.encoding.encode(&self.line_ending.apply(text))
+    }
+
+    /// This format, or UTF-8 with the same line breaks when the encoding
+    /// cannot hold every character of the text
+    pub fn for_text(&self, text: &str) -> Self {
+        if self.encoding.can_encode(text) {
+            *self
+        } else {
+            Self {
+                encoding: TextEncoding::Utf8,
+                ..*self
+            }
+        }
+    }
+
+    /// Description shown in the status bar, such as `UTF-8 · CRLF`
+    pub fn label(&self) -> String {
+        format!("{} · {}", self.encoding.label(), self.line_ending.label())
+    }
+}
+
+/// Text read from a file, with `\n` line breaks, and the format it was in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedText {
+    pub text: String,
+    pub format: TextFormat,
+}
+
+/// Decodes the bytes of a text file in `encoding`, or in the detected one
+/// when None, turning `\r\n` line breaks into `\n`. Bytes that are invalid
+/// in the encoding are an error rather than replaced.
+pub fn decode_text(bytes: &[u8], encoding: Option<TextEncoding>) -> Result<DecodedText> {
+    let encoding = encoding.unwrap_or_else(|| TextEncoding::detect(bytes));
+    encoding.check_decodable(bytes)?;
+    let text = encoding.decode(bytes);
+    let line_ending = LineEnding::detect(&text);
+    Ok(DecodedText {
+        text: text.replace("\r\n", "\n"),
+        format: TextFormat {
+            encoding,
+            line_ending,
+        },
+    })
+}
+
+/// Reads a text file in any supported encoding; see `decode_text`
+pub fn read_text_file(path: &Path, encoding: Option<TextEncoding>) -> Result<DecodedText> {
+    let bytes = fs::read(path).map_err(|e| Error::io(e, path))?;
+    decode_text(&bytes, encoding).map_err(|e| e.with_path(path))
+}
+
+// Line of the character following a text, counted from 1
+fn line_of(text_before: &str) -> usize {
+    text_before.matches('\n').count() + 1
+}
+
+// UTF-16 without a byte order mark: in text that is mostly ASCII, one byte of
+// every pair is zero, the second one in little endian and the first in big
+// endian
+fn detect_utf16_without_bom(bytes: &[u8]) -> Option<TextEncoding> {
+    let pairs: Vec<&[u8]> = bytes.chunks_exact(2).take(512).collect();
+    if pairs.len() < 2 || !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let zero_first = pairs.iter().filter(|pair| pair[0] == 0).count();
+    let zero_second = pairs.iter().filter(|pair| pair[1] == 0).count();
+    let mostly = |count: usize| count * 10 >= pairs.len() * 4;
+    let rarely = |count: usize| count * 20 <= pairs.len();
+    if mostly(zero_second) && rarely(zero_first) {
+        Some(TextEncoding::Utf16Le)
+    } else if mostly(zero_first) && rarely(zero_second) {
+        Some(TextEncoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+// Decodes UTF-16 code units read with `unit`, ignoring a trailing odd byte
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> String {
+    let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+// Line of the first unpaired surrogate of UTF-16 code units read with `unit`
+fn first_invalid_utf16_line(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> Option<usize> {
+    let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    let mut line = 1;
+    char::decode_utf16(units).find_map(|c| match c {
+        Ok('\n') => {
+            line += 1;
+            None
+        }
+        Ok(_) => None,
+        Err(_) => Some(line),
+    })
+}
+
+// Windows-1252 byte of a character, if it has one
+fn windows_1252_byte(c: char) -> Option<u8> {
+    match u32::from(c) {
+        0x00..=0x7F | 0xA0..=0xFF => u8::try_from(u32::from(c)).ok(),
+        _ => WINDOWS_1252_HIGH
+            .iter()
+            .position(|&high| high == c)
+            .and_then(|index| u8::try_from(0x80 + index).ok()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_encodings() {
+        let text = "Instruções: revise o código";
+        for encoding in TextEncoding::ALL {
+            let bytes = encoding.encode(text);
+            assert_eq!(TextEncoding::detect(&bytes), encoding, "{:?}", encoding);
+            assert_eq!(encoding.decode(&bytes), text);
+        }
+        // UTF-16 sem BOM também é reconhecido
+        let without_bom = &TextEncoding::Utf16Le.encode(text)[2..];
+        assert_eq!(TextEncoding::detect(without_bom), TextEncoding::Utf16Le);
+    }
+
+    #[test]
+    fn test_windows_1252_special_characters() {
+        let bytes = [0x93, b'o', b'l', 0xE1, 0x94, b' ', 0x80, b'5', 0x85];
+        assert_eq!(TextEncoding::Windows1252.decode(&bytes), "“olá” €5…");
+        assert_eq!(
+            TextEncoding::Windows1252.encode("“olá” €5…"),
+            bytes.to_vec()
+        );
+
+        assert!(TextEncoding::Windows1252.can_encode("ação – “ok”"));
+        assert!(!TextEncoding::Windows1252.can_encode("🚀"));
+        assert_eq!(TextEncoding::Windows1252.encode("a🚀"), b"a?".to_vec());
+
+        let format = TextFormat {
+            encoding: TextEncoding::Windows1252,
+            line_ending: LineEnding::CrLf,
+        };
+        assert_eq!(format.for_text("ação"), format);
+        assert_eq!(format.for_text("🚀").encoding, TextEncoding::Utf8);
+        assert_eq!(format.for_text("🚀").line_ending, LineEnding::CrLf);
+    }
+
+    #[test]
+    fn test_line_endings_are_detected_and_restored() {
+        let decoded = decode_text(b"um\r\ndois\r\ntr\xEAs\r\n", None).unwrap();
+        assert_eq!(decoded.text, "um\ndois\ntrês\n");
+        assert_eq!(
+            decoded.format,
+            TextFormat {
+                encoding: TextEncoding::Windows1252,
+                line_ending: LineEnding::CrLf,
+            }
+        );
+        assert_eq!(
+            decoded.format.encode(&decoded.text),
+            b"um\r\ndois\r\ntr\xEAs\r\n"
+        );
+
+        assert_eq!(LineEnding::detect("a\nb\r\nc\n"), LineEnding::Lf);
+        assert_eq!(LineEnding::detect("sem quebras"), LineEnding::Lf);
+        assert_eq!(LineEnding::CrLf.apply("a\r\nb\n"), "a\r\nb\r\n");
+    }
+
+    #[test]
+    fn test_forced_encoding_overrides_detection() {
+        // "ção" em UTF-8 lido como Windows-1252 vira mojibake, como pedido
+        let decoded = decode_text("ção".as_bytes(), Some(TextEncoding::Windows1252)).unwrap();
+        assert_eq!(decoded.text, "Ã§Ã£o");
+        assert_eq!(decoded.format.encoding, TextEncoding::Windows1252);
+    }
+
+    #[test]
+    fn test_invalid_text_is_an_encoding_error_with_its_line() {
+        let error = decode_text(b"um\ndois\ntr\xEAs", Some(TextEncoding::Utf8)).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Encoding {
+                encoding: TextEncoding::Utf8,
+                ..
+            }
+        ));
+        assert_eq!(error.context().line, Some(3));
+
+        let error = TextEncoding::Windows1252
+            .check_encodable("ok\nfoguete 🚀")
+            .unwrap_err();
+        assert_eq!(error.context().line, Some(2));
+        assert!(error.to_string().contains('🚀'));
+    }
+}
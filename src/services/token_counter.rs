@@ -0,0 +1,82 @@
+/// Estimates the number of tokens in a text.
+/// Uses the common approximation of four characters per token, which is
+/// close enough for budgeting without shipping a model-specific tokenizer.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Way of estimating token counts chosen in the settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tokenizer {
+    /// Four characters per token, as [`estimate_tokens`]
+    Characters,
+    /// Four tokens for every three words, closer for prose in English
+    Words,
+}
+
+impl Tokenizer {
+    /// All tokenizers, in the order shown in the UI
+    pub const ALL: [Tokenizer; 2] = [Tokenizer::Characters, Tokenizer::Words];
+
+    /// Estimated number of tokens in a text
+    pub fn count(&self, text: &str) -> usize {
+        match self {
+            Tokenizer::Characters => estimate_tokens(text),
+            Tokenizer::Words => (text.split_whitespace().count() * 4).div_ceil(3),
+        }
+    }
+
+    /// Label shown in the UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            Tokenizer::Characters => "Caracteres (≈ 4 por token)",
+            Tokenizer::Words => "Palavras (≈ 3 para 4 tokens)",
+        }
+    }
+
+    /// Value stored in the settings file
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Tokenizer::Characters => "caracteres",
+            Tokenizer::Words => "palavras",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|tokenizer| tokenizer.as_str() == value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_tokens_empty() {
+        assert_eq!(estimate_tokens(""), 0);
+    }
+
+    #[test]
+    fn test_estimate_tokens_rounds_up() {
+        assert_eq!(estimate_tokens("a"), 1);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+
+    #[test]
+    fn test_estimate_tokens_counts_characters_not_bytes() {
+        // "ação" tem 4 caracteres mas 6 bytes
+        assert_eq!(estimate_tokens("ação"), 1);
+    }
+
+    #[test]
+    fn test_word_tokenizer() {
+        assert_eq!(Tokenizer::Words.count(""), 0);
+        assert_eq!(Tokenizer::Words.count("um dois três"), 4);
+        assert_eq!(Tokenizer::Characters.count("abcde"), 2);
+        assert_eq!(Tokenizer::parse("palavras"), Some(Tokenizer::Words));
+        assert_eq!(Tokenizer::parse("outro"), None);
+    }
+}
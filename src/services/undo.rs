@@ -0,0 +1,184 @@
+/// Number of steps kept by default; older ones are dropped
+pub const DEFAULT_UNDO_LIMIT: usize = 100;
+
+/// A state left behind by a change, and the change that left it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UndoStep<T> {
+    /// Description of the change, shown in the history list
+    pub label: String,
+    pub state: T,
+}
+
+/// Undo and redo history of a whole document. Discrete operations are
+/// committed as they happen; typing is picked up by periodic observation and
+/// becomes one step once it pauses.
+#[derive(Debug, Clone)]
+pub struct UndoHistory<T> {
+    current: T,
+    undo: Vec<UndoStep<T>>,
+    redo: Vec<UndoStep<T>>,
+    /// State seen by the last observation and not committed yet
+    observed: Option<T>,
+    limit: usize,
+}
+
+impl<T: Clone + PartialEq> UndoHistory<T> {
+    pub fn new(initial: T, limit: usize) -> Self {
+        Self {
+            current: initial,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            observed: None,
+            limit: limit.max(1),
+        }
+    }
+
+    /// State after the last committed change
+    pub fn current(&self) -> &T {
+        &self.current
+    }
+
+    /// Records a change to `state`. Returns false, recording nothing, when the
+    /// state did not change. A new change drops the steps that could be redone.
+    pub fn commit(&mut self, label: &str, state: T) -> bool {
+        self.observed = None;
+        if state == self.current {
+            return false;
+        }
+
+        let previous = std::mem::replace(&mut self.current, state);
+        self.undo.push(UndoStep {
+            label: label.to_string(),
+            state: previous,
+        });
+        if self.undo.len() > self.limit {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        true
+    }
+
+    /// Feeds the state seen by a periodic check. A change is committed once
+    /// two checks in a row see the same state, so continuous typing becomes
+    /// a single step; `label` describes the change from the current state.
+    /// Returns true when a step was committed.
+    pub fn observe(&mut self, state: T, label: impl FnOnce(&T, &T) -> String) -> bool {
+        if state == self.current {
+            self.observed = None;
+            return false;
+        }
+        if self.observed.as_ref() != Some(&state) {
+            self.observed = Some(state);
+            return false;
+        }
+
+        let label = label(&self.current, &state);
+        self.commit(&label, state)
+    }
+
+    /// Goes back one step and returns the state to show
+    pub fn undo(&mut self) -> Option<T> {
+        let step = self.undo.pop()?;
+        let newer = std::mem::replace(&mut self.current, step.state);
+        self.redo.push(UndoStep {
+            label: step.label,
+            state: newer,
+        });
+        self.observed = None;
+        Some(self.current.clone())
+    }
+
+    /// Reapplies the last undone step and returns the state to show
+    pub fn redo(&mut self) -> Option<T> {
+        let step = self.redo.pop()?;
+        let older = std::mem::replace(&mut self.current, step.state);
+        self.undo.push(UndoStep {
+            label: step.label,
+            state: older,
+        });
+        self.observed = None;
+        Some(self.current.clone())
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Changes that can be undone, the most recent first
+    pub fn undo_labels(&self) -> Vec<String> {
+        self.undo
+            .iter()
+            .rev()
+            .map(|step| step.label.clone())
+            .collect()
+    }
+
+    /// Changes that can be redone, the next one first
+    pub fn redo_labels(&self) -> Vec<String> {
+        self.redo
+            .iter()
+            .rev()
+            .map(|step| step.label.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_and_redo_steps() {
+        let mut history = UndoHistory::new("".to_string(), DEFAULT_UNDO_LIMIT);
+        assert!(history.commit("Abrir arquivo", "a".to_string()));
+        assert!(history.commit("Limpar Tudo", "".to_string()));
+        assert!(!history.commit("Nada", "".to_string()));
+
+        assert_eq!(history.undo_labels(), vec!["Limpar Tudo", "Abrir arquivo"]);
+        assert_eq!(history.undo().as_deref(), Some("a"));
+        assert_eq!(history.undo().as_deref(), Some(""));
+        assert_eq!(history.undo(), None);
+
+        assert_eq!(history.redo_labels(), vec!["Abrir arquivo", "Limpar Tudo"]);
+        assert_eq!(history.redo().as_deref(), Some("a"));
+        assert!(history.can_undo() && history.can_redo());
+
+        // Uma nova alteração descarta o que podia ser refeito
+        history.commit("Edição", "b".to_string());
+        assert!(!history.can_redo());
+        assert_eq!(history.current(), "b");
+    }
+
+    #[test]
+    fn test_observed_typing_becomes_one_step() {
+        let mut history = UndoHistory::new(String::new(), DEFAULT_UNDO_LIMIT);
+        let label = |_: &String, _: &String| "Edição".to_string();
+
+        // Enquanto o texto muda a cada verificação nada é registrado
+        assert!(!history.observe("o".to_string(), label));
+        assert!(!history.observe("ol".to_string(), label));
+        assert!(!history.observe("olá".to_string(), label));
+        assert!(history.observe("olá".to_string(), label));
+
+        assert_eq!(history.undo_labels(), vec!["Edição"]);
+        assert_eq!(history.undo().as_deref(), Some(""));
+        // Após desfazer o estado mostrado não é registrado de novo
+        assert!(!history.observe(String::new(), label));
+    }
+
+    #[test]
+    fn test_history_keeps_limited_steps() {
+        let mut history = UndoHistory::new(0, 3);
+        for value in 1..=5 {
+            history.commit(&format!("passo {}", value), value);
+        }
+
+        assert_eq!(history.undo_labels(), vec!["passo 5", "passo 4", "passo 3"]);
+        while history.undo().is_some() {}
+        assert_eq!(*history.current(), 2);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::error::{IoResultExt, Result};
+use crate::services::prompt_library::{fold_text, PROMPT_EXTENSIONS};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A folder or prompt file below the workspace folder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceNode {
+    pub path: PathBuf,
+    /// Path relative to the workspace folder, always with `/` separators
+    pub relative: String,
+    /// Number of folders between the workspace folder and the node
+    pub depth: usize,
+    pub is_dir: bool,
+}
+
+impl WorkspaceNode {
+    /// File or folder name shown in the tree
+    pub fn name(&self) -> &str {
+        self.relative.rsplit('/').next().unwrap_or(&self.relative)
+    }
+}
+
+/// A node shown in the workspace tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceRow<'a> {
+    pub node: &'a WorkspaceNode,
+    /// Folders only: whether their contents are shown below them
+    pub expanded: bool,
+}
+
+/// Folder of prompt files shown as a tree in the workspace sidebar.
+/// Folders without prompt files are left out.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    root: PathBuf,
+    /// Folders first, then files, each sorted by name, in tree order
+    nodes: Vec<WorkspaceNode>,
+    collapsed: BTreeSet<PathBuf>,
+}
+
+impl Workspace {
+    /// Scans the prompt files below `root`
+    pub fn open(root: &Path) -> Result<Self> {
+        let mut workspace = Self {
+            root: root.to_path_buf(),
+            nodes: Vec::new(),
+            collapsed: BTreeSet::new(),
+        };
+        workspace.refresh()?;
+        Ok(workspace)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn nodes(&self) -> &[WorkspaceNode] {
+        &self.nodes
+    }
+
+    /// Scans the folder again, keeping the collapsed folders that still exist
+    pub fn refresh(&mut self) -> Result<()> {
+        let mut nodes = Vec::new();
+        scan_dir(&self.root, &self.root, 0, &mut nodes)?;
+        self.collapsed
+            .retain(|path| nodes.iter().any(|node| node.is_dir && node.path == *path));
+        self.nodes = nodes;
+        Ok(())
+    }
+
+    /// Shows or hides the contents of a folder
+    pub fn toggle(&mut self, folder: &Path) {
+        if !self.collapsed.remove(folder) {
+            self.collapsed.insert(folder.to_path_buf());
+        }
+    }
+
+    /// Rows of the tree. With a filter, only files whose path contains it
+    /// (ignoring case and accents) are shown, with their folders expanded.
+    pub fn rows(&self, filter: &str) -> Vec<WorkspaceRow<'_>> {
+        let filter = fold_text(filter.trim());
+        if !filter.is_empty() {
+            return self.filtered_rows(&filter);
+        }
+
+        let mut rows = Vec::new();
+        // Depth below which nodes are hidden by a collapsed folder
+        let mut hidden_below: Option<usize> = None;
+        for node in &self.nodes {
+            if let Some(depth) = hidden_below {
+                if node.depth > depth {
+                    continue;
+                }
+                hidden_below = None;
+            }
+            let expanded = node.is_dir && !self.collapsed.contains(&node.path);
+            if node.is_dir && !expanded {
+                hidden_below = Some(node.depth);
+            }
+            rows.push(WorkspaceRow { node, expanded });
+        }
+        rows
+    }
+
+    // Files matching the folded filter and the folders leading to them
+    fn filtered_rows(&self, filter: &str) -> Vec<WorkspaceRow<'_>> {
+        let matches: Vec<&WorkspaceNode> = self
+            .nodes
+            .iter()
+            .filter(|node| !node.is_dir && fold_text(&node.relative).contains(filter))
+            .collect();
+
+        self.nodes
+            .iter()
+            .filter(|node| {
+                if node.is_dir {
+                    let prefix = format!("{}/", node.relative);
+                    matches
+                        .iter()
+                        .any(|file| file.relative.starts_with(&prefix))
+                } else {
+                    matches.contains(node)
+                }
+            })
+            .map(|node| WorkspaceRow {
+                node,
+                expanded: node.is_dir,
+            })
+            .collect()
+    }
+}
+
+// Adds the folders and prompt files of `dir` to `nodes` in tree order,
+// skipping hidden entries and folders without prompt files. Returns whether
+// anything was added. Only an unreadable `dir` is an error: unreadable
+// entries and subfolders are left out, and symlinked folders are not followed
+// so that a link back to an ancestor cannot recurse forever.
+fn scan_dir(root: &Path, dir: &Path, depth: usize, nodes: &mut Vec<WorkspaceNode>) -> Result<bool> {
+    let mut folders = Vec::new();
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_path(dir)? {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let path = entry.path();
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            if entry.file_type().map_or(true, |kind| kind.is_symlink()) {
+                continue;
+            }
+            folders.push(path);
+        } else if is_prompt_file(&path) {
+            files.push(path);
+        }
+    }
+    folders.sort();
+    files.sort();
+
+    let start = nodes.len();
+    for folder in folders {
+        let index = nodes.len();
+        nodes.push(node(root, folder.clone(), depth, true));
+        if !scan_dir(root, &folder, depth + 1, nodes).unwrap_or(false) {
+            nodes.truncate(index);
+        }
+    }
+    for file in files {
+        nodes.push(node(root, file, depth, false));
+    }
+    Ok(nodes.len() > start)
+}
+
+// Tree node for a path below the workspace folder
+fn node(root: &Path, path: PathBuf, depth: usize, is_dir: bool) -> WorkspaceNode {
+    let relative = path
+        .strip_prefix(root)
+        .unwrap_or(&path)
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    WorkspaceNode {
+        path,
+        relative,
+        depth,
+        is_dir,
+    }
+}
+
+// Whether a file has one of the prompt file extensions
+fn is_prompt_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| PROMPT_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    // Pasta com prompts em subpastas, um arquivo que não é prompt e uma pasta oculta
+    fn sample_workspace() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("revisão/código")).unwrap();
+        fs::create_dir_all(root.join("vazia")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("geral.txt"), "").unwrap();
+        fs::write(root.join("notas.pdf"), "").unwrap();
+        fs::write(root.join("revisão/pr.md"), "").unwrap();
+        fs::write(root.join("revisão/código/rust.txt"), "").unwrap();
+        fs::write(root.join(".git/config.txt"), "").unwrap();
+        temp_dir
+    }
+
+    fn relative_paths(rows: &[WorkspaceRow]) -> Vec<String> {
+        rows.iter().map(|row| row.node.relative.clone()).collect()
+    }
+
+    #[test]
+    fn test_tree_lists_folders_then_prompt_files() {
+        let temp_dir = sample_workspace();
+        let workspace = Workspace::open(temp_dir.path()).unwrap();
+
+        let rows = workspace.rows("");
+        assert_eq!(
+            relative_paths(&rows),
+            vec![
+                "revisão",
+                "revisão/código",
+                "revisão/código/rust.txt",
+                "revisão/pr.md",
+                "geral.txt"
+            ]
+        );
+        assert_eq!(rows[2].node.depth, 2);
+        assert_eq!(rows[2].node.name(), "rust.txt");
+        assert!(rows[0].expanded);
+    }
+
+    #[test]
+    fn test_collapsed_folder_hides_its_contents() {
+        let temp_dir = sample_workspace();
+        let mut workspace = Workspace::open(temp_dir.path()).unwrap();
+
+        workspace.toggle(&temp_dir.path().join("revisão"));
+        let rows = workspace.rows("");
+        assert_eq!(relative_paths(&rows), vec!["revisão", "geral.txt"]);
+        assert!(!rows[0].expanded);
+
+        workspace.toggle(&temp_dir.path().join("revisão"));
+        assert_eq!(workspace.rows("").len(), 5);
+    }
+
+    #[test]
+    fn test_filter_keeps_matching_files_and_their_folders() {
+        let temp_dir = sample_workspace();
+        let mut workspace = Workspace::open(temp_dir.path()).unwrap();
+        workspace.toggle(&temp_dir.path().join("revisão"));
+
+        // O filtro ignora acentos e mostra as pastas expandidas
+        assert_eq!(
+            relative_paths(&workspace.rows("CODIGO")),
+            vec!["revisão", "revisão/código", "revisão/código/rust.txt"]
+        );
+        assert!(workspace.rows("inexistente").is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_folders_are_not_followed() {
+        let temp_dir = sample_workspace();
+        // Link de volta para a raiz, que recursaria para sempre
+        std::os::unix::fs::symlink(temp_dir.path(), temp_dir.path().join("revisão/loop")).unwrap();
+
+        let workspace = Workspace::open(temp_dir.path()).unwrap();
+        assert_eq!(workspace.rows("").len(), 5);
+    }
+}
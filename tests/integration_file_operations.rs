@@ -1,12 +1,15 @@
 //! Testes de integração para operações de arquivo
 //!
 //! Estes testes verificam a integração entre geração de prompts,
-//! salvamento de arquivos e carregamento de prompts salvos.
+//! salvamento de arquivos e carregamento de prompts salvos, inclusive em
+//! outras codificações e com finais de linha do Windows.
 
 use prompt_builder_gui::models::{context::Context, few_shot::FewShot, main_content::MainContent};
 use prompt_builder_gui::services::{
-    file_service::{save_prompt_to_file, save_prompt_to_specific_path},
+    file_service::{save_prompt_in_format, save_prompt_to_file, save_prompt_to_specific_path},
     prompt_generator::PromptData,
+    prompt_parser::parse_prompt,
+    text_encoding::{read_text_file, LineEnding, TextEncoding, TextFormat},
 };
 use std::fs;
 use std::path::Path;
@@ -337,3 +340,40 @@ fn test_overwrite_existing_file() {
     assert!(content2.contains("completamente diferente"));
     assert!(!content2.contains("assistente de programação"));
 }
+
+#[test]
+fn test_files_in_other_encodings_open_and_save_back_the_same_way() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let document = "## Contexto\nVocê é um revisor de código.\n\n## Conteúdo Principal\nAponte os erros de lógica.\n";
+
+    for encoding in TextEncoding::ALL {
+        let format = TextFormat {
+            encoding,
+            line_ending: LineEnding::CrLf,
+        };
+        let file_path = temp_dir.path().join(format!("{}.txt", encoding.as_str()));
+        save_prompt_in_format(document, file_path.to_str().unwrap(), format, 0)
+            .expect("Failed to save file");
+
+        // O arquivo é lido sem saber a codificação e volta com as mesmas seções
+        let decoded = read_text_file(&file_path, None).expect("Failed to read file");
+        assert_eq!(decoded.format, format, "{}", encoding.label());
+        assert_eq!(decoded.text, document);
+        let data = parse_prompt(&decoded.text);
+        assert_eq!(
+            data.context.unwrap().description,
+            "Você é um revisor de código."
+        );
+
+        // Salvar de novo no formato lido mantém os bytes do arquivo
+        let original = fs::read(&file_path).unwrap();
+        save_prompt_in_format(
+            &decoded.text,
+            file_path.to_str().unwrap(),
+            decoded.format,
+            0,
+        )
+        .expect("Failed to save file again");
+        assert_eq!(fs::read(&file_path).unwrap(), original);
+    }
+}
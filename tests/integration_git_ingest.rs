@@ -0,0 +1,154 @@
+//! Testes de integração para a importação de contexto do Git
+//!
+//! Estes testes criam repositórios de fixture em diretórios temporários
+//! e verificam o texto gerado para cada fonte de contexto.
+
+use prompt_builder_gui::services::git_ingest::{ingest, GitSource};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Executa um comando git no repositório de fixture
+fn git(repo: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .env("GIT_AUTHOR_DATE", "2024-05-01T12:00:00")
+        .env("GIT_COMMITTER_DATE", "2024-05-01T12:00:00")
+        .status()
+        .expect("git deve estar disponível");
+    assert!(status.success(), "git {:?} falhou", args);
+}
+
+/// Cria um repositório com dois commits: a criação e a alteração de lib.rs
+fn create_fixture_repo() -> TempDir {
+    let temp_dir = TempDir::new().expect("Falha ao criar diretório temporário");
+    let repo = temp_dir.path();
+
+    git(repo, &["init", "-q"]);
+    git(repo, &["config", "user.name", "Ana Teste"]);
+    git(repo, &["config", "user.email", "ana@example.com"]);
+    git(repo, &["config", "commit.gpgsign", "false"]);
+
+    fs::write(repo.join("lib.rs"), "fn main() {}\n").unwrap();
+    git(repo, &["add", "."]);
+    git(repo, &["commit", "-q", "-m", "Cria lib.rs"]);
+    git(repo, &["tag", "v1"]);
+
+    fs::write(
+        repo.join("lib.rs"),
+        "fn main() {\n    println!(\"olá\");\n}\n",
+    )
+    .unwrap();
+    git(repo, &["commit", "-q", "-am", "Imprime saudação"]);
+
+    temp_dir
+}
+
+#[test]
+fn test_clean_repository_has_no_changes() {
+    let fixture = create_fixture_repo();
+
+    let staged = ingest(fixture.path(), &GitSource::StagedDiff).unwrap();
+    let unstaged = ingest(fixture.path(), &GitSource::UnstagedDiff).unwrap();
+    let files = ingest(fixture.path(), &GitSource::ChangedFiles).unwrap();
+
+    assert_eq!(staged, "Alterações staged: (sem alterações)");
+    assert_eq!(unstaged, "Alterações não staged: (sem alterações)");
+    assert_eq!(files, "Arquivos alterados: (nenhum)");
+}
+
+#[test]
+fn test_staged_and_unstaged_diffs_are_separated() {
+    let fixture = create_fixture_repo();
+    let repo = fixture.path();
+
+    fs::write(repo.join("novo.rs"), "pub fn novo() {}\n").unwrap();
+    git(repo, &["add", "novo.rs"]);
+    fs::write(repo.join("lib.rs"), "fn main() {}\n").unwrap();
+
+    let staged = ingest(repo, &GitSource::StagedDiff).unwrap();
+    assert!(staged.starts_with("Alterações staged:\n```diff\n"));
+    assert!(staged.contains("+pub fn novo() {}"));
+    assert!(!staged.contains("lib.rs"));
+    assert!(staged.ends_with("\n```"));
+
+    let unstaged = ingest(repo, &GitSource::UnstagedDiff).unwrap();
+    assert!(unstaged.contains("-    println!(\"olá\");"));
+    assert!(!unstaged.contains("novo.rs"));
+}
+
+#[test]
+fn test_diff_between_refs() {
+    let fixture = create_fixture_repo();
+
+    let source = GitSource::RefDiff {
+        from: "v1".to_string(),
+        to: "HEAD".to_string(),
+    };
+    let diff = ingest(fixture.path(), &source).unwrap();
+
+    assert!(diff.starts_with("Diff entre v1 e HEAD:\n```diff\n"));
+    assert!(diff.contains("+    println!(\"olá\");"));
+}
+
+#[test]
+fn test_diff_with_unknown_ref_reports_git_error() {
+    let fixture = create_fixture_repo();
+
+    let source = GitSource::RefDiff {
+        from: "nao-existe".to_string(),
+        to: "HEAD".to_string(),
+    };
+    let error = ingest(fixture.path(), &source).unwrap_err();
+
+    // A mensagem do git vem depois do repositório em que ele rodou
+    assert_eq!(error.context().path.as_deref(), Some(fixture.path()));
+    assert!(error
+        .to_string()
+        .starts_with(&format!("{}: git diff falhou:", fixture.path().display())));
+}
+
+#[test]
+fn test_recent_commits_summary() {
+    let fixture = create_fixture_repo();
+
+    let summary = ingest(fixture.path(), &GitSource::RecentCommits { count: 10 }).unwrap();
+    let lines: Vec<&str> = summary.lines().collect();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "Commits recentes:");
+    assert!(lines[1].ends_with(" Imprime saudação (Ana Teste, 2024-05-01)"));
+    assert!(lines[2].ends_with(" Cria lib.rs (Ana Teste, 2024-05-01)"));
+
+    let latest = ingest(fixture.path(), &GitSource::RecentCommits { count: 1 }).unwrap();
+    assert_eq!(latest.lines().count(), 2);
+}
+
+#[test]
+fn test_changed_files_list() {
+    let fixture = create_fixture_repo();
+    let repo = fixture.path();
+
+    fs::write(repo.join("lib.rs"), "// alterado\n").unwrap();
+    fs::create_dir(repo.join("docs")).unwrap();
+    fs::write(repo.join("docs").join("notas.md"), "# Notas\n").unwrap();
+
+    let files = ingest(repo, &GitSource::ChangedFiles).unwrap();
+
+    assert_eq!(
+        files,
+        "Arquivos alterados:\n- lib.rs (modificado)\n- docs/notas.md (novo (não rastreado))"
+    );
+}
+
+#[test]
+fn test_path_outside_repository_is_an_error() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let result = ingest(temp_dir.path(), &GitSource::StagedDiff);
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,68 @@
+//! Testes de integração para a tradução da interface
+//!
+//! Estes testes conferem que todo texto marcado com `tr(...)` na janela
+//! tem tradução no catálogo e que prompts salvos em outro idioma são
+//! abertos de volta nas mesmas seções.
+
+use prompt_builder_gui::models::{language::Language, section::SectionKind};
+use prompt_builder_gui::services::{
+    i18n::{is_translated, translate},
+    prompt_generator::PromptData,
+    prompt_parser::parse_prompt,
+};
+use std::fs;
+use std::path::Path;
+
+/// Textos passados para `tr("...")` no arquivo da janela
+fn translated_literals(slint: &str) -> Vec<String> {
+    slint
+        .split("tr(\"")
+        .skip(1)
+        .filter_map(|rest| rest.split_once("\")").map(|(text, _)| text.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_every_window_label_is_in_the_catalog() {
+    let slint =
+        fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("ui/app-window.slint"))
+            .expect("Falha ao ler ui/app-window.slint");
+
+    let labels = translated_literals(&slint);
+    assert!(labels.len() > 100);
+    let missing: Vec<&String> = labels
+        .iter()
+        .filter(|label| !is_translated(label))
+        .collect();
+    assert!(missing.is_empty(), "Sem tradução: {:?}", missing);
+    assert_eq!(
+        translate("⚙️ Configurações", Language::Spanish),
+        "⚙️ Configuración"
+    );
+}
+
+#[test]
+fn test_prompt_written_in_english_opens_in_the_same_sections() {
+    let mut original = PromptData::new();
+    original.metadata.title = "Code review".to_string();
+    original.set_section_body(SectionKind::Context, "You are a reviewer.".to_string());
+    original.set_section_body(SectionKind::Limitations, "Be brief.".to_string());
+    original.set_section_body(SectionKind::OutputFormat, "Markdown".to_string());
+
+    let document = original.build_document_in(Language::English);
+    assert!(document.contains("## Limitations\n"));
+
+    let parsed = parse_prompt(&document);
+    assert_eq!(parsed.metadata.title, "Code review");
+    for kind in SectionKind::ALL {
+        assert_eq!(parsed.section_body(kind), original.section_body(kind));
+    }
+
+    // O preview em espanhol também volta para as mesmas seções
+    let preview = original.build_preview_prompt_in(Language::Spanish);
+    assert!(preview.contains("## Formato de Salida"));
+    assert_eq!(
+        parse_prompt(&preview).section_body(SectionKind::Limitations),
+        Some("Be brief.".to_string())
+    );
+}
@@ -3,6 +3,7 @@
 //! Estes testes simulam o processo de abertura e parsing de arquivos
 //! de prompt salvos, testando a integração completa do sistema.
 
+use prompt_builder_gui::models::tests::{TestCase, TestKind};
 use prompt_builder_gui::models::{
     auxiliary_content::AuxiliaryContent, context::Context, few_shot::FewShot, guidance::Guidance,
     limitations::Limitations, main_content::MainContent, output_format::OutputFormat,
@@ -135,9 +136,7 @@ fn save_current_section_simulation(section: &str, content: &str, data: &mut Prom
             data.guidance = Some(guidance);
         }
         "tests" => {
-            let mut tests = Tests::new();
-            tests.text = trimmed_content.to_string();
-            data.tests = Some(tests);
+            data.tests = Some(Tests::parse(trimmed_content));
         }
         "output_format" => {
             let mut output_format = OutputFormat::new();
@@ -545,3 +544,38 @@ Instruções principais"#;
     // Deve usar o último contexto encontrado
     assert_eq!(parsed_duplicate.context.as_ref().unwrap().description, "Segundo contexto");
 }
+
+#[test]
+fn test_parse_structured_tests_section_roundtrip() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+
+    let mut original_data = PromptData::new();
+
+    let mut tests = Tests::new();
+    tests.text = "Use fixtures em arquivos temporários.".to_string();
+    tests.framework = "pytest".to_string();
+    tests.kinds = vec![TestKind::Unit, TestKind::Integration];
+    tests.coverage_target = Some(90);
+    tests.cases = vec![TestCase {
+        name: "Login com senha incorreta".to_string(),
+        given: "um usuário cadastrado".to_string(),
+        when: "ele informa a senha errada".to_string(),
+        then: "o acesso é negado".to_string(),
+    }];
+    original_data.tests = Some(tests);
+
+    let preview = original_data.build_preview_prompt();
+    let file_path = temp_dir.path().join("structured_tests.txt");
+    fs::write(&file_path, &preview).expect("Failed to write file");
+
+    let loaded_content = fs::read_to_string(&file_path).expect("Failed to read file");
+    let parsed_data = parse_prompt_content_simulation(&loaded_content);
+
+    let original_tests = original_data.tests.as_ref().unwrap();
+    let parsed_tests = parsed_data.tests.as_ref().unwrap();
+    assert_eq!(parsed_tests.text, original_tests.text);
+    assert_eq!(parsed_tests.framework, "pytest");
+    assert_eq!(parsed_tests.kinds, original_tests.kinds);
+    assert_eq!(parsed_tests.coverage_target, Some(90));
+    assert_eq!(parsed_tests.cases, original_tests.cases);
+}
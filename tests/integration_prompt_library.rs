@@ -0,0 +1,199 @@
+//! Testes de integração para a biblioteca local de prompts
+//!
+//! Estes testes montam uma pasta de biblioteca em um diretório temporário
+//! e verificam indexação, busca, filtros por tag, favoritos e o treino do
+//! classificador de seções.
+
+use prompt_builder_gui::models::section::SectionKind;
+use prompt_builder_gui::services::prompt_generator::PromptData;
+use prompt_builder_gui::services::prompt_library::{
+    parse_tags, LibraryQuery, PromptLibrary, INDEX_FILE_NAME,
+};
+use prompt_builder_gui::services::prompt_parser::parse_prompt;
+use prompt_builder_gui::services::{
+    heading_aliases::HeadingAliases,
+    section_classifier::{ClassifierStore, SectionClassifier},
+};
+use std::fs;
+use tempfile::TempDir;
+
+/// Cria uma biblioteca com dois prompts salvos e um arquivo antigo sem metadados
+fn create_library() -> (TempDir, PromptLibrary) {
+    let temp_dir = TempDir::new().expect("Falha ao criar diretório temporário");
+    fs::create_dir(temp_dir.path().join("prompts salvos")).unwrap();
+    fs::write(
+        temp_dir.path().join("prompts salvos").join("antigo.txt"),
+        "Explique o código de migração do banco.",
+    )
+    .unwrap();
+
+    let mut library = PromptLibrary::open(temp_dir.path()).unwrap();
+
+    let mut review = PromptData::new();
+    review.metadata.title = "Revisão de API".to_string();
+    review.metadata.tags = parse_tags("rust, api");
+    review.metadata.model = "gpt-4o".to_string();
+    review.set_section_body(
+        SectionKind::Context,
+        "Você é um revisor de APIs.".to_string(),
+    );
+    review.set_section_body(
+        SectionKind::MainContent,
+        "Revise as rotas REST.".to_string(),
+    );
+    library.add_prompt(&review).unwrap();
+
+    let mut summary = PromptData::new();
+    summary.metadata.title = "Resumo".to_string();
+    summary.metadata.tags = parse_tags("texto");
+    summary.metadata.model = "claude".to_string();
+    summary.set_section_body(SectionKind::MainContent, "Resuma a reunião.".to_string());
+    library.add_prompt(&summary).unwrap();
+
+    (temp_dir, library)
+}
+
+#[test]
+fn test_library_indexes_all_prompt_files() {
+    let (temp_dir, library) = create_library();
+
+    let mut files: Vec<&str> = library.entries().iter().map(|e| e.file.as_str()).collect();
+    files.sort();
+    assert_eq!(
+        files,
+        vec![
+            "Resumo.txt",
+            "Revisão de API.txt",
+            "prompts salvos/antigo.txt"
+        ]
+    );
+
+    // Arquivos sem metadados usam o nome como título
+    let old = library.entry("prompts salvos/antigo.txt").unwrap();
+    assert_eq!(old.title, "antigo");
+    assert!(old.tags.is_empty());
+
+    assert!(temp_dir.path().join(INDEX_FILE_NAME).exists());
+}
+
+#[test]
+fn test_full_text_search_over_sections() {
+    let (_temp_dir, library) = create_library();
+
+    // A busca ignora maiúsculas e acentos e exige todos os termos
+    let query = LibraryQuery {
+        text: "REVISOR apis".to_string(),
+        ..LibraryQuery::default()
+    };
+    let results = library.search(&query);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].title, "Revisão de API");
+
+    let query = LibraryQuery {
+        text: "migracao".to_string(),
+        ..LibraryQuery::default()
+    };
+    assert_eq!(library.search(&query)[0].file, "prompts salvos/antigo.txt");
+
+    let query = LibraryQuery {
+        text: "revisor reunião".to_string(),
+        ..LibraryQuery::default()
+    };
+    assert!(library.search(&query).is_empty());
+}
+
+#[test]
+fn test_tag_filter_and_favorites() {
+    let (_temp_dir, mut library) = create_library();
+
+    let query = LibraryQuery {
+        tags: vec!["API".to_string()],
+        ..LibraryQuery::default()
+    };
+    let results = library.search(&query);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].model, "gpt-4o");
+
+    assert_eq!(library.all_tags(), vec!["api", "rust", "texto"]);
+
+    library.set_favorite("Resumo.txt", true).unwrap();
+    let favorites = LibraryQuery {
+        favorites_only: true,
+        ..LibraryQuery::default()
+    };
+    let results = library.search(&favorites);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].file, "Resumo.txt");
+
+    // Favoritos aparecem primeiro na lista completa
+    assert_eq!(
+        library.search(&LibraryQuery::default())[0].file,
+        "Resumo.txt"
+    );
+}
+
+#[test]
+fn test_metadata_survives_reopening() {
+    let (temp_dir, mut library) = create_library();
+    library.set_favorite("Revisão de API.txt", true).unwrap();
+
+    let reopened = PromptLibrary::open(temp_dir.path()).unwrap();
+    let entry = reopened.entry("Revisão de API.txt").unwrap();
+
+    assert!(entry.favorite);
+    assert_eq!(entry.tags, vec!["rust", "api"]);
+    assert!(entry.created > 0);
+    assert!(entry.details().contains("#rust #api · gpt-4o"));
+}
+
+#[test]
+fn test_duplicate_titles_get_distinct_files() {
+    let (_temp_dir, mut library) = create_library();
+
+    let mut other = PromptData::new();
+    other.metadata.title = "Resumo".to_string();
+    other.set_section_body(SectionKind::Context, "outro".to_string());
+    let file = library.add_prompt(&other).unwrap();
+
+    assert_eq!(file, "Resumo (2).txt");
+    assert_eq!(library.entries().len(), 4);
+}
+
+#[test]
+fn test_opened_entry_parses_back_into_sections() {
+    let (_temp_dir, library) = create_library();
+
+    let content = fs::read_to_string(library.path_of("Revisão de API.txt")).unwrap();
+    let data = parse_prompt(&content);
+
+    assert_eq!(data.metadata.title, "Revisão de API");
+    assert_eq!(data.metadata.model, "gpt-4o");
+    assert_eq!(
+        data.section_body(SectionKind::Context).as_deref(),
+        Some("Você é um revisor de APIs.")
+    );
+    assert_eq!(
+        data.section_body(SectionKind::MainContent).as_deref(),
+        Some("Revise as rotas REST.")
+    );
+}
+
+#[test]
+fn test_classifier_trained_on_library_is_saved_and_loaded() {
+    let (temp_dir, library) = create_library();
+    let paragraph = "Revise as rotas REST.";
+
+    let mut classifier = SectionClassifier::bundled();
+    let before = classifier.training_size();
+    // O arquivo antigo não tem títulos e fica fora do treino
+    let used = classifier.train_library(&library, &HeadingAliases::new());
+    assert_eq!(used, 2);
+    assert_eq!(classifier.training_size(), before + 3);
+
+    let store = ClassifierStore::new(&temp_dir.path().join("classificador.txt"));
+    assert_eq!(store.load().unwrap().training_size(), before);
+    store.save(&classifier).unwrap();
+    let loaded = store.load().unwrap();
+    assert_eq!(loaded.training_size(), classifier.training_size());
+    assert_eq!(loaded.classify(paragraph), classifier.classify(paragraph));
+}
@@ -0,0 +1,96 @@
+//! Testes de integração para a mesclagem de três vias
+//!
+//! Estes testes geram três versões de um prompt pelo editor, mesclam as
+//! versões e leem o resultado de volta em seções.
+
+use prompt_builder_gui::models::section::SectionKind;
+use prompt_builder_gui::services::prompt_generator::PromptData;
+use prompt_builder_gui::services::prompt_merge::merge_documents;
+use prompt_builder_gui::services::prompt_parser::parse_prompt;
+
+/// Versão original compartilhada pela equipe
+fn base_prompt() -> PromptData {
+    let mut data = PromptData::new();
+    data.metadata.title = "Revisão de PR".to_string();
+    data.metadata.model = "gpt-4o".to_string();
+    data.set_section_body(SectionKind::Context, "Você é um revisor.".to_string());
+    data.set_section_body(SectionKind::MainContent, "Revise o diff.".to_string());
+    data.set_section_body(SectionKind::Limitations, "Sem jargão.".to_string());
+    data
+}
+
+#[test]
+fn test_independent_edits_merge_into_parseable_document() {
+    let base = base_prompt();
+
+    let mut ours = base.clone();
+    ours.metadata.model = "claude".to_string();
+    ours.set_section_body(
+        SectionKind::Context,
+        "Você é um revisor sênior.".to_string(),
+    );
+
+    let mut theirs = base.clone();
+    theirs.limitations = None;
+    theirs.set_section_body(SectionKind::OutputFormat, "Lista de itens".to_string());
+
+    let result = merge_documents(
+        &base.build_document(),
+        &ours.build_document(),
+        &theirs.build_document(),
+    );
+    assert!(result.is_clean());
+
+    let merged = parse_prompt(&result.to_document());
+    assert_eq!(merged.metadata.title, "Revisão de PR");
+    assert_eq!(merged.metadata.model, "claude");
+    assert_eq!(
+        merged.section_body(SectionKind::Context).as_deref(),
+        Some("Você é um revisor sênior.")
+    );
+    assert!(merged.limitations.is_none());
+    assert_eq!(
+        merged.section_body(SectionKind::OutputFormat).as_deref(),
+        Some("Lista de itens")
+    );
+}
+
+#[test]
+fn test_resolved_conflicts_produce_clean_document() {
+    let base = base_prompt();
+
+    let mut ours = base.clone();
+    ours.set_section_body(SectionKind::MainContent, "Revise só os testes.".to_string());
+    let mut theirs = base.clone();
+    theirs.set_section_body(
+        SectionKind::MainContent,
+        "Revise a documentação.".to_string(),
+    );
+
+    let mut result = merge_documents(
+        &base.build_document(),
+        &ours.build_document(),
+        &theirs.build_document(),
+    );
+    assert_eq!(result.conflict_count(), 1);
+
+    // Os marcadores de conflito ficam dentro da seção em conflito
+    let with_markers = parse_prompt(&result.to_document());
+    assert!(with_markers
+        .section_body(SectionKind::MainContent)
+        .unwrap()
+        .contains("<<<<<<< nossa"));
+
+    let index = result
+        .sections
+        .iter()
+        .position(|section| section.is_conflict())
+        .unwrap();
+    result.resolve(index, "Revise os testes e a documentação.");
+
+    let merged = parse_prompt(&result.to_document());
+    assert_eq!(
+        merged.section_body(SectionKind::MainContent).as_deref(),
+        Some("Revise os testes e a documentação.")
+    );
+}